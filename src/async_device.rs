@@ -0,0 +1,476 @@
+use crate::std;
+use std::{ops::ControlFlow, time::Duration};
+
+use tokio::{
+    io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt},
+    time::Instant,
+};
+
+use crate::{
+    AckNakSequencer, CommandVariant, DeviceEvent, DevicePoller, DocumentStatus, Error,
+    EscrowDecision, EscrowOutcome, EscrowSession, Frame, HostEvent, HostIntent, HostMachine,
+    HostProtocol, MessageOps, ReplyVariant, Result,
+};
+
+/// Async handle for host-to-device communication.
+///
+/// The handle is generic over any [AsyncRead] + [AsyncWrite] implementation, e.g. an async serial
+/// port, or one side of a [tokio::io::duplex] pair for testing.
+///
+/// The handle drives a [HostProtocol], which frames replies, sequences the ACK/NAK toggle, and
+/// re-sends commands on a missing reply, invalid reply, or toggle mismatch, up to the
+/// [AckNakSequencer] retry limit.
+///
+/// The handle can also drive a state machine wrapping the [HostProtocol], e.g. a [DevicePoller],
+/// see [with_machine](Self::with_machine).
+pub struct AsyncDevice<P, M = HostProtocol> {
+    port: P,
+    machine: M,
+}
+
+impl<P: AsyncRead + AsyncWrite + Unpin> AsyncDevice<P> {
+    /// Creates a new [AsyncDevice] over an already opened port.
+    pub fn new(port: P) -> Self {
+        Self::with_machine(port, HostProtocol::new())
+    }
+
+    /// Sends a [CommandVariant] to the device, and awaits an accepted reply.
+    ///
+    /// The command is stamped with the ACK/NAK toggle it was sent with.
+    pub async fn send(&mut self, command: &mut CommandVariant) -> Result<ReplyVariant> {
+        self.machine.handle_intent(HostIntent::Send(*command));
+        self.drive(command.as_message_mut()).await
+    }
+
+    /// Sends a generic message to the device, and awaits an accepted reply.
+    ///
+    /// The message is stamped with the ACK/NAK toggle it was sent with.
+    pub async fn send_message(&mut self, message: &mut dyn MessageOps) -> Result<ReplyVariant> {
+        let command = CommandVariant::try_from(message.as_bytes())?;
+
+        self.machine.handle_intent(HostIntent::Send(command));
+        self.drive(message).await
+    }
+
+    // Drives the protocol until the queued command is accepted, or fails.
+    async fn drive(&mut self, message: &mut dyn MessageOps) -> Result<ReplyVariant> {
+        let clock = Clock::new(&self.machine);
+
+        loop {
+            while let Some(event) = self.machine.poll_event() {
+                match event {
+                    HostEvent::Reply(reply) => return Ok(reply),
+                    HostEvent::CommandFailed(_, err) => return Err(err),
+                    HostEvent::BytesDiscarded(count) => {
+                        log::debug!("Discarded {count} bytes of invalid input")
+                    }
+                }
+            }
+
+            if !self.machine.is_busy() {
+                return Err(Error::failure("no command waiting for a reply"));
+            }
+
+            if let Some(frame) = self.step(&clock).await? {
+                message.buf_mut().copy_from_slice(frame.buf());
+            }
+        }
+    }
+}
+
+impl<P: AsyncRead + AsyncWrite + Unpin, M: HostMachine> AsyncDevice<P, M> {
+    /// Creates a new [AsyncDevice] driving a [HostMachine], e.g. a [DevicePoller], over an already
+    /// opened port.
+    pub fn with_machine(port: P, machine: M) -> Self {
+        Self { port, machine }
+    }
+
+    /// Gets the reply timeout.
+    pub fn reply_timeout(&self) -> Duration {
+        Duration::from_millis(self.protocol().reply_timeout_ms())
+    }
+
+    /// Sets the reply timeout.
+    pub fn set_reply_timeout(&mut self, reply_timeout: Duration) {
+        self.protocol_mut()
+            .set_reply_timeout_ms(reply_timeout.as_millis() as u64);
+    }
+
+    /// Builder function to set the reply timeout.
+    pub fn with_reply_timeout(mut self, reply_timeout: Duration) -> Self {
+        self.set_reply_timeout(reply_timeout);
+        self
+    }
+
+    /// Builder function to set the maximum number of times a command is re-sent.
+    pub fn with_max_retries(mut self, max_retries: u8) -> Self {
+        self.sequencer_mut().set_max_retries(max_retries);
+        self
+    }
+
+    /// Gets a reference to the [HostMachine].
+    pub fn machine(&self) -> &M {
+        &self.machine
+    }
+
+    /// Gets a mutable reference to the [HostMachine].
+    pub fn machine_mut(&mut self) -> &mut M {
+        &mut self.machine
+    }
+
+    /// Gets a reference to the [HostProtocol].
+    pub fn protocol(&self) -> &HostProtocol {
+        self.machine.protocol()
+    }
+
+    /// Gets a mutable reference to the [HostProtocol].
+    pub fn protocol_mut(&mut self) -> &mut HostProtocol {
+        self.machine.protocol_mut()
+    }
+
+    /// Gets a reference to the [AckNakSequencer].
+    pub fn sequencer(&self) -> &AckNakSequencer {
+        self.protocol().sequencer()
+    }
+
+    /// Gets a mutable reference to the [AckNakSequencer].
+    pub fn sequencer_mut(&mut self) -> &mut AckNakSequencer {
+        self.protocol_mut().sequencer_mut()
+    }
+
+    /// Gets a reference to the underlying port.
+    pub fn port(&self) -> &P {
+        &self.port
+    }
+
+    /// Gets a mutable reference to the underlying port.
+    pub fn port_mut(&mut self) -> &mut P {
+        &mut self.port
+    }
+
+    /// Consumes the [AsyncDevice], returning the underlying port.
+    pub fn into_inner(self) -> P {
+        self.port
+    }
+
+    /// Writes a message to the device.
+    ///
+    /// Calculates the message checksum before writing.
+    pub async fn write_message(&mut self, message: &mut dyn MessageOps) -> Result<()> {
+        let bytes = message.as_bytes();
+
+        log::trace!("Sending message: {bytes:x?}");
+
+        self.port.write_all(bytes).await?;
+        self.port.flush().await?;
+
+        Ok(())
+    }
+
+    /// Drives the [HostMachine] until `on_step` breaks, returning the break value.
+    ///
+    /// `on_step` is called before each write, and read, e.g. to drain the machine's events. An
+    /// error from `on_step` ends the loop.
+    ///
+    /// Returns an error if the machine stalls, with nothing to send, and nothing to wait for.
+    pub async fn run<T, F>(&mut self, mut on_step: F) -> Result<T>
+    where
+        F: FnMut(&mut M) -> Result<ControlFlow<T>>,
+    {
+        let clock = Clock::new(self.machine.protocol());
+
+        loop {
+            if let ControlFlow::Break(value) = on_step(&mut self.machine)? {
+                return Ok(value);
+            }
+
+            if self.machine.poll_timeout().is_none() {
+                return Err(Error::failure("machine stalled, nothing to send"));
+            }
+
+            self.step(&clock).await?;
+        }
+    }
+
+    // Writes the pending message, awaits input until the next deadline, and advances the protocol
+    // clock.
+    //
+    // Returns the written message, if any.
+    async fn step(&mut self, clock: &Clock) -> Result<Option<Frame>> {
+        let mut transmit = self.machine.poll_transmit();
+        if let Some(frame) = transmit.as_mut() {
+            self.write_message(frame).await?;
+        }
+
+        let wait = Duration::from_millis(self.machine.poll_timeout().unwrap_or_default());
+        let mut chunk = [0u8; 64];
+
+        // an expired wait is not an error, the protocol handles the deadline
+        if let Ok(read) = tokio::time::timeout(wait, self.port.read(chunk.as_mut())).await {
+            match read? {
+                0 => return Err(Error::serial("device connection closed")),
+                read => self.machine.handle_input(&chunk[..read]),
+            }
+        }
+
+        let elapsed_ms = clock
+            .now_ms()
+            .saturating_sub(self.machine.protocol().now_ms());
+        self.machine.handle_elapsed(elapsed_ms);
+
+        Ok(transmit)
+    }
+}
+
+impl<P: AsyncRead + AsyncWrite + Unpin> AsyncDevice<P, DevicePoller> {
+    /// Runs the Omnibus poll loop of the [DevicePoller], passing each [DeviceEvent] to the callback,
+    /// until the callback breaks.
+    ///
+    /// Polling is started, if the poller is stopped. Commands failing after all retries are
+    /// logged, and polling continues.
+    pub async fn poll<F>(&mut self, mut on_event: F) -> Result<()>
+    where
+        F: FnMut(&DeviceEvent) -> ControlFlow<()>,
+    {
+        if !self.machine.protocol().polling() {
+            self.machine.start();
+        }
+
+        self.run(|poller| {
+            while let Some(event) = poller.poll_host_event() {
+                log_host_event(&event);
+            }
+
+            while let Some(event) = poller.poll_event() {
+                if on_event(&event).is_break() {
+                    return Ok(ControlFlow::Break(()));
+                }
+            }
+
+            Ok(ControlFlow::Continue(()))
+        })
+        .await
+    }
+}
+
+impl<P, F> AsyncDevice<P, EscrowSession<F>>
+where
+    P: AsyncRead + AsyncWrite + Unpin,
+    F: FnMut(&DocumentStatus) -> Option<EscrowDecision>,
+{
+    /// Runs the [EscrowSession], passing each [EscrowOutcome] to the callback, until the callback
+    /// breaks.
+    ///
+    /// The session is started, if the poller is stopped. Commands failing after all retries are
+    /// logged, and polling continues. Deferred decisions can be made with
+    /// [EscrowSession::decide] through [run](Self::run).
+    pub async fn run_escrow<G>(&mut self, mut on_outcome: G) -> Result<()>
+    where
+        G: FnMut(&EscrowOutcome) -> ControlFlow<()>,
+    {
+        if !self.machine.poller().protocol().polling() {
+            self.machine.start();
+        }
+
+        self.run(|session| {
+            while let Some(event) = session.poll_host_event() {
+                log_host_event(&event);
+            }
+
+            while let Some(event) = session.poll_event() {
+                log::trace!("Device event: {event}");
+            }
+
+            while let Some(outcome) = session.poll_outcome() {
+                if on_outcome(&outcome).is_break() {
+                    return Ok(ControlFlow::Break(()));
+                }
+            }
+
+            Ok(ControlFlow::Continue(()))
+        })
+        .await
+    }
+}
+
+// Logs the protocol events not surfaced by the poll loops.
+fn log_host_event(event: &HostEvent) {
+    match event {
+        HostEvent::CommandFailed(command, err) => {
+            log::warn!("Command failed: {command}, error: {err}")
+        }
+        HostEvent::BytesDiscarded(count) => {
+            log::debug!("Discarded {count} bytes of invalid input")
+        }
+        HostEvent::Reply(_) => (),
+    }
+}
+
+// Keeps the protocol time in step with the wall clock.
+struct Clock {
+    epoch: Instant,
+    start_ms: u64,
+}
+
+impl Clock {
+    fn new(protocol: &HostProtocol) -> Self {
+        Self {
+            epoch: Instant::now(),
+            start_ms: protocol.now_ms(),
+        }
+    }
+
+    fn now_ms(&self) -> u64 {
+        self.start_ms
+            .saturating_add(self.epoch.elapsed().as_millis() as u64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{index, len, AckNak, ErrorCode, OmnibusCommand, QueryBootPartNumberCommand};
+
+    #[rustfmt::skip]
+    const OMNIBUS_REPLY: [u8; 11] = [
+        // STX | LEN | Message type
+        0x02, 0x0b, 0x20,
+        // Data
+        0b0000_0001, 0b0001_0000, 0b0000_0000, 0b0000_0000, 0b0000_0000, 0b0000_0000,
+        // ETX | Checksum
+        0x03, 0x3a,
+    ];
+
+    #[tokio::test]
+    async fn test_async_send() -> Result<()> {
+        let (host, mut acceptor) = tokio::io::duplex(len::MAX_MESSAGE);
+
+        let device_task = tokio::spawn(async move {
+            let mut command = [0u8; len::OMNIBUS_COMMAND];
+            acceptor.read_exact(command.as_mut()).await.unwrap();
+
+            // fragmented reply, with line noise
+            acceptor.write_all([0xff].as_ref()).await.unwrap();
+            acceptor
+                .write_all(OMNIBUS_REPLY[..4].as_ref())
+                .await
+                .unwrap();
+            acceptor
+                .write_all(OMNIBUS_REPLY[4..].as_ref())
+                .await
+                .unwrap();
+
+            command
+        });
+
+        let mut device = AsyncDevice::new(host);
+        let mut command = CommandVariant::OmnibusCommand(OmnibusCommand::new());
+
+        let reply = device.send(&mut command).await?;
+        let sent = device_task.await.unwrap();
+
+        assert_eq!(sent.as_ref(), command.as_message().as_bytes_unchecked());
+        assert!(matches!(reply, ReplyVariant::OmnibusReply(_)));
+        assert_eq!(device.sequencer().acknak(), AckNak::Nak);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_async_send_aux() -> Result<()> {
+        let (host, mut acceptor) = tokio::io::duplex(len::MAX_MESSAGE);
+
+        let device_task = tokio::spawn(async move {
+            let mut command = [0u8; len::AUX_COMMAND];
+            acceptor.read_exact(command.as_mut()).await.unwrap();
+
+            #[rustfmt::skip]
+            let reply = [
+                // STX | LEN | Message type
+                0x02, 0x0e, 0x60,
+                // Project number (in ASCII)
+                b'2', b'8', b'6', b'0', b'0', b'0',
+                // Version (in ASCII)
+                b'1', b'2', b'3',
+                // ETX | Checksum
+                0x03, 0x52,
+            ];
+            acceptor.write_all(reply.as_ref()).await.unwrap();
+        });
+
+        let mut device = AsyncDevice::new(host);
+        let mut command =
+            CommandVariant::QueryBootPartNumberCommand(QueryBootPartNumberCommand::new());
+
+        let reply = device.send(&mut command).await?;
+        device_task.await.unwrap();
+
+        assert!(matches!(reply, ReplyVariant::QueryBootPartNumberReply(_)));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_async_poll() -> Result<()> {
+        let (host, mut acceptor) = tokio::io::duplex(len::MAX_MESSAGE);
+
+        let device_task = tokio::spawn(async move {
+            let mut polls = [[0u8; len::OMNIBUS_COMMAND]; 2];
+
+            acceptor.read_exact(polls[0].as_mut()).await.unwrap();
+            acceptor.write_all(OMNIBUS_REPLY.as_ref()).await.unwrap();
+
+            #[rustfmt::skip]
+            let stacked = [
+                // STX | LEN | Message type (ACK 1)
+                0x02, 0x0b, 0x21,
+                // Data (idling, stacked)
+                0b0001_0001, 0b0001_0000, 0b0000_0000, 0b0000_0000, 0b0000_0000, 0b0000_0000,
+                // ETX | Checksum
+                0x03, 0x2b,
+            ];
+            acceptor.read_exact(polls[1].as_mut()).await.unwrap();
+            acceptor.write_all(stacked.as_ref()).await.unwrap();
+
+            polls
+        });
+
+        let poller = DevicePoller::new().with_poll_interval_ms(0);
+        let mut device = AsyncDevice::with_machine(host, poller);
+
+        device
+            .poll(|event| match event {
+                DeviceEvent::Stacked(_) => ControlFlow::Break(()),
+                _ => ControlFlow::Continue(()),
+            })
+            .await?;
+
+        let polls = device_task.await.unwrap();
+        let acknaks: Vec<AckNak> = polls
+            .iter()
+            .map(|poll| AckNak::from(poll[index::CONTROL]))
+            .collect();
+
+        assert_eq!(acknaks, [AckNak::Ack, AckNak::Nak]);
+        assert_eq!(device.sequencer().acknak(), AckNak::Ack);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_async_send_timeout() {
+        let (host, mut acceptor) = tokio::io::duplex(len::MAX_MESSAGE);
+
+        let mut device = AsyncDevice::new(host)
+            .with_reply_timeout(Duration::from_millis(10))
+            .with_max_retries(1);
+        let mut command = CommandVariant::OmnibusCommand(OmnibusCommand::new());
+
+        let err = device.send(&mut command).await.unwrap_err();
+        assert_eq!(err.code(), ErrorCode::Timeout);
+
+        // the command is sent once, and re-sent once
+        let mut sent = [0u8; len::OMNIBUS_COMMAND * 2];
+        acceptor.read_exact(sent.as_mut()).await.unwrap();
+    }
+}
@@ -6,16 +6,24 @@ use crate::MessageOps;
 /// Auxilliary Commmands (Type 6): The Auxiliary Commands are used to provide functionality outside the scope of the Omnibus command
 /// in the previous sections. These commands can be specific to a certain code base, so be sure to check the
 /// compatibility icons before each section.
-
+///
 /// Developers: add additional types from the specification as needed
 #[repr(u8)]
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum AuxCommand {
     QuerySoftwareCrc = 0x00,
+    QueryCashBoxTotal = 0x01,
+    QueryDeviceResets = 0x02,
+    ClearCashBoxTotal = 0x03,
+    QueryAcceptorType = 0x04,
+    QueryAcceptorSerialNumber = 0x05,
     QueryBootPartNumber = 0x06,
     QueryApplicationPartNumber = 0x07,
     QueryVariantName = 0x08,
     QueryVariantPartNumber = 0x09,
+    QueryAuditLifeTimeTotals = 0x0a,
+    QueryAuditQpMeasures = 0x0b,
+    QueryAuditPerformanceMeasures = 0x0c,
     QueryDeviceCapabilities = 0x0d,
     QueryApplicationId = 0x0e,
     QueryVariantId = 0x0f,
@@ -27,10 +35,18 @@ impl From<u8> for AuxCommand {
     fn from(b: u8) -> Self {
         match b {
             0x00 => Self::QuerySoftwareCrc,
+            0x01 => Self::QueryCashBoxTotal,
+            0x02 => Self::QueryDeviceResets,
+            0x03 => Self::ClearCashBoxTotal,
+            0x04 => Self::QueryAcceptorType,
+            0x05 => Self::QueryAcceptorSerialNumber,
             0x06 => Self::QueryBootPartNumber,
             0x07 => Self::QueryApplicationPartNumber,
             0x08 => Self::QueryVariantName,
             0x09 => Self::QueryVariantPartNumber,
+            0x0a => Self::QueryAuditLifeTimeTotals,
+            0x0b => Self::QueryAuditQpMeasures,
+            0x0c => Self::QueryAuditPerformanceMeasures,
             0x0d => Self::QueryDeviceCapabilities,
             0x0e => Self::QueryApplicationId,
             0x0f => Self::QueryVariantId,
@@ -44,10 +60,18 @@ impl From<AuxCommand> for &'static str {
     fn from(a: AuxCommand) -> Self {
         match a {
             AuxCommand::QuerySoftwareCrc => "QuerySoftwareCrc",
+            AuxCommand::QueryCashBoxTotal => "QueryCashBoxTotal",
+            AuxCommand::QueryDeviceResets => "QueryDeviceResets",
+            AuxCommand::ClearCashBoxTotal => "ClearCashBoxTotal",
+            AuxCommand::QueryAcceptorType => "QueryAcceptorType",
+            AuxCommand::QueryAcceptorSerialNumber => "QueryAcceptorSerialNumber",
             AuxCommand::QueryBootPartNumber => "QueryBootPartNumber",
             AuxCommand::QueryApplicationPartNumber => "QueryApplicationPartNumber",
             AuxCommand::QueryVariantName => "QueryVariantName",
             AuxCommand::QueryVariantPartNumber => "QueryVariantPartNumber",
+            AuxCommand::QueryAuditLifeTimeTotals => "QueryAuditLifeTimeTotals",
+            AuxCommand::QueryAuditQpMeasures => "QueryAuditQpMeasures",
+            AuxCommand::QueryAuditPerformanceMeasures => "QueryAuditPerformanceMeasures",
             AuxCommand::QueryDeviceCapabilities => "QueryDeviceCapabilities",
             AuxCommand::QueryApplicationId => "QueryApplicationId",
             AuxCommand::QueryVariantId => "QueryVariantId",
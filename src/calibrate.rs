@@ -0,0 +1,7 @@
+pub(crate) mod command;
+pub(crate) mod reply;
+mod session;
+
+pub use command::*;
+pub use reply::*;
+pub use session::*;
@@ -0,0 +1,83 @@
+use crate::std;
+use std::fmt;
+
+use crate::{impl_default, impl_message_ops, len::CALIBRATE_COMMAND, MessageOps, MessageType};
+
+/// Calibrate - Command (Type 4)
+///
+/// | **S2K** | **CFSC** | **SC Adv** | **SCR** |
+/// |:-------:|:--------:|:----------:|:-------:|
+///
+/// The Calibrate Command puts the device in field calibration mode. While in calibration mode, the
+/// host continues to poll the device with Calibrate Commands, and the device waits for a special
+/// calibration document to be fed.
+///
+/// In general, due to advances in processes used in manufacturing and continuous self-calibration,
+/// field calibration is not needed. Calibrating a device with an incorrect document will greatly
+/// reduce performance.
+///
+/// The Calibrate Command is formatted as follows:
+///
+/// | Name  | STX  | LEN  | CTRL | Data 0 | Data 1 | Data 2 | ETX  | CHK |
+/// |:------|:----:|:----:|:----:|:------:|:------:|:------:|:----:|:---:|
+/// | Byte  | 0    | 1    | 2    | 3      | 4      | 5      | 6    | 7   |
+/// | Value | 0x02 | 0x08 | 0x4n | 0x00   | 0x00   | 0x00   | 0x03 | zz  |
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CalibrateCommand {
+    buf: [u8; CALIBRATE_COMMAND],
+}
+
+impl CalibrateCommand {
+    /// Creates a new [CalibrateCommand].
+    pub fn new() -> Self {
+        let mut message = Self {
+            buf: [0u8; CALIBRATE_COMMAND],
+        };
+
+        message.init();
+        message.set_message_type(MessageType::Calibrate);
+
+        message
+    }
+}
+
+impl_default!(CalibrateCommand);
+impl_message_ops!(CalibrateCommand);
+
+impl fmt::Display for CalibrateCommand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{{")?;
+        write!(f, r#""acknak": {}, "#, self.acknak())?;
+        write!(f, r#""device_type": {}, "#, self.device_type())?;
+        write!(f, r#""message_type": {}"#, self.message_type())?;
+        write!(f, "}}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Result;
+
+    #[test]
+    #[rustfmt::skip]
+    fn test_calibrate_command_from_buf() -> Result<()> {
+        let msg_bytes = [
+            // STX | LEN | Message Type
+            0x02, 0x08, 0x40,
+            // Data
+            0x00, 0x00, 0x00,
+            // ETX | Checksum
+            0x03, 0x48,
+        ];
+
+        let mut msg = CalibrateCommand::new();
+        msg.from_buf(msg_bytes.as_ref())?;
+
+        assert_eq!(msg.message_type(), MessageType::Calibrate);
+        assert_eq!(CalibrateCommand::new().as_bytes(), msg_bytes.as_ref());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,94 @@
+use crate::std;
+use std::fmt;
+
+use crate::{
+    impl_default, impl_message_ops, impl_omnibus_reply_ops, len::CALIBRATE_REPLY, MessageOps,
+    MessageType, OmnibusReplyOps,
+};
+
+/// Calibrate - Reply (Type 4)
+///
+/// | **S2K** | **CFSC** | **SC Adv** | **SCR** |
+/// |:-------:|:--------:|:----------:|:-------:|
+///
+/// The device replies to a [CalibrateCommand](crate::CalibrateCommand) with the standard Omnibus
+/// status bytes. The [Calibration](crate::Calibration) bit of the
+/// [DeviceStatus](crate::DeviceStatus) is set while the device is in calibration mode.
+///
+/// The Calibrate Reply is formatted as follows:
+///
+/// | Name  | STX  | LEN  | CTRL | Data 0 | Data 1 | Data 2 | Data 3 | Data 4 | Data 5 | ETX  | CHK |
+/// |:------|:----:|:----:|:----:|:------:|:------:|:------:|:------:|:------:|:------:|:----:|:---:|
+/// | Byte  | 0    | 1    | 2    | 3      | 4      | 5      | 6      | 7      | 8      | 9    | 10  |
+/// | Value | 0x02 | 0x0B | 0x4n | nn     | nn     | nn     | nn     | nn     | nn     | 0x03 | zz  |
+///
+/// The data bytes are the same as the [OmnibusReply](crate::OmnibusReply) data bytes.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CalibrateReply {
+    buf: [u8; CALIBRATE_REPLY],
+}
+
+impl CalibrateReply {
+    /// Creates a new [CalibrateReply].
+    pub fn new() -> Self {
+        let mut message = Self {
+            buf: [0u8; CALIBRATE_REPLY],
+        };
+
+        message.init();
+        message.set_message_type(MessageType::Calibrate);
+
+        message
+    }
+}
+
+impl_default!(CalibrateReply);
+impl_message_ops!(CalibrateReply);
+impl_omnibus_reply_ops!(CalibrateReply);
+
+impl fmt::Display for CalibrateReply {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "AckNak: {}, DeviceType: {}, MessageType: {}, DeviceState: {}, DeviceStatus: {}, ExceptionStatus: {}, MiscDeviceState: {}, ModelNumber: {}, CodeRevision: {}",
+            self.acknak(),
+            self.device_type(),
+            self.message_type(),
+            self.device_state(),
+            self.device_status(),
+            self.exception_status(),
+            self.misc_device_state(),
+            self.model_number(),
+            self.code_revision(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Calibration, Result};
+
+    #[test]
+    #[rustfmt::skip]
+    fn test_calibrate_reply_from_buf() -> Result<()> {
+        let msg_bytes = [
+            // STX | LEN | Message Type
+            0x02, 0x0b, 0x40,
+            // Data
+            0x00, 0b0101_0000, 0x00, 0x00, 0x00, 0x00,
+            // ETX | Checksum
+            0x03, 0x1b,
+        ];
+
+        let mut msg = CalibrateReply::new();
+        msg.from_buf(msg_bytes.as_ref())?;
+
+        assert_eq!(msg.message_type(), MessageType::Calibrate);
+        assert_eq!(msg.calibration(), Calibration::Set);
+        assert!(msg.device_status().cassette_attached());
+
+        Ok(())
+    }
+}
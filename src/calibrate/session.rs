@@ -0,0 +1,447 @@
+use alloc::collections::VecDeque;
+
+use crate::std;
+use std::fmt;
+
+use crate::{
+    CalibrateCommand, CommandVariant, HostEvent, HostIntent, HostSession, OmnibusReplyOps,
+    DEFAULT_POLL_INTERVAL_MS,
+};
+
+/// Default number of polls for the device to report calibration mode, before giving up.
+pub const DEFAULT_MAX_ENTRY_POLLS: u8 = 10;
+
+/// Progress of a [CalibrationSession].
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CalibrationState {
+    /// Sending [CalibrateCommand]s, until the device reports calibration mode.
+    Entering,
+    /// The device is in calibration mode, waiting for the calibration document.
+    AwaitingDocument,
+    /// The device is processing the calibration document.
+    Calibrating,
+    /// The device calibrated successfully, and left calibration mode.
+    Succeeded,
+    /// Calibration failed, see [CalibrationFailure].
+    Failed,
+}
+
+impl CalibrationState {
+    /// Gets whether the state is final.
+    pub fn is_finished(&self) -> bool {
+        matches!(self, Self::Succeeded | Self::Failed)
+    }
+}
+
+impl From<CalibrationState> for &'static str {
+    fn from(s: CalibrationState) -> Self {
+        match s {
+            CalibrationState::Entering => "Entering",
+            CalibrationState::AwaitingDocument => "AwaitingDocument",
+            CalibrationState::Calibrating => "Calibrating",
+            CalibrationState::Succeeded => "Succeeded",
+            CalibrationState::Failed => "Failed",
+        }
+    }
+}
+
+impl From<&CalibrationState> for &'static str {
+    fn from(s: &CalibrationState) -> Self {
+        (*s).into()
+    }
+}
+
+impl fmt::Display for CalibrationState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", <&'static str>::from(self))
+    }
+}
+
+/// Reason a [CalibrationSession] failed.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CalibrationFailure {
+    /// The device did not report calibration mode.
+    NotEntered,
+    /// The device left calibration mode without reporting the calibration document, the document
+    /// may have been processed between polls. Check the device calibration before retrying.
+    UnknownOutcome,
+    /// The calibration document was rejected.
+    Rejected,
+    /// The calibration document jammed in the device.
+    Jammed,
+    /// The device reported a failure.
+    DeviceFailure,
+    /// The device stopped replying, or its replies were rejected, until the retries ran out.
+    Timeout,
+}
+
+impl From<CalibrationFailure> for &'static str {
+    fn from(f: CalibrationFailure) -> Self {
+        match f {
+            CalibrationFailure::NotEntered => "NotEntered",
+            CalibrationFailure::UnknownOutcome => "UnknownOutcome",
+            CalibrationFailure::Rejected => "Rejected",
+            CalibrationFailure::Jammed => "Jammed",
+            CalibrationFailure::DeviceFailure => "DeviceFailure",
+            CalibrationFailure::Timeout => "Timeout",
+        }
+    }
+}
+
+impl From<&CalibrationFailure> for &'static str {
+    fn from(f: &CalibrationFailure) -> Self {
+        (*f).into()
+    }
+}
+
+impl fmt::Display for CalibrationFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", <&'static str>::from(self))
+    }
+}
+
+/// Events emitted by a [CalibrationSession] for the host application.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CalibrationEvent {
+    /// The device entered calibration mode, prompt the operator to feed the calibration document.
+    InsertDocument,
+    /// The device is processing the calibration document.
+    Calibrating,
+    /// The device calibrated successfully.
+    Succeeded,
+    /// Calibration failed.
+    Failed(CalibrationFailure),
+}
+
+impl fmt::Display for CalibrationEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InsertDocument => write!(f, "InsertDocument"),
+            Self::Calibrating => write!(f, "Calibrating"),
+            Self::Succeeded => write!(f, "Succeeded"),
+            Self::Failed(failure) => write!(f, "Failed({failure})"),
+        }
+    }
+}
+
+/// Sans-IO guided field calibration workflow.
+///
+/// The session sends a [CalibrateCommand] every poll interval, and tracks the device status in the
+/// replies:
+///
+/// 1. the device sets the [Calibration](crate::Calibration) status bit, and the session emits
+///    [CalibrationEvent::InsertDocument] to prompt for the calibration document,
+/// 2. the device reports accepting the document, and the session emits
+///    [CalibrationEvent::Calibrating],
+/// 3. the device clears the calibration bit, and the session emits [CalibrationEvent::Succeeded].
+///
+/// A rejected or jammed document, a device failure, or the device not entering calibration mode
+/// within [max_entry_polls](Self::max_entry_polls) ends the session with
+/// [CalibrationEvent::Failed]. If the device leaves calibration mode without reporting the
+/// document, the session fails with [CalibrationFailure::UnknownOutcome].
+///
+/// The session is a [HostSession]: each command is sent as a [HostIntent::Send], and the driving
+/// [HostProtocol](crate::HostProtocol) stamps the ACK/NAK toggle, and re-sends the command on a
+/// missing, invalid, or duplicate reply. Once the protocol gives up on a command, the session
+/// fails with [CalibrationFailure::Timeout].
+#[derive(Clone, Debug, PartialEq)]
+pub struct CalibrationSession {
+    state: CalibrationState,
+    failure: Option<CalibrationFailure>,
+    entry_polls: u8,
+    max_entry_polls: u8,
+    poll_interval_ms: u64,
+    next_poll: u64,
+    in_flight: bool,
+    events: VecDeque<CalibrationEvent>,
+}
+
+impl CalibrationSession {
+    /// Creates a new [CalibrationSession].
+    pub fn new() -> Self {
+        Self {
+            state: CalibrationState::Entering,
+            failure: None,
+            entry_polls: 0,
+            max_entry_polls: DEFAULT_MAX_ENTRY_POLLS,
+            poll_interval_ms: DEFAULT_POLL_INTERVAL_MS,
+            next_poll: 0,
+            in_flight: false,
+            events: VecDeque::new(),
+        }
+    }
+
+    /// Gets the maximum number of polls for the device to report calibration mode.
+    pub fn max_entry_polls(&self) -> u8 {
+        self.max_entry_polls
+    }
+
+    /// Sets the maximum number of polls for the device to report calibration mode.
+    pub fn set_max_entry_polls(&mut self, polls: u8) {
+        self.max_entry_polls = polls.max(1);
+    }
+
+    /// Builder function to set the maximum number of polls for the device to report calibration
+    /// mode.
+    pub fn with_max_entry_polls(mut self, polls: u8) -> Self {
+        self.set_max_entry_polls(polls);
+        self
+    }
+
+    /// Gets the interval between [CalibrateCommand]s (milliseconds).
+    pub fn poll_interval_ms(&self) -> u64 {
+        self.poll_interval_ms
+    }
+
+    /// Sets the interval between [CalibrateCommand]s (milliseconds).
+    pub fn set_poll_interval_ms(&mut self, poll_interval_ms: u64) {
+        self.poll_interval_ms = poll_interval_ms;
+    }
+
+    /// Builder function to set the interval between [CalibrateCommand]s (milliseconds).
+    pub fn with_poll_interval_ms(mut self, poll_interval_ms: u64) -> Self {
+        self.set_poll_interval_ms(poll_interval_ms);
+        self
+    }
+
+    /// Gets the current [CalibrationState].
+    pub fn state(&self) -> CalibrationState {
+        self.state
+    }
+
+    /// Gets the [CalibrationFailure], if calibration failed.
+    pub fn failure(&self) -> Option<CalibrationFailure> {
+        self.failure
+    }
+
+    /// Gets whether the session has finished, successfully or not.
+    pub fn is_finished(&self) -> bool {
+        self.state.is_finished()
+    }
+
+    /// Gets the next [CalibrationEvent], if any.
+    pub fn poll_event(&mut self) -> Option<CalibrationEvent> {
+        self.events.pop_front()
+    }
+
+    fn handle_status(&mut self, reply: &dyn OmnibusReplyOps) {
+        let device_state = reply.device_state();
+        let device_status = reply.device_status();
+
+        if reply.exception_status().failure() {
+            self.fail(CalibrationFailure::DeviceFailure);
+            return;
+        }
+
+        match self.state {
+            CalibrationState::Entering => {
+                if device_status.calibration() {
+                    log::info!("Device in calibration mode, waiting for the calibration document");
+                    self.state = CalibrationState::AwaitingDocument;
+                    self.events.push_back(CalibrationEvent::InsertDocument);
+                } else {
+                    self.entry_polls = self.entry_polls.saturating_add(1);
+                    if self.entry_polls >= self.max_entry_polls {
+                        self.fail(CalibrationFailure::NotEntered);
+                    }
+                }
+            }
+            CalibrationState::AwaitingDocument | CalibrationState::Calibrating => {
+                if device_status.jammed() {
+                    self.fail(CalibrationFailure::Jammed);
+                } else if device_status.rejected() {
+                    self.fail(CalibrationFailure::Rejected);
+                } else if !device_status.calibration() {
+                    if self.state == CalibrationState::Calibrating {
+                        log::info!("Calibration succeeded");
+                        self.state = CalibrationState::Succeeded;
+                        self.events.push_back(CalibrationEvent::Succeeded);
+                    } else {
+                        // accepting was never seen, the result of the calibration is unknown
+                        self.fail(CalibrationFailure::UnknownOutcome);
+                    }
+                } else if self.state == CalibrationState::AwaitingDocument
+                    && device_state.accepting()
+                {
+                    self.state = CalibrationState::Calibrating;
+                    self.events.push_back(CalibrationEvent::Calibrating);
+                }
+            }
+            CalibrationState::Succeeded | CalibrationState::Failed => (),
+        }
+    }
+
+    fn fail(&mut self, failure: CalibrationFailure) {
+        log::error!("Calibration failed in the {} state: {failure}", self.state);
+        self.state = CalibrationState::Failed;
+        self.failure = Some(failure);
+        self.events.push_back(CalibrationEvent::Failed(failure));
+    }
+}
+
+impl HostSession for CalibrationSession {
+    fn poll_intent(&mut self, now_ms: u64) -> Option<HostIntent> {
+        if self.in_flight || self.is_finished() || now_ms < self.next_poll {
+            return None;
+        }
+
+        self.in_flight = true;
+        self.next_poll = now_ms.saturating_add(self.poll_interval_ms);
+
+        Some(HostIntent::Send(CommandVariant::CalibrateCommand(
+            CalibrateCommand::new(),
+        )))
+    }
+
+    fn poll_timeout(&self, now_ms: u64) -> Option<u64> {
+        if self.in_flight || self.is_finished() {
+            None
+        } else {
+            Some(self.next_poll.saturating_sub(now_ms))
+        }
+    }
+
+    fn handle_event(&mut self, event: &HostEvent) {
+        match event {
+            HostEvent::Reply(reply) if self.in_flight => {
+                self.in_flight = false;
+                self.handle_status(reply.as_omnibus_reply());
+            }
+            HostEvent::CommandFailed(_, err) if self.in_flight => {
+                self.in_flight = false;
+                log::warn!("Calibrate command failed: {err}");
+                self.fail(CalibrationFailure::Timeout);
+            }
+            _ => (),
+        }
+    }
+
+    fn is_finished(&self) -> bool {
+        CalibrationSession::is_finished(self)
+    }
+}
+
+impl Default for CalibrationSession {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        Accepting, CalibrateReply, Calibration, Error, HostProtocol, MessageOps, Rejected,
+        ReplyVariant,
+    };
+
+    fn send(session: &mut CalibrationSession) -> CommandVariant {
+        match session.poll_intent(session.next_poll) {
+            Some(HostIntent::Send(command)) => command,
+            intent => panic!("expected a command, have: {intent:?}"),
+        }
+    }
+
+    fn reply(session: &mut CalibrationSession, f: impl FnOnce(&mut CalibrateReply)) {
+        assert!(send(session).is_calibrate_command());
+
+        let mut msg = CalibrateReply::new();
+        f(&mut msg);
+
+        session.handle_event(&HostEvent::Reply(ReplyVariant::CalibrateReply(msg)));
+    }
+
+    #[test]
+    fn test_calibration_session() {
+        let mut session = CalibrationSession::new().with_poll_interval_ms(100);
+
+        reply(&mut session, |_| ());
+        assert_eq!(session.state(), CalibrationState::Entering);
+        assert_eq!(session.poll_event(), None);
+
+        // the next command waits for the poll interval
+        assert_eq!(session.poll_intent(50), None);
+        assert_eq!(session.poll_timeout(50), Some(50));
+
+        reply(&mut session, |r| r.set_calibration(Calibration::Set));
+        assert_eq!(session.state(), CalibrationState::AwaitingDocument);
+        assert_eq!(session.poll_event(), Some(CalibrationEvent::InsertDocument));
+
+        reply(&mut session, |r| {
+            r.set_calibration(Calibration::Set);
+            r.set_accepting(Accepting::Set);
+        });
+        assert_eq!(session.poll_event(), Some(CalibrationEvent::Calibrating));
+
+        reply(&mut session, |_| ());
+        assert_eq!(session.poll_event(), Some(CalibrationEvent::Succeeded));
+        assert!(session.is_finished());
+        assert_eq!(session.poll_intent(u64::MAX), None);
+        assert_eq!(session.poll_timeout(0), None);
+    }
+
+    #[test]
+    fn test_calibration_session_failures() {
+        let mut session = CalibrationSession::new().with_max_entry_polls(2);
+        reply(&mut session, |_| ());
+        reply(&mut session, |_| ());
+        assert_eq!(
+            session.poll_event(),
+            Some(CalibrationEvent::Failed(CalibrationFailure::NotEntered))
+        );
+
+        let mut session = CalibrationSession::new();
+        reply(&mut session, |r| r.set_calibration(Calibration::Set));
+        reply(&mut session, |r| {
+            r.set_calibration(Calibration::Set);
+            r.set_rejected(Rejected::Set);
+        });
+        assert_eq!(session.failure(), Some(CalibrationFailure::Rejected));
+
+        // calibration mode ends without the document being reported
+        let mut session = CalibrationSession::new();
+        reply(&mut session, |r| r.set_calibration(Calibration::Set));
+        reply(&mut session, |_| ());
+        assert_eq!(session.failure(), Some(CalibrationFailure::UnknownOutcome));
+
+        let mut session = CalibrationSession::new();
+        let command = send(&mut session);
+        session.handle_event(&HostEvent::CommandFailed(
+            command,
+            Error::timeout("timed out waiting for reply"),
+        ));
+        assert_eq!(session.failure(), Some(CalibrationFailure::Timeout));
+    }
+
+    #[test]
+    fn test_duplicate_replies_exhaust_retries() {
+        let mut protocol = HostProtocol::new().with_max_retries(1);
+        let mut session = CalibrationSession::new();
+
+        protocol.handle_intent(session.poll_intent(protocol.now_ms()).unwrap());
+        let frame = protocol.poll_transmit().unwrap();
+
+        let mut msg = CalibrateReply::new();
+        msg.set_acknak(frame.acknak());
+        msg.set_calibration(Calibration::Set);
+        protocol.handle_input(msg.as_bytes());
+        session.handle_event(&protocol.poll_event().unwrap());
+        assert_eq!(session.state(), CalibrationState::AwaitingDocument);
+
+        protocol.handle_elapsed(session.poll_timeout(protocol.now_ms()).unwrap());
+        protocol.handle_intent(session.poll_intent(protocol.now_ms()).unwrap());
+
+        // the device keeps replaying its previous reply
+        for _ in 0..2 {
+            assert!(protocol.poll_transmit().is_some());
+            protocol.handle_input(msg.as_bytes());
+            protocol.handle_elapsed(protocol.reply_timeout_ms());
+        }
+
+        session.handle_event(&protocol.poll_event().unwrap());
+        assert_eq!(session.failure(), Some(CalibrationFailure::Timeout));
+        assert!(!protocol.is_busy());
+    }
+}
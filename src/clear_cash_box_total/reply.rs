@@ -0,0 +1,104 @@
+use crate::std;
+use std::fmt;
+
+use crate::{
+    impl_message_ops, impl_omnibus_nop_reply, len::CLEAR_CASH_BOX_TOTAL_REPLY, seven_bit_u24,
+    u24_seven_bit, MessageOps, MessageType,
+};
+
+pub mod index {
+    pub const TOTAL: usize = 3;
+    pub const TOTAL_END: usize = 9;
+}
+
+/// Clear Cash Box Total - Reply (Subtype 0x03)
+///
+/// | **S2K** | **CFSC** | **SC Adv** | **SCR** |
+/// |:-------:|:--------:|:----------:|:-------:|
+///
+/// The Clear Cash Box Total Reply is formatted as follows:
+///
+/// | Name  | STX  | LEN  | CTRL | Data 0 | Data 1 | Data 2 | Data 3 | Data 4 | Data 5 | ETX  | CHK |
+/// |:------|:----:|:----:|:----:|:------:|:------:|:------:|:------:|:------:|:------:|:----:|:---:|
+/// | Byte  | 0    | 1    | 2    | 3      | 4      | 5      | 6      | 7      | 8      | 9    | 10  |
+/// | Value | 0x02 | 0x0B | 0x6n | 0x0n   | 0x0n   | 0x0n   | 0x0n   | 0x0n   | 0x0n   | 0x03 | zz  |
+///
+/// The device replies with the cleared cash box total, which should be zero. The 24-bit note count
+/// is sent in bytes 3 through 8, four bits at a time, most significant nibble first.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ClearCashBoxTotalReply {
+    buf: [u8; CLEAR_CASH_BOX_TOTAL_REPLY],
+}
+
+impl ClearCashBoxTotalReply {
+    /// Creates a new [ClearCashBoxTotalReply].
+    pub fn new() -> Self {
+        let mut message = Self {
+            buf: [0u8; CLEAR_CASH_BOX_TOTAL_REPLY],
+        };
+
+        message.init();
+        message.set_message_type(MessageType::AuxCommand);
+
+        message
+    }
+
+    /// Gets the cash box total after the clear.
+    ///
+    /// A non-zero total means the device did not clear the count.
+    pub fn cash_box_total(&self) -> u32 {
+        seven_bit_u24(self.buf[index::TOTAL..index::TOTAL_END].as_ref())
+    }
+
+    /// Sets the number of notes stacked in the cash box.
+    ///
+    /// The count is truncated to 24 bits.
+    pub fn set_cash_box_total(&mut self, total: u32) {
+        self.buf[index::TOTAL..index::TOTAL_END].copy_from_slice(u24_seven_bit(total).as_ref());
+    }
+}
+
+impl_message_ops!(ClearCashBoxTotalReply);
+impl_omnibus_nop_reply!(ClearCashBoxTotalReply);
+
+impl fmt::Display for ClearCashBoxTotalReply {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "AckNak: {}, DeviceType: {}, MessageType: {}, CashBoxTotal: {}",
+            self.acknak(),
+            self.device_type(),
+            self.message_type(),
+            self.cash_box_total(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Result;
+
+    #[test]
+    #[rustfmt::skip]
+    fn test_clear_cash_box_total_reply_from_buf() -> Result<()> {
+        let msg_bytes = [
+            // STX | LEN | Message Type
+            0x02, 0x0b, 0x60,
+            // Cash box total (cleared)
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            // ETX | Checksum
+            0x03, 0x6b,
+        ];
+
+        let mut msg = ClearCashBoxTotalReply::new();
+        msg.from_buf(msg_bytes.as_ref())?;
+
+        assert_eq!(msg.message_type(), MessageType::AuxCommand);
+        assert_eq!(msg.cash_box_total(), 0);
+        assert_eq!(ClearCashBoxTotalReply::new().as_bytes(), msg_bytes.as_ref());
+
+        Ok(())
+    }
+}
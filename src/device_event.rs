@@ -0,0 +1,385 @@
+use alloc::collections::VecDeque;
+
+use crate::std;
+use std::fmt;
+
+use crate::{
+    DeviceState, DeviceStateTransition, DeviceStatus, DocumentStatus, DocumentType,
+    ExceptionStatus, MessageType, MiscDeviceState, ReplyVariant,
+};
+
+/// Typed device events, detected from changes in successive Omnibus replies.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DeviceEvent {
+    /// A valid document entered escrow.
+    Escrowed(DocumentStatus),
+    /// The device stacked a document.
+    ///
+    /// Carries the [DocumentStatus] reported with the stacked event, or the status of the last
+    /// escrowed document if the stacked reply does not identify the document.
+    Stacked(DocumentStatus),
+    /// The device returned a document to the customer.
+    Returned,
+    /// The device rejected a document.
+    Rejected,
+    /// The device detected an attempt to fraud the system.
+    Cheated,
+    /// The document path is jammed.
+    Jammed,
+    /// The cash box is full.
+    StackerFull,
+    /// The cash box was removed.
+    CashBoxRemoved,
+    /// The cash box was attached.
+    CashBoxAttached,
+    /// The device powered up, and is initializing.
+    PowerUp,
+    /// The device encountered a problem, and is out of service.
+    Failure,
+    /// The device stalled.
+    Stalled,
+    /// The device state changed in a way that is not part of normal device operation, see
+    /// [DeviceStateModel](crate::DeviceStateModel).
+    UnexpectedTransition(DeviceStateTransition),
+}
+
+impl From<&DeviceEvent> for &'static str {
+    fn from(e: &DeviceEvent) -> Self {
+        match e {
+            DeviceEvent::Escrowed(_) => "Escrowed",
+            DeviceEvent::Stacked(_) => "Stacked",
+            DeviceEvent::Returned => "Returned",
+            DeviceEvent::Rejected => "Rejected",
+            DeviceEvent::Cheated => "Cheated",
+            DeviceEvent::Jammed => "Jammed",
+            DeviceEvent::StackerFull => "Stacker full",
+            DeviceEvent::CashBoxRemoved => "CashBox removed",
+            DeviceEvent::CashBoxAttached => "CashBox attached",
+            DeviceEvent::PowerUp => "Power up",
+            DeviceEvent::Failure => "Failure",
+            DeviceEvent::Stalled => "Stalled",
+            DeviceEvent::UnexpectedTransition(_) => "Unexpected transition",
+        }
+    }
+}
+
+impl From<DeviceEvent> for &'static str {
+    fn from(e: DeviceEvent) -> Self {
+        (&e).into()
+    }
+}
+
+impl fmt::Display for DeviceEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DeviceEvent::Escrowed(status) => write!(f, "Escrowed({status})"),
+            DeviceEvent::Stacked(status) => write!(f, "Stacked({status})"),
+            DeviceEvent::UnexpectedTransition(transition) => {
+                write!(f, "UnexpectedTransition({transition})")
+            }
+            event => write!(f, "{}", <&'static str>::from(event)),
+        }
+    }
+}
+
+/// Detects [DeviceEvent]s by comparing successive Omnibus replies.
+///
+/// Compares the [DeviceState], [DeviceStatus], [ExceptionStatus], and [MiscDeviceState] bytes of
+/// each reply against the previous reply. Events fire on the transition into a condition, so a bit
+/// reported across several replies (e.g. `stacked_event`, or `jammed`) produces exactly one event.
+///
+/// Before the first reply, the device is assumed to be idle with the cash box attached.
+///
+/// Replies without Omnibus status bytes (e.g. Auxilliary replies) are ignored.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DeviceEventDetector {
+    device_state: DeviceState,
+    device_status: DeviceStatus,
+    exception_status: ExceptionStatus,
+    misc_device_state: MiscDeviceState,
+    document: DocumentStatus,
+    events: VecDeque<DeviceEvent>,
+}
+
+impl DeviceEventDetector {
+    /// Creates a new [DeviceEventDetector].
+    pub fn new() -> Self {
+        let mut device_status = DeviceStatus::from(0);
+        device_status.set_cassette_attached(true);
+
+        Self {
+            device_state: DeviceState::none(),
+            device_status,
+            exception_status: ExceptionStatus::none(),
+            misc_device_state: MiscDeviceState::from(0),
+            document: DocumentStatus::default(),
+            events: VecDeque::new(),
+        }
+    }
+
+    /// Gets the [DeviceState] of the last handled reply.
+    pub fn device_state(&self) -> DeviceState {
+        self.device_state
+    }
+
+    /// Gets the [DeviceStatus] of the last handled reply.
+    pub fn device_status(&self) -> DeviceStatus {
+        self.device_status
+    }
+
+    /// Gets the [ExceptionStatus] of the last handled reply.
+    pub fn exception_status(&self) -> ExceptionStatus {
+        self.exception_status
+    }
+
+    /// Gets the [MiscDeviceState] of the last handled reply.
+    pub fn misc_device_state(&self) -> MiscDeviceState {
+        self.misc_device_state
+    }
+
+    /// Handles a reply from the device, queueing any detected [DeviceEvent]s.
+    pub fn handle_reply(&mut self, reply: &ReplyVariant) {
+        if !matches!(
+            reply.as_message().message_type(),
+            MessageType::OmnibusReply | MessageType::OmnibusBookmark | MessageType::Extended
+        ) {
+            return;
+        }
+
+        let omnibus = reply.as_omnibus_reply();
+        let (state, status, exception, misc) = (
+            omnibus.device_state(),
+            omnibus.device_status(),
+            omnibus.exception_status(),
+            omnibus.misc_device_state(),
+        );
+        let (last_state, last_status, last_exception, last_misc) = (
+            self.device_state,
+            self.device_status,
+            self.exception_status,
+            self.misc_device_state,
+        );
+
+        if rising(last_exception.power_up(), exception.power_up()) {
+            self.events.push_back(DeviceEvent::PowerUp);
+        }
+        if rising(last_exception.failure(), exception.failure()) {
+            self.events.push_back(DeviceEvent::Failure);
+        }
+        if rising(last_status.cassette_attached(), status.cassette_attached()) {
+            self.events.push_back(DeviceEvent::CashBoxAttached);
+        }
+        if rising(
+            !last_status.cassette_attached(),
+            !status.cassette_attached(),
+        ) {
+            self.events.push_back(DeviceEvent::CashBoxRemoved);
+        }
+        if rising(last_state.escrowed_state(), state.escrowed_state()) {
+            self.document = reply.document_status();
+            self.events.push_back(DeviceEvent::Escrowed(self.document));
+        }
+        if rising(last_state.stacked_event(), state.stacked_event()) {
+            let document = reply.document_status();
+            let document = if document.document_type() == DocumentType::NoDocument {
+                self.document
+            } else {
+                document
+            };
+
+            self.document = DocumentStatus::default();
+            self.events.push_back(DeviceEvent::Stacked(document));
+        }
+        if rising(last_state.returned_event(), state.returned_event()) {
+            self.document = DocumentStatus::default();
+            self.events.push_back(DeviceEvent::Returned);
+        }
+        if rising(last_status.rejected(), status.rejected()) {
+            self.events.push_back(DeviceEvent::Rejected);
+        }
+        if rising(last_status.cheated(), status.cheated()) {
+            self.events.push_back(DeviceEvent::Cheated);
+        }
+        if rising(last_status.jammed(), status.jammed()) {
+            self.events.push_back(DeviceEvent::Jammed);
+        }
+        if rising(last_status.stacker_full(), status.stacker_full()) {
+            self.events.push_back(DeviceEvent::StackerFull);
+        }
+        if rising(last_misc.stalled(), misc.stalled()) {
+            self.events.push_back(DeviceEvent::Stalled);
+        }
+
+        self.device_state = state;
+        self.device_status = status;
+        self.exception_status = exception;
+        self.misc_device_state = misc;
+    }
+
+    /// Gets the next [DeviceEvent], if any.
+    pub fn poll_event(&mut self) -> Option<DeviceEvent> {
+        self.events.pop_front()
+    }
+
+    /// Resets the detector to the initial state, and clears any queued events.
+    pub fn reset(&mut self) {
+        *self = Self::new();
+    }
+}
+
+impl Default for DeviceEventDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn rising(last: bool, next: bool) -> bool {
+    !last && next
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        BarcodeVoucher, Coupon, ExtendedBarcodeReply, ExtendedCouponReply, OmnibusReply,
+        OmnibusReplyOps, QueryBootPartNumberReply, StandardDenomination,
+    };
+    use alloc::vec::Vec;
+
+    fn omnibus_reply(device_state: u8, device_status: u8, exception_status: u8) -> ReplyVariant {
+        let mut reply = OmnibusReply::new();
+
+        reply.set_device_state(device_state.into());
+        reply.set_device_status(device_status.into());
+        reply.set_exception_status(exception_status.into());
+
+        ReplyVariant::OmnibusReply(reply)
+    }
+
+    fn drain(detector: &mut DeviceEventDetector) -> Vec<DeviceEvent> {
+        let mut events = Vec::new();
+        while let Some(event) = detector.poll_event() {
+            events.push(event);
+        }
+        events
+    }
+
+    #[test]
+    fn test_edge_events_fire_once() {
+        let mut detector = DeviceEventDetector::new();
+
+        // idling, cash box attached
+        detector.handle_reply(&omnibus_reply(0b0000_0001, 0b0001_0000, 0));
+        assert_eq!(drain(&mut detector), []);
+
+        // escrowed, with a note value
+        let escrowed = omnibus_reply(0b0000_0100, 0b0001_0000, 0b0001_0000);
+        detector.handle_reply(&escrowed);
+        assert_eq!(
+            drain(&mut detector),
+            [DeviceEvent::Escrowed(escrowed.document_status())]
+        );
+        assert_ne!(
+            escrowed.document_status().standard_denomination(),
+            StandardDenomination::none()
+        );
+
+        // stacked event is reported on two consecutive replies
+        detector.handle_reply(&omnibus_reply(0b0001_0001, 0b0001_0000, 0));
+        detector.handle_reply(&omnibus_reply(0b0001_0001, 0b0001_0000, 0));
+        assert_eq!(
+            drain(&mut detector),
+            [DeviceEvent::Stacked(escrowed.document_status())]
+        );
+
+        // a second note is stacked, without passing through escrow
+        detector.handle_reply(&omnibus_reply(0b0000_0001, 0b0001_0000, 0));
+        detector.handle_reply(&omnibus_reply(0b0001_0001, 0b0001_0000, 0));
+        assert_eq!(
+            drain(&mut detector),
+            [DeviceEvent::Stacked(DocumentStatus::default())]
+        );
+    }
+
+    #[test]
+    fn test_barcode_escrowed() -> crate::Result<()> {
+        let mut detector = DeviceEventDetector::new();
+        let voucher = BarcodeVoucher::new("123456789012345678")?;
+
+        let mut reply = ExtendedBarcodeReply::new();
+        reply.set_device_state(0b0000_0100.into());
+        reply.set_device_status(0b0001_0000.into());
+        reply.set_barcode(&voucher);
+
+        detector.handle_reply(&ReplyVariant::ExtendedBarcodeReply(reply));
+
+        match drain(&mut detector).as_slice() {
+            [DeviceEvent::Escrowed(document)] => {
+                assert_eq!(document.document_type(), DocumentType::Barcode);
+                assert_eq!(document.barcode(), Some(&voucher));
+            }
+            events => panic!("expected a barcode escrowed event, have: {events:?}"),
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_coupon_escrowed_and_stacked() {
+        let mut detector = DeviceEventDetector::new();
+        let coupon = Coupon::new(20, 3);
+
+        let mut reply = ExtendedCouponReply::new();
+        reply.set_device_state(0b0000_0100.into());
+        reply.set_device_status(0b0001_0000.into());
+        reply.set_coupon(coupon);
+
+        let escrowed = ReplyVariant::ExtendedCouponReply(reply);
+        detector.handle_reply(&escrowed);
+        assert_eq!(
+            drain(&mut detector),
+            [DeviceEvent::Escrowed(escrowed.document_status())]
+        );
+
+        // stacked reported in a standard omnibus reply
+        detector.handle_reply(&omnibus_reply(0b0001_0001, 0b0001_0000, 0));
+
+        match drain(&mut detector).as_slice() {
+            [DeviceEvent::Stacked(document)] => {
+                assert_eq!(document.document_type(), DocumentType::Coupon);
+                assert_eq!(document.coupon(), Some(coupon));
+            }
+            events => panic!("expected a coupon stacked event, have: {events:?}"),
+        }
+    }
+
+    #[test]
+    fn test_status_events() {
+        let mut detector = DeviceEventDetector::new();
+
+        // powering up, with the cash box removed
+        detector.handle_reply(&omnibus_reply(0, 0, 0b0000_0001));
+        assert_eq!(
+            drain(&mut detector),
+            [DeviceEvent::PowerUp, DeviceEvent::CashBoxRemoved]
+        );
+
+        detector.handle_reply(&omnibus_reply(0b0000_0001, 0b0001_0000, 0));
+        assert_eq!(drain(&mut detector), [DeviceEvent::CashBoxAttached]);
+
+        // jammed, and rejected
+        detector.handle_reply(&omnibus_reply(0, 0b0001_0110, 0));
+        detector.handle_reply(&omnibus_reply(0, 0b0001_0100, 0));
+        assert_eq!(
+            drain(&mut detector),
+            [DeviceEvent::Rejected, DeviceEvent::Jammed]
+        );
+
+        // aux replies carry no status bytes
+        detector.handle_reply(&ReplyVariant::QueryBootPartNumberReply(
+            QueryBootPartNumberReply::new(),
+        ));
+        assert_eq!(drain(&mut detector), []);
+        assert!(detector.device_status().jammed());
+    }
+}
@@ -0,0 +1,347 @@
+use alloc::collections::VecDeque;
+
+use crate::{
+    DeviceEvent, DeviceEventDetector, DeviceStateModel, Frame, HostContext, HostEvent, HostIntent,
+    HostMachine, HostProtocol, MessageType, OmnibusCommand,
+};
+
+/// Sans-IO Omnibus poll loop, emitting typed [DeviceEvent]s.
+///
+/// Wraps a [HostProtocol] to send an [OmnibusCommand] at a configurable interval, and feeds every
+/// accepted reply through a [DeviceEventDetector], and a [DeviceStateModel]. Suspicious, and
+/// impossible state transitions are emitted as a [DeviceEvent::UnexpectedTransition].
+///
+/// The I/O layer drives the poller the same way as the [HostProtocol]. Device events are drained
+/// with [poll_event](Self::poll_event), and the underlying [HostEvent]s (replies, failed commands,
+/// etc.) with [poll_host_event](Self::poll_host_event).
+///
+/// Example:
+///
+/// ```rust
+/// # use ebds::{DeviceEvent, DevicePoller, DocumentStatus};
+/// let mut poller = DevicePoller::new().with_poll_interval_ms(100);
+/// poller.start();
+///
+/// // write the poll to the device
+/// assert!(poller.poll_transmit().is_some());
+///
+/// // device reply arrives, with the stacked bit set
+/// poller.handle_input(&[0x02, 0x0b, 0x20, 0x11, 0x10, 0x00, 0x00, 0x00, 0x00, 0x03, 0x2a]);
+/// assert_eq!(
+///     poller.poll_event(),
+///     Some(DeviceEvent::Stacked(DocumentStatus::default()))
+/// );
+/// assert_eq!(poller.poll_event(), None);
+/// ```
+#[derive(Debug)]
+pub struct DevicePoller {
+    protocol: HostProtocol,
+    detector: DeviceEventDetector,
+    state_model: DeviceStateModel,
+    events: VecDeque<DeviceEvent>,
+    host_events: VecDeque<HostEvent>,
+}
+
+impl DevicePoller {
+    /// Creates a new [DevicePoller].
+    ///
+    /// Polling does not begin until [start](Self::start) is called.
+    pub fn new() -> Self {
+        Self {
+            protocol: HostProtocol::new(),
+            detector: DeviceEventDetector::new(),
+            state_model: DeviceStateModel::new()
+                .with_context(HostContext::from_command(&OmnibusCommand::new())),
+            events: VecDeque::new(),
+            host_events: VecDeque::new(),
+        }
+    }
+
+    /// Gets the interval between Omnibus polls (milliseconds).
+    pub fn poll_interval_ms(&self) -> u64 {
+        self.protocol.poll_interval_ms()
+    }
+
+    /// Sets the interval between Omnibus polls (milliseconds).
+    pub fn set_poll_interval_ms(&mut self, poll_interval_ms: u64) {
+        self.protocol.set_poll_interval_ms(poll_interval_ms);
+    }
+
+    /// Builder function to set the interval between Omnibus polls (milliseconds).
+    pub fn with_poll_interval_ms(mut self, poll_interval_ms: u64) -> Self {
+        self.set_poll_interval_ms(poll_interval_ms);
+        self
+    }
+
+    /// Builder function to set the [OmnibusCommand] used for periodic polls.
+    pub fn with_poll_command(mut self, poll_command: OmnibusCommand) -> Self {
+        self.handle_intent(HostIntent::SetPollCommand(poll_command));
+        self
+    }
+
+    /// Gets a reference to the underlying [HostProtocol].
+    pub fn protocol(&self) -> &HostProtocol {
+        &self.protocol
+    }
+
+    /// Gets a mutable reference to the underlying [HostProtocol].
+    ///
+    /// Events emitted by the [HostProtocol] are only processed by the [DevicePoller] when fed
+    /// through the poller's `handle_*` functions.
+    pub fn protocol_mut(&mut self) -> &mut HostProtocol {
+        &mut self.protocol
+    }
+
+    /// Gets a reference to the [DeviceEventDetector].
+    pub fn detector(&self) -> &DeviceEventDetector {
+        &self.detector
+    }
+
+    /// Gets a reference to the [DeviceStateModel].
+    pub fn state_model(&self) -> &DeviceStateModel {
+        &self.state_model
+    }
+
+    /// Starts sending periodic Omnibus polls.
+    pub fn start(&mut self) {
+        self.handle_intent(HostIntent::StartPolling);
+    }
+
+    /// Stops sending periodic Omnibus polls.
+    pub fn stop(&mut self) {
+        self.handle_intent(HostIntent::StopPolling);
+    }
+
+    /// Handles a user intent.
+    ///
+    /// See [HostProtocol::handle_intent].
+    pub fn handle_intent(&mut self, intent: HostIntent) {
+        if let HostIntent::SetPollCommand(command) = &intent {
+            self.state_model
+                .set_context(HostContext::from_command(command));
+        }
+
+        self.protocol.handle_intent(intent);
+        self.process();
+    }
+
+    /// Handles bytes received from the device.
+    pub fn handle_input(&mut self, bytes: &[u8]) {
+        self.protocol.handle_input(bytes);
+        self.process();
+    }
+
+    /// Handles the passage of time (milliseconds).
+    pub fn handle_elapsed(&mut self, elapsed_ms: u64) {
+        self.protocol.handle_elapsed(elapsed_ms);
+        self.process();
+    }
+
+    /// Gets the next message to write to the device, if any.
+    pub fn poll_transmit(&mut self) -> Option<Frame> {
+        self.protocol.poll_transmit()
+    }
+
+    /// Gets the time until the next deadline (milliseconds), if any.
+    pub fn poll_timeout(&self) -> Option<u64> {
+        self.protocol.poll_timeout()
+    }
+
+    /// Gets the next [DeviceEvent], if any.
+    pub fn poll_event(&mut self) -> Option<DeviceEvent> {
+        self.events.pop_front()
+    }
+
+    /// Gets the next [HostEvent], if any.
+    pub fn poll_host_event(&mut self) -> Option<HostEvent> {
+        self.host_events.pop_front()
+    }
+
+    fn process(&mut self) {
+        while let Some(event) = self.protocol.poll_event() {
+            if let HostEvent::Reply(reply) = &event {
+                self.detector.handle_reply(reply);
+                while let Some(device_event) = self.detector.poll_event() {
+                    self.events.push_back(device_event);
+                }
+
+                if matches!(
+                    reply.as_message().message_type(),
+                    MessageType::OmnibusReply
+                        | MessageType::OmnibusBookmark
+                        | MessageType::Extended
+                ) {
+                    if let Some(transition) =
+                        self.state_model.handle_reply(reply.as_omnibus_reply())
+                    {
+                        if !transition.is_expected() {
+                            self.events
+                                .push_back(DeviceEvent::UnexpectedTransition(transition));
+                        }
+                    }
+                }
+            }
+
+            self.host_events.push_back(event);
+        }
+    }
+}
+
+impl HostMachine for DevicePoller {
+    fn protocol(&self) -> &HostProtocol {
+        DevicePoller::protocol(self)
+    }
+
+    fn protocol_mut(&mut self) -> &mut HostProtocol {
+        DevicePoller::protocol_mut(self)
+    }
+
+    fn handle_input(&mut self, bytes: &[u8]) {
+        DevicePoller::handle_input(self, bytes)
+    }
+
+    fn handle_elapsed(&mut self, elapsed_ms: u64) {
+        DevicePoller::handle_elapsed(self, elapsed_ms)
+    }
+
+    fn poll_transmit(&mut self) -> Option<Frame> {
+        DevicePoller::poll_transmit(self)
+    }
+
+    fn poll_timeout(&self) -> Option<u64> {
+        DevicePoller::poll_timeout(self)
+    }
+}
+
+impl Default for DevicePoller {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        AckNak, CassetteAttached, DeviceStateFlags, DeviceStateTransition, MessageOps,
+        OmnibusReply, OmnibusReplyOps, PowerUpStatus, ReplyVariant,
+    };
+
+    #[rustfmt::skip]
+    const IDLE_REPLY: [u8; 11] = [
+        // STX | LEN | Message type
+        0x02, 0x0b, 0x20,
+        // Data
+        0b0000_0001, 0b0001_0000, 0b0000_0000, 0b0000_0000, 0b0000_0000, 0b0000_0000,
+        // ETX | Checksum
+        0x03, 0x3a,
+    ];
+
+    #[rustfmt::skip]
+    const RETURNED_REPLY: [u8; 11] = [
+        // STX | LEN | Message type
+        0x02, 0x0b, 0x21,
+        // Data
+        0b0100_0001, 0b0001_0000, 0b0000_0000, 0b0000_0000, 0b0000_0000, 0b0000_0000,
+        // ETX | Checksum
+        0x03, 0x7b,
+    ];
+
+    #[rustfmt::skip]
+    const RETURNED_REPLY_ACK: [u8; 11] = [
+        // STX | LEN | Message type
+        0x02, 0x0b, 0x20,
+        // Data
+        0b0100_0001, 0b0001_0000, 0b0000_0000, 0b0000_0000, 0b0000_0000, 0b0000_0000,
+        // ETX | Checksum
+        0x03, 0x7a,
+    ];
+
+    #[test]
+    fn test_poll_events() {
+        let mut poller = DevicePoller::new().with_poll_interval_ms(50);
+        poller.start();
+
+        assert!(poller.poll_transmit().is_some());
+        poller.handle_input(IDLE_REPLY.as_ref());
+        assert_eq!(poller.poll_event(), None);
+        assert!(matches!(
+            poller.poll_host_event(),
+            Some(HostEvent::Reply(ReplyVariant::OmnibusReply(_)))
+        ));
+
+        assert_eq!(poller.poll_timeout(), Some(50));
+        poller.handle_elapsed(50);
+        assert!(poller.poll_transmit().is_some());
+        poller.handle_input(RETURNED_REPLY.as_ref());
+        assert_eq!(poller.poll_event(), Some(DeviceEvent::Returned));
+        // the default poll command enables no denominations
+        assert_eq!(poller.state_model().state(), DeviceStateFlags::HostDisabled);
+
+        // device keeps reporting the returned event on the next poll
+        poller.handle_elapsed(50);
+        assert!(poller.poll_transmit().is_some());
+        poller.handle_input(RETURNED_REPLY_ACK.as_ref());
+        assert_eq!(poller.poll_event(), None);
+    }
+
+    #[test]
+    fn test_unexpected_transition_event() {
+        let mut poller = DevicePoller::new().with_poll_interval_ms(50);
+        poller.start();
+
+        // the first reply after power up is expected
+        assert!(poller.poll_transmit().is_some());
+        poller.handle_input(IDLE_REPLY.as_ref());
+        assert_eq!(poller.poll_event(), None);
+
+        // a reset while the device is running is suspicious
+        let mut reply = OmnibusReply::new();
+        reply.set_acknak(AckNak::Nak);
+        reply.set_cassette_attached(CassetteAttached::Set);
+        reply.set_power_up(PowerUpStatus::Set);
+
+        poller.handle_elapsed(50);
+        assert!(poller.poll_transmit().is_some());
+        poller.handle_input(reply.as_bytes());
+
+        assert_eq!(poller.poll_event(), Some(DeviceEvent::PowerUp));
+        assert_eq!(
+            poller.poll_event(),
+            Some(DeviceEvent::UnexpectedTransition(
+                DeviceStateTransition::new(
+                    DeviceStateFlags::HostDisabled,
+                    DeviceStateFlags::PowerUp,
+                )
+            ))
+        );
+        assert_eq!(poller.state_model().suspicious(), 1);
+    }
+
+    #[test]
+    #[rustfmt::skip]
+    fn test_bookmark_reply_updates_state() {
+        let bookmark_reply = [
+            // STX | LEN | Message type
+            0x02, 0x0b, 0x30,
+            // Data
+            0b0000_0001, 0b0001_0000, 0b0000_0000, 0b0000_0000, 0b0000_0000, 0b0000_0000,
+            // ETX | Checksum
+            0x03, 0x2a,
+        ];
+
+        let mut poller = DevicePoller::new().with_poll_interval_ms(50);
+        poller.protocol_mut().set_bookmark_mode(true);
+        poller.start();
+
+        let poll = poller.poll_transmit().unwrap();
+        assert_eq!(poll.message_type(), MessageType::OmnibusBookmark);
+
+        poller.handle_input(bookmark_reply.as_ref());
+        assert!(matches!(
+            poller.poll_host_event(),
+            Some(HostEvent::Reply(ReplyVariant::OmnibusBookmarkReply(_)))
+        ));
+        assert_eq!(poller.state_model().state(), DeviceStateFlags::HostDisabled);
+    }
+}
@@ -0,0 +1,345 @@
+use embedded_io::{ErrorKind, Read, ReadReady, Write};
+
+use crate::{
+    AckNakSequencer, CommandVariant, Error, HostEvent, HostIntent, HostProtocol, MessageOps,
+    ReplyVariant, Result,
+};
+
+/// Caller-supplied timer used for reply timeouts.
+///
+/// Implement over the hardware timer, or system tick, of the target platform.
+pub trait ReplyTimer {
+    /// Starts the timer, expiring after the provided number of milliseconds.
+    fn start(&mut self, timeout_ms: u32);
+
+    /// Gets the number of milliseconds elapsed since the last call to [start](Self::start).
+    fn elapsed_ms(&mut self) -> u32;
+
+    /// Blocks until the timer expires, or another event (e.g. a UART receive interrupt) wakes the
+    /// caller.
+    ///
+    /// Typically implemented with a wait-for-interrupt (`WFI`) instruction, or an RTOS event wait.
+    fn wait(&mut self);
+}
+
+/// Transport for `no_std` targets over [embedded_io] serial traits.
+///
+/// Performs the same frame/send/receive cycle as the `std` transports, using fixed-size buffers
+/// instead of heap allocations. The transport drives the same [HostProtocol], which frames replies,
+/// sequences the ACK/NAK toggle, and re-sends commands on a missing reply, invalid reply, or toggle
+/// mismatch. The [HostProtocol] queues are fixed-capacity, see
+/// [MAX_QUEUED_COMMANDS](crate::MAX_QUEUED_COMMANDS), and
+/// [MAX_QUEUED_EVENTS](crate::MAX_QUEUED_EVENTS).
+///
+/// Exchanges with the device, including timeouts, retries, and I/O errors, do not allocate. Only
+/// the errors for a reply the message types cannot parse allocate, to carry formatted details.
+///
+/// The port must implement [ReadReady], so reads never block past the reply timeout. While no
+/// input is ready, the transport blocks in [ReplyTimer::wait].
+pub struct EmbeddedTransport<P, T> {
+    port: P,
+    timer: T,
+    protocol: HostProtocol,
+}
+
+impl<P, T> EmbeddedTransport<P, T>
+where
+    P: Read + ReadReady + Write,
+    T: ReplyTimer,
+{
+    /// Creates a new [EmbeddedTransport] from a serial port, and a [ReplyTimer].
+    pub fn new(port: P, timer: T) -> Self {
+        Self {
+            port,
+            timer,
+            protocol: HostProtocol::new(),
+        }
+    }
+
+    /// Gets the reply timeout (milliseconds).
+    pub fn reply_timeout_ms(&self) -> u32 {
+        self.protocol.reply_timeout_ms() as u32
+    }
+
+    /// Sets the reply timeout (milliseconds).
+    pub fn set_reply_timeout_ms(&mut self, reply_timeout_ms: u32) {
+        self.protocol.set_reply_timeout_ms(reply_timeout_ms.into());
+    }
+
+    /// Builder function to set the reply timeout (milliseconds).
+    pub fn with_reply_timeout_ms(mut self, reply_timeout_ms: u32) -> Self {
+        self.set_reply_timeout_ms(reply_timeout_ms);
+        self
+    }
+
+    /// Builder function to set the maximum number of times a command is re-sent.
+    pub fn with_max_retries(mut self, max_retries: u8) -> Self {
+        self.protocol.sequencer_mut().set_max_retries(max_retries);
+        self
+    }
+
+    /// Gets a reference to the [HostProtocol].
+    pub fn protocol(&self) -> &HostProtocol {
+        &self.protocol
+    }
+
+    /// Gets a mutable reference to the [HostProtocol].
+    pub fn protocol_mut(&mut self) -> &mut HostProtocol {
+        &mut self.protocol
+    }
+
+    /// Gets a reference to the [AckNakSequencer].
+    pub fn sequencer(&self) -> &AckNakSequencer {
+        self.protocol.sequencer()
+    }
+
+    /// Gets a mutable reference to the [AckNakSequencer].
+    pub fn sequencer_mut(&mut self) -> &mut AckNakSequencer {
+        self.protocol.sequencer_mut()
+    }
+
+    /// Gets a reference to the underlying port.
+    pub fn port(&self) -> &P {
+        &self.port
+    }
+
+    /// Gets a mutable reference to the underlying port.
+    pub fn port_mut(&mut self) -> &mut P {
+        &mut self.port
+    }
+
+    /// Consumes the [EmbeddedTransport], returning the underlying port and timer.
+    pub fn into_inner(self) -> (P, T) {
+        (self.port, self.timer)
+    }
+
+    /// Sends a [CommandVariant] to the device, and waits for an accepted reply.
+    ///
+    /// The command is stamped with the ACK/NAK toggle it was sent with.
+    pub fn send(&mut self, command: &mut CommandVariant) -> Result<ReplyVariant> {
+        self.protocol.handle_intent(HostIntent::Send(*command));
+        self.drive(command.as_message_mut())
+    }
+
+    /// Sends a generic message to the device, and waits for an accepted reply.
+    ///
+    /// The message is stamped with the ACK/NAK toggle it was sent with.
+    pub fn send_message(&mut self, message: &mut dyn MessageOps) -> Result<ReplyVariant> {
+        let command = CommandVariant::try_from(message.as_bytes())?;
+
+        self.protocol.handle_intent(HostIntent::Send(command));
+        self.drive(message)
+    }
+
+    /// Writes a message to the device.
+    ///
+    /// Calculates the message checksum before writing.
+    pub fn write_message(&mut self, message: &mut dyn MessageOps) -> Result<()> {
+        self.port.write_all(message.as_bytes()).map_err(io_error)?;
+        self.port.flush().map_err(io_error)?;
+
+        Ok(())
+    }
+
+    // Drives the protocol until the queued command is accepted, or fails.
+    fn drive(&mut self, message: &mut dyn MessageOps) -> Result<ReplyVariant> {
+        let mut chunk = [0u8; 64];
+
+        loop {
+            while let Some(event) = self.protocol.poll_event() {
+                match event {
+                    HostEvent::Reply(reply) => return Ok(reply),
+                    HostEvent::CommandFailed(_, err) => return Err(err),
+                    HostEvent::BytesDiscarded(count) => {
+                        log::debug!("Discarded {count} bytes of invalid input")
+                    }
+                }
+            }
+
+            if !self.protocol.is_busy() {
+                return Err(Error::failure("no command waiting for a reply"));
+            }
+
+            if let Some(mut frame) = self.protocol.poll_transmit() {
+                message.buf_mut().copy_from_slice(frame.buf());
+                self.write_message(&mut frame)?;
+            }
+
+            let timeout_ms = self
+                .protocol
+                .poll_timeout()
+                .unwrap_or_default()
+                .min(u32::MAX.into()) as u32;
+
+            self.timer.start(timeout_ms);
+
+            let mut ready = self.port.read_ready().map_err(io_error)?;
+            while !ready && self.timer.elapsed_ms() < timeout_ms {
+                self.timer.wait();
+                ready = self.port.read_ready().map_err(io_error)?;
+            }
+
+            if ready {
+                match self.port.read(chunk.as_mut()).map_err(io_error)? {
+                    0 => return Err(Error::serial("device connection closed")),
+                    read => self.protocol.handle_input(&chunk[..read]),
+                }
+            }
+
+            self.protocol.handle_elapsed(self.timer.elapsed_ms().into());
+        }
+    }
+}
+
+// Maps an I/O error to a static message, so reporting it does not allocate.
+fn io_error<E: embedded_io::Error>(err: E) -> Error {
+    let kind = err.kind();
+    log::debug!("Embedded I/O error: {kind:?}");
+
+    Error::serial(match kind {
+        ErrorKind::TimedOut => "embedded I/O error: timed out",
+        ErrorKind::Interrupted => "embedded I/O error: interrupted",
+        ErrorKind::InvalidData => "embedded I/O error: invalid data",
+        ErrorKind::WriteZero => "embedded I/O error: write zero",
+        ErrorKind::BrokenPipe => "embedded I/O error: broken pipe",
+        ErrorKind::NotConnected => "embedded I/O error: not connected",
+        ErrorKind::Unsupported => "embedded I/O error: unsupported",
+        ErrorKind::OutOfMemory => "embedded I/O error: out of memory",
+        _ => "embedded I/O error: other",
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{len, ErrorCode, OmnibusCommand};
+
+    #[rustfmt::skip]
+    const OMNIBUS_REPLY: [u8; 11] = [
+        // STX | LEN | Message type
+        0x02, 0x0b, 0x20,
+        // Data
+        0b0000_0001, 0b0001_0000, 0b0000_0000, 0b0000_0000, 0b0000_0000, 0b0000_0000,
+        // ETX | Checksum
+        0x03, 0x3a,
+    ];
+
+    struct MockPort {
+        rx: [u8; 32],
+        rx_len: usize,
+        rx_pos: usize,
+        tx: [u8; 32],
+        tx_len: usize,
+        reads: usize,
+    }
+
+    impl MockPort {
+        fn new(rx: &[u8]) -> Self {
+            let mut port = Self {
+                rx: [0u8; 32],
+                rx_len: rx.len(),
+                rx_pos: 0,
+                tx: [0u8; 32],
+                tx_len: 0,
+                reads: 0,
+            };
+            port.rx[..rx.len()].copy_from_slice(rx);
+            port
+        }
+    }
+
+    impl embedded_io::ErrorType for MockPort {
+        type Error = embedded_io::ErrorKind;
+    }
+
+    impl Read for MockPort {
+        fn read(&mut self, buf: &mut [u8]) -> core::result::Result<usize, Self::Error> {
+            let n = buf.len().min(self.rx_len - self.rx_pos);
+            buf[..n].copy_from_slice(&self.rx[self.rx_pos..self.rx_pos + n]);
+            self.rx_pos += n;
+            self.reads += 1;
+            Ok(n)
+        }
+    }
+
+    impl ReadReady for MockPort {
+        fn read_ready(&mut self) -> core::result::Result<bool, Self::Error> {
+            Ok(self.rx_pos < self.rx_len)
+        }
+    }
+
+    impl Write for MockPort {
+        fn write(&mut self, buf: &[u8]) -> core::result::Result<usize, Self::Error> {
+            let n = buf.len().min(self.tx.len() - self.tx_len);
+            self.tx[self.tx_len..self.tx_len + n].copy_from_slice(&buf[..n]);
+            self.tx_len += n;
+            Ok(n)
+        }
+
+        fn flush(&mut self) -> core::result::Result<(), Self::Error> {
+            Ok(())
+        }
+    }
+
+    // Advances one millisecond per wait.
+    #[derive(Default)]
+    struct MockTimer {
+        elapsed_ms: u32,
+        waits: u32,
+    }
+
+    impl ReplyTimer for MockTimer {
+        fn start(&mut self, _timeout_ms: u32) {
+            self.elapsed_ms = 0;
+        }
+
+        fn elapsed_ms(&mut self) -> u32 {
+            self.elapsed_ms
+        }
+
+        fn wait(&mut self) {
+            self.elapsed_ms += 1;
+            self.waits += 1;
+        }
+    }
+
+    #[test]
+    fn test_embedded_send() -> Result<()> {
+        let mut transport =
+            EmbeddedTransport::new(MockPort::new(OMNIBUS_REPLY.as_ref()), MockTimer::default());
+        let mut command = CommandVariant::OmnibusCommand(OmnibusCommand::new());
+
+        let reply = transport.send(&mut command)?;
+
+        let (port, timer) = transport.into_inner();
+
+        assert_eq!(
+            &port.tx[..port.tx_len],
+            command.as_message().as_bytes_unchecked()
+        );
+        assert!(matches!(reply, ReplyVariant::OmnibusReply(_)));
+        // the available input is read in one call, without waiting on the timer
+        assert_eq!(port.reads, 1);
+        assert_eq!(timer.waits, 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_embedded_send_timeout() {
+        let mut transport = EmbeddedTransport::new(MockPort::new(&[]), MockTimer::default())
+            .with_reply_timeout_ms(5)
+            .with_max_retries(1);
+        let mut command = CommandVariant::OmnibusCommand(OmnibusCommand::new());
+
+        let err = transport.send(&mut command).unwrap_err();
+
+        assert_eq!(err.code(), ErrorCode::Timeout);
+        assert_eq!(transport.port().tx_len, len::OMNIBUS_COMMAND * 2);
+
+        // the transport blocks on the timer, instead of polling the port
+        let (port, timer) = transport.into_inner();
+        assert_eq!(port.reads, 0);
+        assert_eq!(timer.waits, 10);
+    }
+}
@@ -1,3 +1,4 @@
+use alloc::borrow::Cow;
 #[cfg(not(feature = "std"))]
 use alloc::string::String;
 
@@ -326,7 +327,7 @@ impl From<HalResult> for JsonRpcError {
 #[cfg(feature = "std")]
 impl From<JsonRpcError> for std::io::Error {
     fn from(err: JsonRpcError) -> Self {
-        Self::new(std::io::ErrorKind::Other, format!("{err}"))
+        Self::other(format!("{err}"))
     }
 }
 
@@ -356,18 +357,20 @@ impl From<std::str::Utf8Error> for JsonRpcError {
 }
 
 /// Basic error type for serial communication
+///
+/// Errors created from a `&'static str` message do not allocate.
 #[repr(C)]
 #[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 pub struct Error {
     code: ErrorCode,
-    message: String,
+    message: Cow<'static, str>,
 }
 
 impl Error {
     /// Create a generic failure Error
     pub fn failure<S>(message: S) -> Self
     where
-        S: Into<String>,
+        S: Into<Cow<'static, str>>,
     {
         Self {
             code: ErrorCode::Failure,
@@ -378,7 +381,7 @@ impl Error {
     /// Create a serial port failure Error
     pub fn serial<S>(message: S) -> Self
     where
-        S: Into<String>,
+        S: Into<Cow<'static, str>>,
     {
         Self {
             code: ErrorCode::SerialPort,
@@ -386,10 +389,21 @@ impl Error {
         }
     }
 
+    /// Create a timeout failure Error
+    pub fn timeout<S>(message: S) -> Self
+    where
+        S: Into<Cow<'static, str>>,
+    {
+        Self {
+            code: ErrorCode::Timeout,
+            message: message.into(),
+        }
+    }
+
     /// Create a JSON-RPC failure Error
     pub fn json_rpc<S>(message: S) -> Self
     where
-        S: Into<String>,
+        S: Into<Cow<'static, str>>,
     {
         Self {
             code: ErrorCode::JsonRpc,
@@ -404,7 +418,7 @@ impl Error {
 
     /// Get the error message
     pub fn message(&self) -> &str {
-        self.message.as_str()
+        self.message.as_ref()
     }
 }
 
@@ -419,7 +433,7 @@ impl From<std::io::Error> for Error {
     fn from(err: std::io::Error) -> Self {
         Self {
             code: ErrorCode::Failure,
-            message: format!("I/O error: {}", err),
+            message: format!("I/O error: {}", err).into(),
         }
     }
 }
@@ -428,7 +442,7 @@ impl From<std::str::Utf8Error> for Error {
     fn from(err: std::str::Utf8Error) -> Self {
         Self {
             code: ErrorCode::Failure,
-            message: format!("Utf8 error: {}", err),
+            message: format!("Utf8 error: {}", err).into(),
         }
     }
 }
@@ -437,7 +451,7 @@ impl From<serialport::Error> for Error {
     fn from(err: serialport::Error) -> Self {
         Self {
             code: ErrorCode::SerialPort,
-            message: format!("Serial port error: {err}"),
+            message: format!("Serial port error: {err}").into(),
         }
     }
 }
@@ -484,6 +498,8 @@ pub enum ErrorCode {
     SerialPort = -2,
     /// JSON-RPC failure code
     JsonRpc = -3,
+    /// Failure code for a device that did not respond in time
+    Timeout = -4,
 }
 
 impl From<ErrorCode> for &'static str {
@@ -492,6 +508,7 @@ impl From<ErrorCode> for &'static str {
             ErrorCode::Failure => "failure",
             ErrorCode::SerialPort => "serial port",
             ErrorCode::JsonRpc => "JSON-RPC",
+            ErrorCode::Timeout => "timeout",
         }
     }
 }
@@ -0,0 +1,620 @@
+use alloc::collections::VecDeque;
+
+use crate::std;
+use std::fmt;
+
+use crate::{
+    CommandVariant, DeviceEvent, DevicePoller, DocumentStatus, Frame, HostEvent, HostIntent,
+    HostMachine, HostProtocol, SetEscrowTimeoutCommand,
+};
+
+/// Default time the device holds an undecided document in escrow (seconds).
+pub const DEFAULT_ESCROW_TIMEOUT_SECS: u8 = 30;
+/// Maximum escrow timeout supported by [SetEscrowTimeoutCommand] (seconds).
+pub const MAX_ESCROW_TIMEOUT_SECS: u8 = 127;
+
+/// Host decision for an escrowed document.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum EscrowDecision {
+    /// Stack the document in the cash box.
+    Stack,
+    /// Return the document to the customer.
+    Return,
+}
+
+impl From<EscrowDecision> for &'static str {
+    fn from(d: EscrowDecision) -> Self {
+        match d {
+            EscrowDecision::Stack => "Stack",
+            EscrowDecision::Return => "Return",
+        }
+    }
+}
+
+impl From<&EscrowDecision> for &'static str {
+    fn from(d: &EscrowDecision) -> Self {
+        (*d).into()
+    }
+}
+
+impl fmt::Display for EscrowDecision {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", <&'static str>::from(self))
+    }
+}
+
+/// Final outcome of an escrowed document.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum EscrowOutcome {
+    /// The document was stacked.
+    Stacked(DocumentStatus),
+    /// The document was returned, as decided by the host.
+    Returned(DocumentStatus),
+    /// The host decided to stack the document, but the stack failed, and the document was returned.
+    StackFailed(DocumentStatus),
+    /// The host did not decide before the escrow timeout, and the document was returned.
+    TimedOut(DocumentStatus),
+    /// The device rejected the document before the host decided, and returned it.
+    Rejected(DocumentStatus),
+    /// The device reported an out-of-service condition before the document left escrow.
+    Failed(DocumentStatus, DeviceEvent),
+}
+
+impl EscrowOutcome {
+    /// Gets the [DocumentStatus] of the escrowed document.
+    pub fn document(&self) -> &DocumentStatus {
+        match self {
+            Self::Stacked(doc)
+            | Self::Returned(doc)
+            | Self::StackFailed(doc)
+            | Self::TimedOut(doc)
+            | Self::Rejected(doc)
+            | Self::Failed(doc, _) => doc,
+        }
+    }
+
+    /// Gets whether the document was stacked.
+    pub fn is_stacked(&self) -> bool {
+        matches!(self, Self::Stacked(_))
+    }
+}
+
+impl fmt::Display for EscrowOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Stacked(doc) => write!(f, "Stacked({doc})"),
+            Self::Returned(doc) => write!(f, "Returned({doc})"),
+            Self::StackFailed(doc) => write!(f, "StackFailed({doc})"),
+            Self::TimedOut(doc) => write!(f, "TimedOut({doc})"),
+            Self::Rejected(doc) => write!(f, "Rejected({doc})"),
+            Self::Failed(doc, event) => write!(f, "Failed({doc}, {event})"),
+        }
+    }
+}
+
+// Progress of the document currently in escrow.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum EscrowState {
+    Idle,
+    AwaitingDecision {
+        document: DocumentStatus,
+        deadline: u64,
+    },
+    Stacking {
+        document: DocumentStatus,
+    },
+    Returning {
+        document: DocumentStatus,
+        timed_out: bool,
+    },
+}
+
+/// Sans-IO escrow session, driving escrowed documents to a final [EscrowOutcome].
+///
+/// Wraps a [DevicePoller], and is driven by the I/O layer the same way. On start, the session
+/// sends a [SetEscrowTimeoutCommand], and begins polling.
+///
+/// When a document enters escrow, its [DocumentStatus] (including the
+/// [AcceptedNoteTableItem](crate::AcceptedNoteTableItem) in extended note mode, or the
+/// [StandardDenomination](crate::StandardDenomination) otherwise) is passed to the decision
+/// callback. The callback returns:
+///
+/// - `Some(decision)`: the session sets the `stack` or `return` bit on each poll until the
+///   document leaves escrow.
+/// - `None`: the decision is deferred, and can be made later with [decide](Self::decide).
+///
+/// If no decision is made, the device returns the document after the escrow timeout. Should the
+/// device keep the document past the timeout (plus one poll interval), the session returns it.
+/// Either way, the outcome is [EscrowOutcome::TimedOut].
+///
+/// The blocking `SequencedTransport` (`std` feature), and the async `AsyncDevice` (`tokio`
+/// feature), drive the session over a port with `run_escrow`.
+pub struct EscrowSession<F> {
+    poller: DevicePoller,
+    decide: F,
+    escrow_timeout_secs: u8,
+    state: EscrowState,
+    events: VecDeque<DeviceEvent>,
+    host_events: VecDeque<HostEvent>,
+    outcomes: VecDeque<EscrowOutcome>,
+}
+
+impl<F> EscrowSession<F>
+where
+    F: FnMut(&DocumentStatus) -> Option<EscrowDecision>,
+{
+    /// Creates a new [EscrowSession] with the provided [DevicePoller], and decision callback.
+    pub fn new(poller: DevicePoller, decide: F) -> Self {
+        Self {
+            poller,
+            decide,
+            escrow_timeout_secs: DEFAULT_ESCROW_TIMEOUT_SECS,
+            state: EscrowState::Idle,
+            events: VecDeque::new(),
+            host_events: VecDeque::new(),
+            outcomes: VecDeque::new(),
+        }
+    }
+
+    /// Gets the escrow timeout (seconds).
+    pub fn escrow_timeout_secs(&self) -> u8 {
+        self.escrow_timeout_secs
+    }
+
+    /// Sets the escrow timeout (seconds).
+    ///
+    /// Values are clamped to 1-127 seconds. A disabled timeout would leave an undecided document
+    /// in escrow indefinitely.
+    ///
+    /// Takes effect on the next call to [start](Self::start).
+    pub fn set_escrow_timeout_secs(&mut self, secs: u8) {
+        self.escrow_timeout_secs = secs.clamp(1, MAX_ESCROW_TIMEOUT_SECS);
+    }
+
+    /// Builder function to set the escrow timeout (seconds).
+    pub fn with_escrow_timeout_secs(mut self, secs: u8) -> Self {
+        self.set_escrow_timeout_secs(secs);
+        self
+    }
+
+    /// Gets a reference to the [DevicePoller].
+    pub fn poller(&self) -> &DevicePoller {
+        &self.poller
+    }
+
+    /// Gets a mutable reference to the [DevicePoller].
+    pub fn poller_mut(&mut self) -> &mut DevicePoller {
+        &mut self.poller
+    }
+
+    /// Gets whether a document is in escrow, and has not reached a final outcome.
+    pub fn in_progress(&self) -> bool {
+        self.state != EscrowState::Idle
+    }
+
+    /// Sends the escrow timeout to the device, and starts polling.
+    pub fn start(&mut self) {
+        let mut command = SetEscrowTimeoutCommand::new();
+        command.set_notes_timeout(self.escrow_timeout_secs);
+
+        self.poller
+            .handle_intent(HostIntent::Send(CommandVariant::SetEscrowTimeoutCommand(
+                command,
+            )));
+        self.poller.start();
+        self.process();
+    }
+
+    /// Stops polling.
+    ///
+    /// Any document in escrow is left for the device to return after the escrow timeout.
+    pub fn stop(&mut self) {
+        self.poller.stop();
+        self.process();
+    }
+
+    /// Makes a deferred decision for the document awaiting a decision.
+    ///
+    /// Returns `false` if no document is awaiting a decision.
+    pub fn decide(&mut self, decision: EscrowDecision) -> bool {
+        match self.state {
+            EscrowState::AwaitingDecision { document, .. } => {
+                self.apply(document, decision, false);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Handles bytes received from the device.
+    pub fn handle_input(&mut self, bytes: &[u8]) {
+        self.poller.handle_input(bytes);
+        self.process();
+    }
+
+    /// Handles the passage of time (milliseconds).
+    pub fn handle_elapsed(&mut self, elapsed_ms: u64) {
+        // decide before the poller advances, so the next poll carries the return bit
+        if let EscrowState::AwaitingDecision { document, deadline } = self.state {
+            if self.now_ms().saturating_add(elapsed_ms) >= deadline {
+                log::warn!("No escrow decision before the escrow timeout, returning document");
+                self.apply(document, EscrowDecision::Return, true);
+            }
+        }
+
+        self.poller.handle_elapsed(elapsed_ms);
+        self.process();
+    }
+
+    /// Gets the next message to write to the device, if any.
+    pub fn poll_transmit(&mut self) -> Option<Frame> {
+        self.poller.poll_transmit()
+    }
+
+    /// Gets the time until the next deadline (milliseconds), if any.
+    pub fn poll_timeout(&self) -> Option<u64> {
+        let poller_timeout = self.poller.poll_timeout();
+
+        match self.state {
+            EscrowState::AwaitingDecision { deadline, .. } => {
+                let decision_timeout = deadline.saturating_sub(self.now_ms());
+                Some(poller_timeout.map_or(decision_timeout, |t| t.min(decision_timeout)))
+            }
+            _ => poller_timeout,
+        }
+    }
+
+    /// Gets the next [EscrowOutcome], if any.
+    pub fn poll_outcome(&mut self) -> Option<EscrowOutcome> {
+        self.outcomes.pop_front()
+    }
+
+    /// Gets the next [DeviceEvent], if any.
+    pub fn poll_event(&mut self) -> Option<DeviceEvent> {
+        self.events.pop_front()
+    }
+
+    /// Gets the next [HostEvent], if any.
+    pub fn poll_host_event(&mut self) -> Option<HostEvent> {
+        self.host_events.pop_front()
+    }
+
+    fn now_ms(&self) -> u64 {
+        self.poller.protocol().now_ms()
+    }
+
+    fn apply(&mut self, document: DocumentStatus, decision: EscrowDecision, timed_out: bool) {
+        log::debug!("Escrow decision: {decision}, document: {document}");
+
+        self.state = match decision {
+            EscrowDecision::Stack => EscrowState::Stacking { document },
+            EscrowDecision::Return => EscrowState::Returning {
+                document,
+                timed_out,
+            },
+        };
+
+        self.drive();
+    }
+
+    // Re-asserts the decision on the next poll, while the document is still in escrow.
+    fn drive(&mut self) {
+        if !self.poller.detector().device_state().escrowed_state() {
+            return;
+        }
+
+        match self.state {
+            EscrowState::Stacking { .. } => self.poller.handle_intent(HostIntent::Stack),
+            EscrowState::Returning { .. } => self.poller.handle_intent(HostIntent::Return),
+            _ => (),
+        }
+    }
+
+    fn process(&mut self) {
+        while let Some(event) = self.poller.poll_event() {
+            self.handle_event(event);
+            self.events.push_back(event);
+        }
+
+        let mut replied = false;
+        while let Some(event) = self.poller.poll_host_event() {
+            replied |= matches!(event, HostEvent::Reply(_));
+            self.host_events.push_back(event);
+        }
+
+        // device events only fire on changes, so check each reply for a document still in escrow
+        if replied {
+            self.drive();
+        }
+    }
+
+    fn handle_event(&mut self, event: DeviceEvent) {
+        let state = self.state;
+
+        let outcome = match (state, event) {
+            (EscrowState::Idle, DeviceEvent::Escrowed(document)) => {
+                match (self.decide)(&document) {
+                    Some(decision) => self.apply(document, decision, false),
+                    None => {
+                        let deadline = self.now_ms().saturating_add(
+                            u64::from(self.escrow_timeout_secs) * 1000
+                                + self.poller.poll_interval_ms(),
+                        );
+                        self.state = EscrowState::AwaitingDecision { document, deadline };
+                    }
+                }
+                None
+            }
+            (EscrowState::Stacking { document }, DeviceEvent::Stacked(_)) => {
+                Some(EscrowOutcome::Stacked(document))
+            }
+            (EscrowState::Stacking { document }, DeviceEvent::Returned | DeviceEvent::Rejected) => {
+                log::warn!("Stack failed, document {event}: {document}");
+                Some(EscrowOutcome::StackFailed(document))
+            }
+            (
+                EscrowState::Returning {
+                    document,
+                    timed_out,
+                },
+                DeviceEvent::Returned | DeviceEvent::Rejected,
+            ) => {
+                if timed_out {
+                    Some(EscrowOutcome::TimedOut(document))
+                } else {
+                    Some(EscrowOutcome::Returned(document))
+                }
+            }
+            (EscrowState::Returning { document, .. }, DeviceEvent::Stacked(_)) => {
+                log::warn!("Return failed, document stacked: {document}");
+                Some(EscrowOutcome::Stacked(document))
+            }
+            (EscrowState::AwaitingDecision { document, .. }, DeviceEvent::Returned) => {
+                Some(EscrowOutcome::TimedOut(document))
+            }
+            (EscrowState::AwaitingDecision { document, .. }, DeviceEvent::Stacked(_)) => {
+                Some(EscrowOutcome::Stacked(document))
+            }
+            (EscrowState::AwaitingDecision { document, .. }, DeviceEvent::Rejected) => {
+                Some(EscrowOutcome::Rejected(document))
+            }
+            (
+                EscrowState::AwaitingDecision { document, .. }
+                | EscrowState::Stacking { document }
+                | EscrowState::Returning { document, .. },
+                DeviceEvent::Jammed
+                | DeviceEvent::Failure
+                | DeviceEvent::Cheated
+                | DeviceEvent::CashBoxRemoved
+                | DeviceEvent::PowerUp,
+            ) => Some(EscrowOutcome::Failed(document, event)),
+            _ => None,
+        };
+
+        if let Some(outcome) = outcome {
+            log::debug!("Escrow outcome: {outcome}");
+            self.state = EscrowState::Idle;
+            self.outcomes.push_back(outcome);
+        }
+    }
+}
+
+impl<F> HostMachine for EscrowSession<F>
+where
+    F: FnMut(&DocumentStatus) -> Option<EscrowDecision>,
+{
+    fn protocol(&self) -> &HostProtocol {
+        self.poller.protocol()
+    }
+
+    fn protocol_mut(&mut self) -> &mut HostProtocol {
+        self.poller.protocol_mut()
+    }
+
+    fn handle_input(&mut self, bytes: &[u8]) {
+        EscrowSession::handle_input(self, bytes)
+    }
+
+    fn handle_elapsed(&mut self, elapsed_ms: u64) {
+        EscrowSession::handle_elapsed(self, elapsed_ms)
+    }
+
+    fn poll_transmit(&mut self) -> Option<Frame> {
+        EscrowSession::poll_transmit(self)
+    }
+
+    fn poll_timeout(&self) -> Option<u64> {
+        EscrowSession::poll_timeout(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        DocumentReturn, DocumentStack, MessageOps, OmnibusCommand, OmnibusCommandOps, OmnibusReply,
+        OmnibusReplyOps, SetEscrowTimeoutReply,
+    };
+
+    const IDLE: u8 = 0b0000_0001;
+    const ESCROWED: u8 = 0b0000_0100;
+    const STACKING: u8 = 0b0000_1000;
+    const STACKED: u8 = 0b0001_0001;
+    const RETURNING: u8 = 0b0010_0000;
+    const RETURNED: u8 = 0b0100_0001;
+    const CASSETTE_ATTACHED: u8 = 0b0001_0000;
+    const REJECTED: u8 = 0b0000_0010;
+
+    // Starts the session, and replies to the escrow timeout command.
+    fn start<F>(session: &mut EscrowSession<F>) -> SetEscrowTimeoutCommand
+    where
+        F: FnMut(&DocumentStatus) -> Option<EscrowDecision>,
+    {
+        session.start();
+
+        let frame = session.poll_transmit().unwrap();
+        let command = match CommandVariant::try_from(frame.buf()).unwrap() {
+            CommandVariant::SetEscrowTimeoutCommand(command) => command,
+            command => panic!("unexpected command: {command}"),
+        };
+
+        let mut reply = SetEscrowTimeoutReply::new();
+        reply.set_acknak(frame.acknak());
+        reply.set_device_state(IDLE.into());
+        reply.set_device_status(CASSETTE_ATTACHED.into());
+        session.handle_input(reply.as_bytes());
+
+        command
+    }
+
+    // Replies to the next poll with the device state, and waits for the next poll.
+    fn exchange<F>(session: &mut EscrowSession<F>, device_state: u8) -> OmnibusCommand
+    where
+        F: FnMut(&DocumentStatus) -> Option<EscrowDecision>,
+    {
+        exchange_status(session, device_state, CASSETTE_ATTACHED)
+    }
+
+    // Replies to the next poll with the device state, and status, and waits for the next poll.
+    fn exchange_status<F>(
+        session: &mut EscrowSession<F>,
+        device_state: u8,
+        device_status: u8,
+    ) -> OmnibusCommand
+    where
+        F: FnMut(&DocumentStatus) -> Option<EscrowDecision>,
+    {
+        let frame = session.poll_transmit().unwrap();
+        let mut poll = OmnibusCommand::new();
+        poll.from_buf(frame.buf()).unwrap();
+
+        let mut reply = OmnibusReply::new();
+        reply.set_acknak(poll.acknak());
+        reply.set_device_state(device_state.into());
+        reply.set_device_status(device_status.into());
+        session.handle_input(reply.as_bytes());
+
+        let interval = session.poller().poll_interval_ms();
+        session.handle_elapsed(interval);
+
+        poll
+    }
+
+    #[test]
+    fn test_escrow_stack() {
+        let mut session = EscrowSession::new(DevicePoller::new(), |_: &DocumentStatus| {
+            Some(EscrowDecision::Stack)
+        })
+        .with_escrow_timeout_secs(10);
+
+        assert_eq!(start(&mut session).notes_timeout(), 10);
+
+        exchange(&mut session, IDLE);
+        exchange(&mut session, ESCROWED);
+        assert!(session.in_progress());
+
+        // stack bit is held until the document leaves escrow
+        let poll = exchange(&mut session, ESCROWED);
+        assert_eq!(poll.document_stack(), DocumentStack::Set);
+        let poll = exchange(&mut session, STACKING);
+        assert_eq!(poll.document_stack(), DocumentStack::Set);
+        let poll = exchange(&mut session, STACKED);
+        assert_eq!(poll.document_stack(), DocumentStack::Unset);
+
+        match session.poll_outcome() {
+            Some(EscrowOutcome::Stacked(_)) => (),
+            outcome => panic!("unexpected outcome: {outcome:?}"),
+        }
+        assert!(!session.in_progress());
+        assert_eq!(session.poll_outcome(), None);
+    }
+
+    #[test]
+    fn test_escrow_stack_failed() {
+        let mut session = EscrowSession::new(DevicePoller::new(), |_: &DocumentStatus| {
+            Some(EscrowDecision::Stack)
+        });
+
+        start(&mut session);
+
+        exchange(&mut session, ESCROWED);
+        exchange(&mut session, STACKING);
+        exchange(&mut session, RETURNING);
+        exchange(&mut session, RETURNED);
+
+        match session.poll_outcome() {
+            Some(EscrowOutcome::StackFailed(_)) => (),
+            outcome => panic!("unexpected outcome: {outcome:?}"),
+        }
+    }
+
+    #[test]
+    fn test_escrow_stack_rejected() {
+        let mut session = EscrowSession::new(DevicePoller::new(), |_: &DocumentStatus| {
+            Some(EscrowDecision::Stack)
+        });
+
+        start(&mut session);
+
+        exchange(&mut session, ESCROWED);
+        exchange(&mut session, STACKING);
+        exchange_status(&mut session, IDLE, CASSETTE_ATTACHED | REJECTED);
+
+        match session.poll_outcome() {
+            Some(EscrowOutcome::StackFailed(_)) => (),
+            outcome => panic!("unexpected outcome: {outcome:?}"),
+        }
+        assert!(!session.in_progress());
+
+        // the device returns the document straight from escrow
+        exchange(&mut session, ESCROWED);
+        exchange(&mut session, RETURNED);
+
+        match session.poll_outcome() {
+            Some(EscrowOutcome::StackFailed(_)) => (),
+            outcome => panic!("unexpected outcome: {outcome:?}"),
+        }
+        assert!(!session.in_progress());
+    }
+
+    #[test]
+    fn test_escrow_rejected_before_decision() {
+        let mut session = EscrowSession::new(DevicePoller::new(), |_: &DocumentStatus| None);
+
+        start(&mut session);
+
+        exchange(&mut session, ESCROWED);
+        exchange_status(&mut session, IDLE, CASSETTE_ATTACHED | REJECTED);
+
+        match session.poll_outcome() {
+            Some(EscrowOutcome::Rejected(_)) => (),
+            outcome => panic!("unexpected outcome: {outcome:?}"),
+        }
+        assert!(!session.decide(EscrowDecision::Stack));
+    }
+
+    #[test]
+    fn test_escrow_no_decision() {
+        let mut session = EscrowSession::new(DevicePoller::new(), |_: &DocumentStatus| None)
+            .with_escrow_timeout_secs(1);
+
+        start(&mut session);
+        exchange(&mut session, ESCROWED);
+
+        // device never times out the document, the session returns it
+        let mut polls = 0;
+        while exchange(&mut session, ESCROWED).document_return() == DocumentReturn::Unset {
+            polls += 1;
+            assert!(polls < 10, "document was never returned");
+        }
+
+        exchange(&mut session, RETURNED);
+
+        match session.poll_outcome() {
+            Some(EscrowOutcome::TimedOut(_)) => (),
+            outcome => panic!("unexpected outcome: {outcome:?}"),
+        }
+        assert!(!session.decide(EscrowDecision::Stack));
+    }
+}
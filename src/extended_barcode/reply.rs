@@ -0,0 +1,259 @@
+use crate::std;
+use std::fmt;
+
+use crate::{
+    impl_extended_ops, impl_message_ops, impl_omnibus_extended_reply, len::EXTENDED_BARCODE_REPLY,
+    DocumentStatus, DocumentType, Error, ExtendedCommand, ExtendedCommandOps, MessageOps,
+    MessageType, OmnibusReplyOps, Result,
+};
+
+/// Length of the barcode data field (in bytes).
+pub const BARCODE_LEN: usize = 24;
+/// Value of unused bytes in the barcode data field: `(`.
+pub const BARCODE_FILL: u8 = 0x28;
+
+pub mod index {
+    use super::BARCODE_LEN;
+
+    pub const BARCODE: usize = 10;
+    pub const BARCODE_END: usize = BARCODE + BARCODE_LEN;
+}
+
+/// A validated barcode voucher (e.g. a TITO ticket) read by the device.
+///
+/// The barcode is up to [BARCODE_LEN] printable ASCII characters.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BarcodeVoucher {
+    buf: [u8; BARCODE_LEN],
+    len: usize,
+}
+
+impl BarcodeVoucher {
+    /// Creates a new [BarcodeVoucher] from the barcode characters.
+    ///
+    /// Returns an error if the barcode is empty, longer than [BARCODE_LEN] characters, or contains
+    /// characters other than printable ASCII.
+    pub fn new(barcode: &str) -> Result<Self> {
+        let bytes = barcode.as_bytes();
+        let len = bytes.len();
+
+        if len == 0 {
+            return Err(Error::failure("empty barcode"));
+        }
+
+        if len > BARCODE_LEN {
+            return Err(Error::failure(format!(
+                "barcode too long, have: {len}, maximum: {BARCODE_LEN}"
+            )));
+        }
+
+        if let Some(b) = bytes.iter().find(|b| !b.is_ascii_graphic()) {
+            return Err(Error::failure(format!(
+                "invalid barcode character: 0x{b:02x}"
+            )));
+        }
+
+        let mut buf = [BARCODE_FILL; BARCODE_LEN];
+        buf[..len].copy_from_slice(bytes);
+
+        Ok(Self { buf, len })
+    }
+
+    /// Parses a [BarcodeVoucher] from the barcode data field of an [ExtendedBarcodeReply].
+    ///
+    /// Trailing fill bytes are removed before validation.
+    pub fn from_field(field: &[u8]) -> Result<Self> {
+        let field = &field[..field.len().min(BARCODE_LEN)];
+        let len = field
+            .iter()
+            .rposition(|&b| b != BARCODE_FILL)
+            .map_or(0, |p| p + 1);
+
+        let barcode = std::str::from_utf8(&field[..len])
+            .map_err(|_| Error::failure("invalid barcode characters"))?;
+
+        Self::new(barcode)
+    }
+
+    /// Gets the barcode as a string.
+    pub fn as_str(&self) -> &str {
+        // only printable ASCII is accepted on construction
+        std::str::from_utf8(&self.buf[..self.len]).unwrap_or_default()
+    }
+
+    /// Gets the number of barcode characters.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Gets whether the barcode is empty.
+    ///
+    /// Always false for a validated barcode.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+impl fmt::Display for BarcodeVoucher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Extended Barcode - Reply (Subtype 0x01)
+///
+/// | **S2K** | **CFSC** | **SC Adv** | **SCR** |
+/// |:-------:|:--------:|:----------:|:-------:|
+///
+/// When barcode vouchers are enabled (see [Barcode](crate::Barcode)), the device replies to an
+/// [OmnibusCommand](crate::OmnibusCommand) with an [ExtendedBarcodeReply] while a barcode voucher
+/// is escrowed, or being stacked or returned.
+///
+/// The barcode data field holds the barcode as ASCII characters, with unused bytes filled with
+/// `0x28` (`(`).
+///
+/// The Extended Barcode Reply is formatted as follows:
+///
+/// | Name  | STX  | LEN  | CTRL | Subtype | Data 0 | ... | Data 5 | Data 6 - Data 29 | ETX  | CHK |
+/// |:------|:----:|:----:|:----:|:-------:|:------:|:---:|:------:|:----------------:|:----:|:---:|
+/// | Byte  | 0    | 1    | 2    | 3       | 4      | ... | 9      | 10 - 33          | 34   | 35  |
+/// | Value | 0x02 | 0x24 | 0x7n | 0x01    | nn     | nn  | nn     | barcode          | 0x03 | zz  |
+///
+/// Data bytes 0 - 5 are the same as the [OmnibusReply](crate::OmnibusReply) data bytes.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ExtendedBarcodeReply {
+    buf: [u8; EXTENDED_BARCODE_REPLY],
+}
+
+impl ExtendedBarcodeReply {
+    /// Creates a new [ExtendedBarcodeReply].
+    pub fn new() -> Self {
+        let mut message = Self {
+            buf: [0u8; EXTENDED_BARCODE_REPLY],
+        };
+
+        message.init();
+        message.set_message_type(MessageType::Extended);
+        message.set_extended_command(ExtendedCommand::ExtendedBarcodeReply);
+        message.buf[index::BARCODE..index::BARCODE_END]
+            .copy_from_slice(&[BARCODE_FILL; BARCODE_LEN]);
+
+        message
+    }
+
+    /// Gets the raw barcode data field, including fill bytes.
+    pub fn barcode_field(&self) -> &[u8] {
+        self.buf[index::BARCODE..index::BARCODE_END].as_ref()
+    }
+
+    /// Gets the validated [BarcodeVoucher].
+    ///
+    /// Returns an error if the barcode data field is empty, or contains invalid characters.
+    pub fn barcode(&self) -> Result<BarcodeVoucher> {
+        BarcodeVoucher::from_field(self.barcode_field())
+    }
+
+    /// Sets the barcode data field.
+    pub fn set_barcode(&mut self, barcode: &BarcodeVoucher) {
+        self.buf[index::BARCODE..index::BARCODE_END].copy_from_slice(barcode.buf.as_ref());
+    }
+}
+
+impl Default for ExtendedBarcodeReply {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl_message_ops!(ExtendedBarcodeReply);
+impl_extended_ops!(ExtendedBarcodeReply);
+impl_omnibus_extended_reply!(ExtendedBarcodeReply);
+
+impl From<&ExtendedBarcodeReply> for DocumentStatus {
+    fn from(reply: &ExtendedBarcodeReply) -> Self {
+        match reply.barcode() {
+            Ok(barcode) => Self::default()
+                .with_document_type(DocumentType::Barcode)
+                .with_barcode(barcode),
+            Err(err) => {
+                log::warn!("Invalid barcode in extended barcode reply: {err}");
+                Self::default()
+            }
+        }
+    }
+}
+
+impl fmt::Display for ExtendedBarcodeReply {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "AckNak: {}, DeviceType: {}, MessageType: {}, Subtype: {}, DeviceState: {}, DeviceStatus: {}, ExceptionStatus: {}, MiscDeviceState: {}, ModelNumber: {}, CodeRevision: {}, Barcode: ",
+            self.acknak(),
+            self.device_type(),
+            self.message_type(),
+            self.extended_command(),
+            self.device_state(),
+            self.device_status(),
+            self.exception_status(),
+            self.misc_device_state(),
+            self.model_number(),
+            self.code_revision(),
+        )?;
+
+        match self.barcode() {
+            Ok(barcode) => write!(f, "{barcode}"),
+            Err(_) => write!(f, "{:x?}", self.barcode_field()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[rustfmt::skip]
+    fn test_extended_barcode_reply_from_buf() -> Result<()> {
+        let msg_bytes = [
+            // STX | LEN | Message type | Subtype
+            0x02, 0x24, 0x70, 0x01,
+            // Data (escrowed)
+            0x04, 0x10, 0x00, 0x00, 0x00, 0x00,
+            // Barcode
+            b'1', b'2', b'3', b'4', b'5', b'6', b'7', b'8', b'9', b'0', b'1', b'2',
+            b'3', b'4', b'5', b'6', b'7', b'8', 0x28, 0x28, 0x28, 0x28, 0x28, 0x28,
+            // ETX | Checksum
+            0x03, 0x48,
+        ];
+
+        let mut msg = ExtendedBarcodeReply::new();
+        msg.from_buf(msg_bytes.as_ref())?;
+
+        assert_eq!(msg.message_type(), MessageType::Extended);
+        assert_eq!(msg.extended_command(), ExtendedCommand::ExtendedBarcodeReply);
+        assert!(msg.device_state().escrowed_state());
+        assert_eq!(msg.barcode()?.as_str(), "123456789012345678");
+
+        let document = DocumentStatus::from(&msg);
+        assert_eq!(document.document_type(), DocumentType::Barcode);
+        assert_eq!(document.barcode(), Some(&BarcodeVoucher::new("123456789012345678")?));
+
+        let mut reply = ExtendedBarcodeReply::new();
+        reply.set_device_state(msg.device_state());
+        reply.set_device_status(msg.device_status());
+        reply.set_barcode(&msg.barcode()?);
+        assert_eq!(reply.as_bytes(), msg_bytes.as_ref());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_barcode_validation() {
+        assert!(BarcodeVoucher::new("").is_err());
+        assert!(BarcodeVoucher::new("1234567890123456789012345").is_err());
+        assert!(BarcodeVoucher::new("1234 5678").is_err());
+        assert!(BarcodeVoucher::from_field(&[BARCODE_FILL; BARCODE_LEN]).is_err());
+        assert!(BarcodeVoucher::from_field(&[0xff; BARCODE_LEN]).is_err());
+    }
+}
@@ -0,0 +1,3 @@
+pub(crate) mod reply;
+
+pub use reply::*;
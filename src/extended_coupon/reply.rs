@@ -0,0 +1,218 @@
+use crate::std;
+use std::fmt;
+
+use crate::{
+    impl_extended_ops, impl_message_ops, impl_omnibus_extended_reply, len::EXTENDED_COUPON_REPLY,
+    seven_bit_u16, u16_seven_bit, DocumentStatus, DocumentType, ExtendedCommand,
+    ExtendedCommandOps, MessageOps, MessageType, OmnibusReplyOps,
+};
+
+pub mod index {
+    pub const VALUE: usize = 10;
+    pub const VALUE_END: usize = VALUE + 4;
+    pub const OWNER_ID: usize = 14;
+}
+
+/// Maximum owner ID of a generic coupon.
+pub const MAX_COUPON_OWNER_ID: u8 = 0xf;
+
+/// A generic coupon read by the device, see [ExtendedCouponReply].
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Coupon {
+    value: u16,
+    owner_id: u8,
+}
+
+impl Coupon {
+    /// Creates a new [Coupon].
+    ///
+    /// The owner ID is masked to the range 0 - [MAX_COUPON_OWNER_ID].
+    pub const fn new(value: u16, owner_id: u8) -> Self {
+        Self {
+            value,
+            owner_id: owner_id & MAX_COUPON_OWNER_ID,
+        }
+    }
+
+    /// Gets the coupon value.
+    ///
+    /// The value is in whole units of the device currency. A value of zero is a free vend coupon.
+    pub const fn value(&self) -> u16 {
+        self.value
+    }
+
+    /// Gets the coupon owner ID.
+    pub const fn owner_id(&self) -> u8 {
+        self.owner_id
+    }
+
+    /// Gets whether the coupon is a free vend coupon.
+    pub const fn is_free_vend(&self) -> bool {
+        self.value == 0
+    }
+}
+
+impl fmt::Display for Coupon {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Value: {}, OwnerId: {}", self.value, self.owner_id)
+    }
+}
+
+/// Extended Coupon - Reply (Subtype 0x04)
+///
+/// | **S2K** | **CFSC** | **SC Adv** | **SCR** |
+/// |:-------:|:--------:|:----------:|:-------:|
+///
+/// When extended coupon reporting is enabled (see
+/// [ExtendedCouponReporting](crate::ExtendedCouponReporting)), the device replies to an
+/// [OmnibusCommand](crate::OmnibusCommand) with an [ExtendedCouponReply] while a generic coupon is
+/// escrowed, or stacked.
+///
+/// The reply shares subtype 0x04 with the [SetEscrowTimeoutReply](crate::SetEscrowTimeoutReply),
+/// and is distinguished by its length.
+///
+/// The Extended Coupon Reply is formatted as follows:
+///
+/// | Name  | STX  | LEN  | CTRL | Subtype | Data 0 | ... | Data 5 | Data 6 - Data 9 | Data 10  | ETX  | CHK |
+/// |:------|:----:|:----:|:----:|:-------:|:------:|:---:|:------:|:---------------:|:--------:|:----:|:---:|
+/// | Byte  | 0    | 1    | 2    | 3       | 4      | ... | 9      | 10 - 13         | 14       | 15   | 16  |
+/// | Value | 0x02 | 0x11 | 0x7n | 0x04    | nn     | nn  | nn     | value           | owner ID | 0x03 | zz  |
+///
+/// Data bytes 0 - 5 are the same as the [OmnibusReply](crate::OmnibusReply) data bytes.
+///
+/// The coupon value is a 16-bit number, sent as four nibbles (most significant first), one in the
+/// lower nibble of each byte. The owner ID is sent in the lower nibble of data byte 10.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ExtendedCouponReply {
+    buf: [u8; EXTENDED_COUPON_REPLY],
+}
+
+impl ExtendedCouponReply {
+    /// Creates a new [ExtendedCouponReply].
+    pub fn new() -> Self {
+        let mut message = Self {
+            buf: [0u8; EXTENDED_COUPON_REPLY],
+        };
+
+        message.init();
+        message.set_message_type(MessageType::Extended);
+        message.set_extended_command(ExtendedCommand::SetEscrowTimeout);
+
+        message
+    }
+
+    /// Gets the coupon value.
+    pub fn coupon_value(&self) -> u16 {
+        seven_bit_u16(self.buf[index::VALUE..index::VALUE_END].as_ref())
+    }
+
+    /// Sets the coupon value.
+    pub fn set_coupon_value(&mut self, value: u16) {
+        self.buf[index::VALUE..index::VALUE_END].copy_from_slice(u16_seven_bit(value).as_ref());
+    }
+
+    /// Gets the coupon owner ID.
+    pub fn owner_id(&self) -> u8 {
+        self.buf[index::OWNER_ID] & MAX_COUPON_OWNER_ID
+    }
+
+    /// Sets the coupon owner ID.
+    pub fn set_owner_id(&mut self, owner_id: u8) {
+        self.buf[index::OWNER_ID] = owner_id & MAX_COUPON_OWNER_ID;
+    }
+
+    /// Gets the [Coupon].
+    pub fn coupon(&self) -> Coupon {
+        Coupon::new(self.coupon_value(), self.owner_id())
+    }
+
+    /// Sets the [Coupon].
+    pub fn set_coupon(&mut self, coupon: Coupon) {
+        self.set_coupon_value(coupon.value());
+        self.set_owner_id(coupon.owner_id());
+    }
+}
+
+impl Default for ExtendedCouponReply {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl_message_ops!(ExtendedCouponReply);
+impl_extended_ops!(ExtendedCouponReply);
+impl_omnibus_extended_reply!(ExtendedCouponReply);
+
+impl From<&ExtendedCouponReply> for DocumentStatus {
+    fn from(reply: &ExtendedCouponReply) -> Self {
+        Self::default()
+            .with_document_type(DocumentType::Coupon)
+            .with_coupon(reply.coupon())
+    }
+}
+
+impl fmt::Display for ExtendedCouponReply {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "AckNak: {}, DeviceType: {}, MessageType: {}, Subtype: {}, DeviceState: {}, DeviceStatus: {}, ExceptionStatus: {}, MiscDeviceState: {}, ModelNumber: {}, CodeRevision: {}, Coupon: {}",
+            self.acknak(),
+            self.device_type(),
+            self.message_type(),
+            self.extended_command(),
+            self.device_state(),
+            self.device_status(),
+            self.exception_status(),
+            self.misc_device_state(),
+            self.model_number(),
+            self.code_revision(),
+            self.coupon(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ReplyVariant, Result, SetEscrowTimeoutReply};
+
+    #[test]
+    #[rustfmt::skip]
+    fn test_extended_coupon_reply_from_buf() -> Result<()> {
+        let msg_bytes = [
+            // STX | LEN | Message type | Subtype
+            0x02, 0x11, 0x70, 0x04,
+            // Data (escrowed)
+            0x04, 0x10, 0x00, 0x00, 0x00, 0x00,
+            // Value | Owner ID
+            0x00, 0x00, 0x01, 0x04, 0x03,
+            // ETX | Checksum
+            0x03, 0x77,
+        ];
+
+        let mut msg = ExtendedCouponReply::new();
+        msg.from_buf(msg_bytes.as_ref())?;
+
+        assert_eq!(msg.message_type(), MessageType::Extended);
+        assert_eq!(msg.extended_command(), ExtendedCommand::SetEscrowTimeout);
+        assert_eq!(msg.coupon(), Coupon::new(20, 3));
+
+        let document = DocumentStatus::from(&msg);
+        assert_eq!(document.document_type(), DocumentType::Coupon);
+        assert_eq!(document.coupon(), Some(Coupon::new(20, 3)));
+
+        let mut reply = ExtendedCouponReply::new();
+        reply.set_device_state(msg.device_state());
+        reply.set_device_status(msg.device_status());
+        reply.set_coupon(Coupon::new(20, 3));
+        assert_eq!(reply.as_bytes(), msg_bytes.as_ref());
+
+        // subtype 0x04 replies are told apart by length
+        assert!(ReplyVariant::from_buf(msg_bytes.as_ref())?.is_extended_coupon_reply());
+        assert!(ReplyVariant::from_buf(SetEscrowTimeoutReply::new().as_bytes())?
+            .is_set_escrow_timeout_reply());
+
+        Ok(())
+    }
+}
@@ -53,10 +53,12 @@ impl From<&ExtendedNoteReply> for DocumentStatus {
 
         match reply.banknote_classification() {
             BanknoteClassification::Genuine | BanknoteClassification::DisabledOrNotSupported => {
-                status.with_accepted_note_table_item(AcceptedNoteTableItem::new(
-                    reply.into(),
-                    reply.orientation(),
-                ))
+                status
+                    .with_accepted_note_table_item(AcceptedNoteTableItem::new(
+                        reply.into(),
+                        reply.orientation(),
+                    ))
+                    .with_document_type(DocumentType::Banknote)
             }
             _ => status,
         }
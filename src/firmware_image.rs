@@ -0,0 +1,311 @@
+use alloc::{string::String, vec::Vec};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::std;
+use std::fmt;
+
+use crate::{
+    download_packet_count, len::FLASH_DATA_PACKET, ApplicationPartNumber, Error,
+    FirmwareDownloader, Result, SerialProtocol,
+};
+
+/// Length of a SHA-256 digest (in bytes).
+pub const SHA256_LEN: usize = 32;
+
+/// A firmware image, validated against the flash download length rules.
+///
+/// The image must be a non-empty multiple of 32 bytes (see
+/// [FlashDownloadMessage7bit](crate::FlashDownloadMessage7bit)). The 8-bit protocol sends 64-byte
+/// packets, with a final 32-byte packet for images that are an odd multiple of 32 bytes.
+///
+/// The SHA-256 digest of the image is computed on load, for comparison against a
+/// [FirmwareManifest].
+#[derive(Clone, Debug, PartialEq)]
+pub struct FirmwareImage {
+    data: Vec<u8>,
+    sha256: [u8; SHA256_LEN],
+}
+
+impl FirmwareImage {
+    /// Creates a new [FirmwareImage] from the raw image bytes.
+    ///
+    /// Returns an error if the image is empty, or not a multiple of 32 bytes.
+    pub fn new(data: Vec<u8>) -> Result<Self> {
+        if data.is_empty() {
+            return Err(Error::failure("empty firmware image"));
+        }
+
+        if !data.len().is_multiple_of(FLASH_DATA_PACKET) {
+            return Err(Error::failure(format!(
+                "firmware image length must be a multiple of {FLASH_DATA_PACKET} bytes, have: {}",
+                data.len()
+            )));
+        }
+
+        let sha256 = Sha256::digest(&data).into();
+
+        Ok(Self { data, sha256 })
+    }
+
+    /// Loads a [FirmwareImage] from the file at the provided path.
+    #[cfg(feature = "std")]
+    pub fn open<P: AsRef<std::path::Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let data = std::fs::read(path)?;
+
+        log::debug!(
+            "Loaded firmware image: {}, length: {}",
+            path.display(),
+            data.len()
+        );
+
+        Self::new(data)
+    }
+
+    /// Gets a reference to the image bytes.
+    pub fn data(&self) -> &[u8] {
+        self.data.as_ref()
+    }
+
+    /// Gets the length of the image (in bytes).
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Gets whether the image is empty.
+    ///
+    /// Always false for a validated image.
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Gets the SHA-256 digest of the image.
+    pub fn sha256(&self) -> &[u8; SHA256_LEN] {
+        &self.sha256
+    }
+
+    /// Gets the SHA-256 digest of the image as a lowercase hex string.
+    pub fn sha256_hex(&self) -> String {
+        sha256_hex(&self.sha256)
+    }
+
+    /// Gets the number of flash download packets for the [SerialProtocol].
+    ///
+    /// Returns an error if the image is too large for the protocol.
+    pub fn packet_count(&self, protocol: SerialProtocol) -> Result<u16> {
+        download_packet_count(self.len(), protocol)
+    }
+
+    /// Creates a [FirmwareDownloader] for the image with the [SerialProtocol].
+    pub fn downloader(&self, protocol: SerialProtocol) -> Result<FirmwareDownloader<'_>> {
+        FirmwareDownloader::new(self.data(), protocol)
+    }
+}
+
+pub(crate) fn sha256_hex(digest: &[u8]) -> String {
+    digest.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+impl fmt::Display for FirmwareImage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Length: {}, SHA-256: {}", self.len(), self.sha256_hex())
+    }
+}
+
+/// An expected firmware image listed in a [FirmwareManifest].
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct FirmwareManifestEntry {
+    part_number: String,
+    sha256: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    length: Option<usize>,
+}
+
+impl FirmwareManifestEntry {
+    /// Creates a new [FirmwareManifestEntry].
+    pub fn new(part_number: &str, sha256: &str) -> Self {
+        Self {
+            part_number: part_number.into(),
+            sha256: sha256.into(),
+            length: None,
+        }
+    }
+
+    /// Gets the application part number string, e.g. `286101103`.
+    pub fn part_number(&self) -> &str {
+        self.part_number.as_str()
+    }
+
+    /// Gets the application part number as an [ApplicationPartNumber].
+    pub fn application_part_number(&self) -> ApplicationPartNumber {
+        self.part_number.as_bytes().into()
+    }
+
+    /// Gets the expected SHA-256 digest as a hex string.
+    pub fn sha256(&self) -> &str {
+        self.sha256.as_str()
+    }
+
+    /// Gets the expected image length (in bytes), if listed.
+    pub fn length(&self) -> Option<usize> {
+        self.length
+    }
+
+    /// Builder function to set the expected image length (in bytes).
+    pub fn with_length(mut self, length: usize) -> Self {
+        self.length = Some(length);
+        self
+    }
+
+    /// Gets whether the [FirmwareImage] matches the entry's digest, and length (if listed).
+    pub fn matches(&self, image: &FirmwareImage) -> bool {
+        self.sha256
+            .eq_ignore_ascii_case(image.sha256_hex().as_str())
+            && self.length.map(|l| l == image.len()).unwrap_or(true)
+    }
+}
+
+impl fmt::Display for FirmwareManifestEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "PartNumber: {}, SHA-256: {}",
+            self.part_number, self.sha256
+        )?;
+
+        if let Some(length) = self.length {
+            write!(f, ", Length: {length}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A manifest of expected firmware images, used to gate flashing of the wrong image.
+///
+/// The manifest is a JSON document listing application part numbers, and SHA-256 digests:
+///
+/// ```json
+/// {
+///   "images": [
+///     { "part_number": "286101103", "sha256": "5f70bf18...", "length": 2097152 }
+///   ]
+/// }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+pub struct FirmwareManifest {
+    images: Vec<FirmwareManifestEntry>,
+}
+
+impl FirmwareManifest {
+    /// Creates a new [FirmwareManifest] from a list of entries.
+    pub fn new(images: Vec<FirmwareManifestEntry>) -> Self {
+        Self { images }
+    }
+
+    /// Parses a [FirmwareManifest] from a JSON string.
+    pub fn from_json(json: &str) -> Result<Self> {
+        serde_json::from_str(json)
+            .map_err(|err| Error::failure(format!("invalid firmware manifest: {err}")))
+    }
+
+    /// Loads a [FirmwareManifest] from the JSON file at the provided path.
+    #[cfg(feature = "std")]
+    pub fn open<P: AsRef<std::path::Path>>(path: P) -> Result<Self> {
+        Self::from_json(std::fs::read_to_string(path)?.as_str())
+    }
+
+    /// Gets the list of manifest entries.
+    pub fn images(&self) -> &[FirmwareManifestEntry] {
+        self.images.as_ref()
+    }
+
+    /// Gets the manifest entry for the part number, if any.
+    pub fn find(&self, part_number: &str) -> Option<&FirmwareManifestEntry> {
+        self.images.iter().find(|e| e.part_number == part_number)
+    }
+
+    /// Gets the manifest entry matching the [FirmwareImage], if any.
+    pub fn find_image(&self, image: &FirmwareImage) -> Option<&FirmwareManifestEntry> {
+        self.images.iter().find(|e| e.matches(image))
+    }
+
+    /// Verifies the [FirmwareImage] is the expected image for the part number.
+    ///
+    /// Returns an error if the part number is not listed, or the image does not match the listed
+    /// digest and length.
+    pub fn verify(
+        &self,
+        image: &FirmwareImage,
+        part_number: &str,
+    ) -> Result<&FirmwareManifestEntry> {
+        let entry = self.find(part_number).ok_or_else(|| {
+            Error::failure(format!(
+                "part number not in the firmware manifest: {part_number}"
+            ))
+        })?;
+
+        if entry.matches(image) {
+            Ok(entry)
+        } else {
+            Err(Error::failure(format!(
+                "firmware image does not match the manifest for part number {part_number}, have: {image}, expected: {entry}"
+            )))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    #[test]
+    fn test_image_length_rules() {
+        assert!(FirmwareImage::new(vec![]).is_err());
+        assert!(FirmwareImage::new(vec![0u8; 48]).is_err());
+
+        let image = FirmwareImage::new(vec![0u8; 96]).unwrap();
+        assert_eq!(image.packet_count(SerialProtocol::_7bit), Ok(3));
+        assert_eq!(image.packet_count(SerialProtocol::_8bit), Ok(2));
+        assert_eq!(
+            image.sha256_hex(),
+            "2ea9ab9198d1638007400cd2c3bef1cc745b864b76011a0e1bc52180ac6452d4"
+        );
+    }
+
+    #[test]
+    fn test_manifest_verify() {
+        let image = FirmwareImage::new(vec![0u8; 96]).unwrap();
+
+        let manifest = FirmwareManifest::from_json(
+            r#"{
+                "images": [
+                    {
+                        "part_number": "286101103",
+                        "sha256": "2EA9AB9198D1638007400CD2C3BEF1CC745B864B76011A0E1BC52180AC6452D4",
+                        "length": 96
+                    },
+                    {
+                        "part_number": "286101104",
+                        "sha256": "0000000000000000000000000000000000000000000000000000000000000000"
+                    }
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let entry = manifest.verify(&image, "286101103").unwrap();
+        assert_eq!(
+            entry.application_part_number(),
+            ApplicationPartNumber::from(b"286101103".as_ref())
+        );
+        assert_eq!(manifest.find_image(&image), Some(entry));
+
+        assert!(manifest.verify(&image, "286101104").is_err());
+        assert!(manifest.verify(&image, "286101105").is_err());
+        assert!(FirmwareManifest::from_json("{}").is_err());
+    }
+}
@@ -1,6 +1,13 @@
+use serde::{Deserialize, Serialize};
+
+use crate::std;
+use std::fmt;
+
 use crate::MessageOps;
 
 mod baud_rate;
+mod download_record;
+mod downloader;
 mod message_7bit;
 mod message_8bit;
 mod reply_7bit;
@@ -10,6 +17,8 @@ mod start_download;
 pub use baud_rate::{
     index as baud_rate_index, BaudRate, BaudRateChangeReply, BaudRateChangeRequest,
 };
+pub use download_record::*;
+pub use downloader::*;
 pub use message_7bit::{index as message_7bit_index, FlashDownloadMessage7bit};
 pub use message_8bit::{
     index as message_8bit_index, FlashDownloadMessage8bit_32, FlashDownloadMessage8bit_64,
@@ -35,22 +44,16 @@ pub trait FlashDownloadMessage<const DATA_LEN: usize>: MessageOps {
     /// Increments the packet number by one.
     ///
     /// If the packet number reaches u16::MAX (65_535), any additional increments will overflow,
-    /// starting the count back at zero.
-    ///
-    /// It isn't clear that is what the vendor intends, but that is the behavior in C.
+    /// starting the count back at zero (matching the behavior in C).
     ///
-    /// Without overflow, this limits the firmware size to:
+    /// A packet number of `0xffff` is reserved for the power loss reply (see
+    /// [FlashDownloadReply::power_loss]), so the [FirmwareDownloader] rejects any image needing
+    /// more than [MAX_DOWNLOAD_PACKETS] packets. This limits the firmware size to:
     ///
     /// * 8-bit protocol: ~4MB (4_194_240 = 65_535 * 64)
     /// * 7-bit protocol: ~2MB (2_097_120 = 65_535 * 32)
     fn increment_packet_number(&mut self) -> u16 {
-        // FIXME: the desired behavior of an increment past the max is unclear.
-        //
-        // C behavior is to overflow, restarting at 0, but it isn't obvious that's what CPI
-        // intends.
-        //
-        // FWIW their firmware files all appear to be below the limit.
-        let packet_number = self.packet_number().overflowing_add(1).0;
+        let packet_number = self.packet_number().wrapping_add(1);
         self.set_packet_number(packet_number);
         packet_number
     }
@@ -102,10 +105,31 @@ pub trait FlashDownloadReply: MessageOps {
 }
 
 #[repr(u8)]
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
 pub enum SerialProtocol {
     /// 7-bit serial protocol, 7 data bits, Parity: even, 1 stop bits
     _7bit,
     /// 8-bit serial protocol, 8 data bits, Parity: none, 1 stop bits
     _8bit,
 }
+
+impl From<SerialProtocol> for &'static str {
+    fn from(p: SerialProtocol) -> Self {
+        match p {
+            SerialProtocol::_7bit => "7-bit",
+            SerialProtocol::_8bit => "8-bit",
+        }
+    }
+}
+
+impl From<&SerialProtocol> for &'static str {
+    fn from(p: &SerialProtocol) -> Self {
+        (*p).into()
+    }
+}
+
+impl fmt::Display for SerialProtocol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", <&'static str>::from(self))
+    }
+}
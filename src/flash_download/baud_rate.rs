@@ -1,3 +1,5 @@
+use serde::{Deserialize, Serialize};
+
 use crate::std;
 use std::fmt;
 
@@ -15,7 +17,7 @@ pub mod index {
 
 /// Represents the acceptable values for host-device serial baud rates.
 #[repr(u8)]
-#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize, Serialize)]
 pub enum BaudRate {
     #[default]
     _9600 = 0x01,
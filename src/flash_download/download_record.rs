@@ -0,0 +1,144 @@
+use alloc::string::String;
+
+use serde::{Deserialize, Serialize};
+
+use crate::std;
+use std::fmt;
+
+use crate::{BaudRate, Error, Result, SerialProtocol};
+
+/// Number of acknowledged packets between [DownloadRecord] saves during a resumable download.
+///
+/// The record is only a hint for [FirmwareDownloader::resume](crate::FirmwareDownloader::resume),
+/// so a record lagging the device by a few packets costs little, and avoids a file write per
+/// packet.
+pub const DOWNLOAD_RECORD_INTERVAL: u16 = 32;
+
+/// Persistent progress record of a firmware download.
+///
+/// Saved by the host as packets are acknowledged, so a download interrupted by a host restart can
+/// resume with [FirmwareDownloader::resume](crate::FirmwareDownloader::resume), instead of
+/// restarting from the first packet.
+///
+/// The record is stored as JSON:
+///
+/// ```json
+/// { "sha256": "2ea9ab91...", "protocol": "_8bit", "last_acked_packet": 41, "baud_rate": "_38400" }
+/// ```
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct DownloadRecord {
+    sha256: String,
+    protocol: SerialProtocol,
+    last_acked_packet: Option<u16>,
+    baud_rate: BaudRate,
+}
+
+impl DownloadRecord {
+    /// Creates a new [DownloadRecord].
+    pub fn new(
+        sha256: &str,
+        protocol: SerialProtocol,
+        last_acked_packet: Option<u16>,
+        baud_rate: BaudRate,
+    ) -> Self {
+        Self {
+            sha256: sha256.into(),
+            protocol,
+            last_acked_packet,
+            baud_rate,
+        }
+    }
+
+    /// Gets the SHA-256 digest of the firmware image, as a hex string.
+    pub fn sha256(&self) -> &str {
+        self.sha256.as_str()
+    }
+
+    /// Gets the [SerialProtocol] used for image packets.
+    pub fn protocol(&self) -> SerialProtocol {
+        self.protocol
+    }
+
+    /// Gets the last packet acknowledged by the device, if any.
+    pub fn last_acked_packet(&self) -> Option<u16> {
+        self.last_acked_packet
+    }
+
+    /// Gets the [BaudRate] negotiated for an 8-bit download.
+    pub fn baud_rate(&self) -> BaudRate {
+        self.baud_rate
+    }
+
+    /// Parses a [DownloadRecord] from a JSON string.
+    pub fn from_json(json: &str) -> Result<Self> {
+        serde_json::from_str(json)
+            .map_err(|err| Error::failure(format!("invalid download record: {err}")))
+    }
+
+    /// Serializes the [DownloadRecord] to a JSON string.
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string(self)
+            .map_err(|err| Error::failure(format!("invalid download record: {err}")))
+    }
+
+    /// Loads a [DownloadRecord] from the file at the provided path.
+    ///
+    /// Returns `Ok(None)` if the file does not exist.
+    #[cfg(feature = "std")]
+    pub fn load<P: AsRef<std::path::Path>>(path: P) -> Result<Option<Self>> {
+        match std::fs::read_to_string(path) {
+            Ok(json) => Self::from_json(json.as_str()).map(Some),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Saves the [DownloadRecord] to the file at the provided path.
+    ///
+    /// The record is written to a temporary file, and renamed over the previous record, so a crash
+    /// mid-write leaves the previous record intact.
+    #[cfg(feature = "std")]
+    pub fn save<P: AsRef<std::path::Path>>(&self, path: P) -> Result<()> {
+        let path = path.as_ref();
+        let tmp_path = path.with_extension("tmp");
+
+        std::fs::write(&tmp_path, self.to_json()?)?;
+        std::fs::rename(&tmp_path, path)?;
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for DownloadRecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "SHA-256: {}, Protocol: {}, LastAckedPacket: {:?}, BaudRate: {}",
+            self.sha256, self.protocol, self.last_acked_packet, self.baud_rate
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_download_record_json() -> Result<()> {
+        let record = DownloadRecord::new(
+            "2ea9ab91",
+            SerialProtocol::_8bit,
+            Some(41),
+            BaudRate::_38400,
+        );
+
+        let json = record.to_json()?;
+        assert_eq!(
+            json,
+            r#"{"sha256":"2ea9ab91","protocol":"_8bit","last_acked_packet":41,"baud_rate":"_38400"}"#
+        );
+        assert_eq!(DownloadRecord::from_json(json.as_str())?, record);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,873 @@
+use alloc::{collections::VecDeque, string::String};
+
+use sha2::{Digest, Sha256};
+
+use crate::std;
+use std::fmt;
+
+use crate::{
+    len::{FLASH_DATA_PACKET, FLASH_DATA_PACKET_64},
+    sha256_hex, BaudRate, BaudRateChangeRequest, CommandVariant, DownloadReady, DownloadRecord,
+    Error, HostEvent, HostIntent, HostSession, ReplyVariant, Result, StartDownloadCommand,
+    DEFAULT_MAX_RETRIES, SHA256_LEN,
+};
+
+use super::{
+    FlashDownloadMessage, FlashDownloadMessage7bit, FlashDownloadMessage8bit_32,
+    FlashDownloadMessage8bit_64, FlashDownloadReply, SerialProtocol,
+};
+
+/// Maximum number of packets in a firmware download.
+///
+/// Packet numbers are 16-bit, and `0xffff` is reserved for the power loss reply.
+pub const MAX_DOWNLOAD_PACKETS: usize = 0xffff;
+
+/// Gets the number of image bytes sent in each packet for the [SerialProtocol].
+///
+/// The final 8-bit packet may only contain 32 bytes, see [FlashDownloadMessage8bit_32].
+pub const fn download_packet_len(protocol: SerialProtocol) -> usize {
+    match protocol {
+        SerialProtocol::_7bit => FLASH_DATA_PACKET,
+        SerialProtocol::_8bit => FLASH_DATA_PACKET_64,
+    }
+}
+
+/// Gets the maximum firmware image length for the [SerialProtocol].
+pub const fn max_download_len(protocol: SerialProtocol) -> usize {
+    MAX_DOWNLOAD_PACKETS * download_packet_len(protocol)
+}
+
+/// Gets the number of packets needed to download an image of `image_len` bytes.
+///
+/// Returns an error if the image is empty, is not a multiple of 32 bytes, or needs more than
+/// [MAX_DOWNLOAD_PACKETS] packets with the [SerialProtocol].
+pub fn download_packet_count(image_len: usize, protocol: SerialProtocol) -> Result<u16> {
+    if image_len == 0 {
+        return Err(Error::failure("empty firmware image"));
+    }
+
+    if !image_len.is_multiple_of(FLASH_DATA_PACKET) {
+        return Err(Error::failure(format!(
+            "firmware image length must be a multiple of {FLASH_DATA_PACKET} bytes, have: {image_len}"
+        )));
+    }
+
+    let max_len = max_download_len(protocol);
+    if image_len > max_len {
+        return Err(Error::failure(format!(
+            "firmware image too large for the {protocol} protocol, have: {image_len}, max: {max_len}"
+        )));
+    }
+
+    let packet_len = download_packet_len(protocol);
+
+    Ok(image_len.div_ceil(packet_len) as u16)
+}
+
+/// Phases of a firmware download.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DownloadPhase {
+    /// Waiting for the device to enter download mode.
+    Start,
+    /// Negotiating the fast serial download baud rate.
+    BaudRateChange,
+    /// Sending image packets.
+    Download,
+    /// The device acknowledged the final packet.
+    Complete,
+    /// The download failed, see [DownloadEvent::Failed].
+    Failed,
+}
+
+impl From<DownloadPhase> for &'static str {
+    fn from(p: DownloadPhase) -> Self {
+        match p {
+            DownloadPhase::Start => "Start",
+            DownloadPhase::BaudRateChange => "BaudRateChange",
+            DownloadPhase::Download => "Download",
+            DownloadPhase::Complete => "Complete",
+            DownloadPhase::Failed => "Failed",
+        }
+    }
+}
+
+impl From<&DownloadPhase> for &'static str {
+    fn from(p: &DownloadPhase) -> Self {
+        (*p).into()
+    }
+}
+
+impl fmt::Display for DownloadPhase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", <&'static str>::from(self))
+    }
+}
+
+/// Progress of the packet phase of a firmware download.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DownloadProgress {
+    packets_acked: u16,
+    total_packets: u16,
+    restarts: u32,
+}
+
+impl DownloadProgress {
+    /// Gets the number of packets acknowledged by the device.
+    pub fn packets_acked(&self) -> u16 {
+        self.packets_acked
+    }
+
+    /// Gets the total number of packets in the image.
+    pub fn total_packets(&self) -> u16 {
+        self.total_packets
+    }
+
+    /// Gets the number of times the download restarted from the first packet.
+    pub fn restarts(&self) -> u32 {
+        self.restarts
+    }
+
+    /// Gets the download progress as a percentage.
+    pub fn percent(&self) -> u8 {
+        ((self.packets_acked as u32 * 100) / self.total_packets.max(1) as u32) as u8
+    }
+}
+
+impl fmt::Display for DownloadProgress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}/{} packets ({}%)",
+            self.packets_acked,
+            self.total_packets,
+            self.percent()
+        )
+    }
+}
+
+/// Events emitted by the [FirmwareDownloader].
+#[derive(Clone, Debug, PartialEq)]
+pub enum DownloadEvent {
+    /// The I/O layer must change the serial port line settings before the next transmit.
+    SetLineSettings(BaudRate, SerialProtocol),
+    /// The device acknowledged (or rewound to) a packet.
+    Progress(DownloadProgress),
+    /// The device acknowledged the final packet.
+    Complete,
+    /// The download failed, and will not make further progress.
+    Failed(Error),
+}
+
+impl fmt::Display for DownloadEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::SetLineSettings(baud_rate, protocol) => {
+                write!(f, "SetLineSettings({baud_rate}, {protocol})")
+            }
+            Self::Progress(progress) => write!(f, "Progress({progress})"),
+            Self::Complete => write!(f, "Complete"),
+            Self::Failed(err) => write!(f, "Failed({err})"),
+        }
+    }
+}
+
+/// Sans-IO firmware download engine for the 7-bit and 8-bit flash download protocols.
+///
+/// The downloader puts the device in download mode with a [StartDownloadCommand]. For the 8-bit
+/// protocol, it then requests the fast serial download [BaudRate] with a [BaudRateChangeRequest].
+/// If the device does not accept the request, the next lower [BaudRate] is requested, and the
+/// download falls back to the 7-bit protocol once no lower rate is left.
+///
+/// Image packets are sent in order, following the `packet_number` reported in each device reply:
+/// a reply for an earlier packet rewinds the download, and a power loss reply restarts it from the
+/// first packet. After the final packet is acknowledged, the original line settings are restored.
+///
+/// The downloader is a [HostSession]: each message is sent as a [HostIntent::Send], and the
+/// driving [HostProtocol](crate::HostProtocol) stamps the ACK/NAK toggle, and re-sends the message
+/// on a missing or invalid reply. [DownloadEvent]s are drained with [poll_event](Self::poll_event),
+/// and must be handled before the next transmit.
+///
+/// Progress can be persisted with a [DownloadRecord], to [resume](Self::resume) the download after
+/// a host restart.
+#[derive(Debug)]
+pub struct FirmwareDownloader<'a> {
+    image: &'a [u8],
+    sha256: [u8; SHA256_LEN],
+    protocol: SerialProtocol,
+    baud_rate: BaudRate,
+    original_baud_rate: BaudRate,
+    line_changed: bool,
+    phase: DownloadPhase,
+    next_packet: u16,
+    resume_hint: Option<u16>,
+    total_packets: u16,
+    restarts: u32,
+    retries: u8,
+    max_retries: u8,
+    in_flight: bool,
+    events: VecDeque<DownloadEvent>,
+}
+
+impl<'a> FirmwareDownloader<'a> {
+    /// Creates a new [FirmwareDownloader] for the firmware image.
+    ///
+    /// Returns an error if the image is not valid for the [SerialProtocol], see
+    /// [download_packet_count].
+    pub fn new(image: &'a [u8], protocol: SerialProtocol) -> Result<Self> {
+        let total_packets = download_packet_count(image.len(), protocol)?;
+
+        Ok(Self {
+            image,
+            sha256: Sha256::digest(image).into(),
+            protocol,
+            baud_rate: BaudRate::_9600,
+            original_baud_rate: BaudRate::_9600,
+            line_changed: false,
+            phase: DownloadPhase::Start,
+            next_packet: 0,
+            resume_hint: None,
+            total_packets,
+            restarts: 0,
+            retries: 0,
+            max_retries: DEFAULT_MAX_RETRIES,
+            in_flight: false,
+            events: VecDeque::new(),
+        })
+    }
+
+    /// Creates a [FirmwareDownloader] resuming an interrupted download from a [DownloadRecord].
+    ///
+    /// The record is only a hint: the device is put back in download mode, and sent the packet
+    /// after the last acknowledged packet in the record. The `packet_number` in the device reply
+    /// is authoritative, so the download continues from the packet the device reports (or from
+    /// the first packet on power loss), even if the record is stale.
+    ///
+    /// The [SerialProtocol], and [BaudRate] are taken from the record, as negotiated with the
+    /// device before the interruption (e.g. after a fallback to the 7-bit protocol).
+    ///
+    /// Returns an error if the record is for a different image.
+    pub fn resume(image: &'a [u8], record: &DownloadRecord) -> Result<Self> {
+        let mut downloader =
+            Self::new(image, record.protocol())?.with_baud_rate(record.baud_rate());
+
+        let sha256 = downloader.sha256_hex();
+        if !record.sha256().eq_ignore_ascii_case(sha256.as_str()) {
+            return Err(Error::failure(format!(
+                "download record is for a different image, have: {sha256}, expected: {}",
+                record.sha256()
+            )));
+        }
+
+        if let Some(last_acked) = record.last_acked_packet() {
+            // re-send the final packet of a finished download, the device acknowledges it again
+            downloader.next_packet = last_acked
+                .saturating_add(1)
+                .min(downloader.total_packets - 1);
+        }
+        downloader.resume_hint = Some(downloader.next_packet);
+
+        log::info!(
+            "Resuming firmware download from packet {} of {}",
+            downloader.next_packet,
+            downloader.total_packets
+        );
+
+        Ok(downloader)
+    }
+
+    /// Gets the SHA-256 digest of the firmware image, as a hex string.
+    pub fn sha256_hex(&self) -> String {
+        sha256_hex(&self.sha256)
+    }
+
+    /// Gets a [DownloadRecord] of the current download progress.
+    pub fn record(&self) -> DownloadRecord {
+        DownloadRecord::new(
+            self.sha256_hex().as_str(),
+            self.protocol,
+            self.next_packet.checked_sub(1),
+            self.baud_rate,
+        )
+    }
+
+    /// Gets the [SerialProtocol] used for image packets.
+    ///
+    /// Changes to the 7-bit protocol if the device does not support fast serial download.
+    pub fn protocol(&self) -> SerialProtocol {
+        self.protocol
+    }
+
+    /// Gets the [BaudRate] requested for an 8-bit download.
+    pub fn baud_rate(&self) -> BaudRate {
+        self.baud_rate
+    }
+
+    /// Sets the [BaudRate] requested for an 8-bit download.
+    ///
+    /// Ignored for the 7-bit protocol.
+    pub fn set_baud_rate(&mut self, baud_rate: BaudRate) {
+        self.baud_rate = match baud_rate {
+            BaudRate::Reserved => BaudRate::_9600,
+            _ => baud_rate,
+        };
+    }
+
+    /// Builder function to set the [BaudRate] requested for an 8-bit download.
+    pub fn with_baud_rate(mut self, baud_rate: BaudRate) -> Self {
+        self.set_baud_rate(baud_rate);
+        self
+    }
+
+    /// Gets the [BaudRate] restored after an 8-bit download.
+    pub fn original_baud_rate(&self) -> BaudRate {
+        self.original_baud_rate
+    }
+
+    /// Builder function to set the [BaudRate] restored after an 8-bit download.
+    pub fn with_original_baud_rate(mut self, baud_rate: BaudRate) -> Self {
+        self.original_baud_rate = baud_rate;
+        self
+    }
+
+    /// Builder function to set the maximum number of times a message is re-sent after a reply the
+    /// download cannot use (e.g. the device is not in download mode yet).
+    ///
+    /// Re-sends after a missing or invalid reply are counted by the driving
+    /// [HostProtocol](crate::HostProtocol).
+    pub fn with_max_retries(mut self, max_retries: u8) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Gets the current [DownloadPhase].
+    pub fn phase(&self) -> DownloadPhase {
+        self.phase
+    }
+
+    /// Gets whether the download finished, either successfully or with a failure.
+    pub fn is_finished(&self) -> bool {
+        matches!(self.phase, DownloadPhase::Complete | DownloadPhase::Failed)
+    }
+
+    /// Gets the current [DownloadProgress].
+    pub fn progress(&self) -> DownloadProgress {
+        DownloadProgress {
+            packets_acked: self.next_packet,
+            total_packets: self.total_packets,
+            restarts: self.restarts,
+        }
+    }
+
+    /// Gets the next [DownloadEvent], if any.
+    pub fn poll_event(&mut self) -> Option<DownloadEvent> {
+        self.events.pop_front()
+    }
+
+    fn next_command(&self) -> CommandVariant {
+        match self.phase {
+            DownloadPhase::Start => {
+                CommandVariant::StartDownloadCommand(StartDownloadCommand::new())
+            }
+            DownloadPhase::BaudRateChange => {
+                let mut request = BaudRateChangeRequest::new();
+                request.set_baud_rate(self.baud_rate);
+                CommandVariant::BaudRateChangeRequest(request)
+            }
+            _ => self.packet_command(),
+        }
+    }
+
+    fn handle_reply(&mut self, reply: &ReplyVariant) {
+        match (self.phase, reply) {
+            (DownloadPhase::Start, ReplyVariant::StartDownloadReply(start)) => {
+                if start.download_ready() == DownloadReady::Set {
+                    self.retries = 0;
+                    self.phase = match self.protocol {
+                        SerialProtocol::_7bit => DownloadPhase::Download,
+                        SerialProtocol::_8bit => DownloadPhase::BaudRateChange,
+                    };
+                } else {
+                    log::debug!("Device not yet in download mode, re-sending start command");
+                    self.retry(Error::failure("device did not enter download mode"));
+                }
+            }
+            (DownloadPhase::BaudRateChange, ReplyVariant::BaudRateChangeReply(baud)) => {
+                if baud.baud_rate() == self.baud_rate {
+                    self.line_changed = true;
+                    self.phase = DownloadPhase::Download;
+                    self.events.push_back(DownloadEvent::SetLineSettings(
+                        self.baud_rate,
+                        SerialProtocol::_8bit,
+                    ));
+                } else {
+                    self.lower_baud_rate();
+                }
+            }
+            (DownloadPhase::Download, ReplyVariant::FlashDownloadReply7bit(packet))
+                if self.protocol == SerialProtocol::_7bit =>
+            {
+                self.handle_packet_reply(packet);
+            }
+            (DownloadPhase::Download, ReplyVariant::FlashDownloadReply8bit(packet))
+                if self.protocol == SerialProtocol::_8bit =>
+            {
+                self.handle_packet_reply(packet);
+            }
+            (phase, reply) => {
+                log::warn!("Unexpected reply in the {phase} phase: {reply}");
+                self.retry(Error::failure(format!(
+                    "unexpected reply in the {phase} phase: {reply}"
+                )));
+            }
+        }
+    }
+
+    fn handle_packet_reply<R: FlashDownloadReply>(&mut self, reply: &R) {
+        if reply.power_loss() {
+            log::warn!("Device reported power loss, restarting download from the first packet");
+            self.next_packet = 0;
+            self.resume_hint = None;
+            self.restarts = self.restarts.saturating_add(1);
+        } else {
+            let acked = reply.packet_number() as usize + 1;
+
+            if acked > self.total_packets as usize {
+                self.fail(Error::failure(format!(
+                    "device reported unknown packet: {}, total packets: {}",
+                    reply.packet_number(),
+                    self.total_packets
+                )));
+                return;
+            }
+
+            if let Some(hint) = self.resume_hint.take() {
+                if acked != hint as usize + 1 {
+                    log::info!(
+                        "Device resumed at packet: {}, download record hint: {hint}",
+                        reply.packet_number()
+                    );
+                }
+            } else if acked != self.next_packet as usize + 1 {
+                log::debug!(
+                    "Device reported packet: {}, resyncing from packet: {acked}",
+                    reply.packet_number()
+                );
+            }
+
+            self.next_packet = acked as u16;
+        }
+
+        self.retries = 0;
+        self.events
+            .push_back(DownloadEvent::Progress(self.progress()));
+
+        if self.next_packet == self.total_packets {
+            if self.line_changed {
+                self.line_changed = false;
+                self.events.push_back(DownloadEvent::SetLineSettings(
+                    self.original_baud_rate,
+                    SerialProtocol::_7bit,
+                ));
+            }
+
+            self.phase = DownloadPhase::Complete;
+            self.events.push_back(DownloadEvent::Complete);
+        }
+    }
+
+    fn packet_command(&self) -> CommandVariant {
+        let packet_len = download_packet_len(self.protocol);
+        let start = self.next_packet as usize * packet_len;
+        let end = (start + packet_len).min(self.image.len());
+        let data = &self.image[start..end];
+
+        match self.protocol {
+            SerialProtocol::_7bit => {
+                let mut msg = FlashDownloadMessage7bit::new();
+                msg.set_packet_number(self.next_packet);
+                msg.set_data(data);
+                CommandVariant::FlashDownloadMessage7bit(msg)
+            }
+            SerialProtocol::_8bit if data.len() == FLASH_DATA_PACKET_64 => {
+                let mut msg = FlashDownloadMessage8bit_64::new();
+                msg.set_packet_number(self.next_packet);
+                msg.set_data(data);
+                CommandVariant::FlashDownloadMessage8bit64(msg)
+            }
+            SerialProtocol::_8bit => {
+                let mut msg = FlashDownloadMessage8bit_32::new();
+                msg.set_packet_number(self.next_packet);
+                msg.set_data(data);
+                CommandVariant::FlashDownloadMessage8bit32(msg)
+            }
+        }
+    }
+
+    // The device NAKs a baud rate it does not support, request the next lower rate instead.
+    fn lower_baud_rate(&mut self) {
+        let lower = match self.baud_rate {
+            BaudRate::_115200 => BaudRate::_38400,
+            BaudRate::_38400 => BaudRate::_19200,
+            BaudRate::_19200 => BaudRate::_9600,
+            _ => return self.fallback_7bit(),
+        };
+
+        log::info!(
+            "Device does not accept {} baud, requesting: {lower}",
+            self.baud_rate
+        );
+        self.baud_rate = lower;
+    }
+
+    fn fallback_7bit(&mut self) {
+        log::warn!("Device does not support fast serial download, using the 7-bit protocol");
+
+        match download_packet_count(self.image.len(), SerialProtocol::_7bit) {
+            Ok(total_packets) => {
+                // each 64-byte packet is two 32-byte packets in the 7-bit protocol
+                self.next_packet = self.next_packet.saturating_mul(2).min(total_packets - 1);
+                self.protocol = SerialProtocol::_7bit;
+                self.total_packets = total_packets;
+                self.retries = 0;
+                self.phase = DownloadPhase::Download;
+            }
+            Err(err) => self.fail(err),
+        }
+    }
+
+    fn retry(&mut self, err: Error) {
+        if self.retries >= self.max_retries {
+            self.fail(err);
+        } else {
+            self.retries += 1;
+        }
+    }
+
+    fn fail(&mut self, err: Error) {
+        log::error!("Firmware download failed: {err}");
+        self.phase = DownloadPhase::Failed;
+        self.events.push_back(DownloadEvent::Failed(err));
+    }
+}
+
+impl HostSession for FirmwareDownloader<'_> {
+    fn poll_intent(&mut self, _now_ms: u64) -> Option<HostIntent> {
+        if self.in_flight || self.is_finished() {
+            return None;
+        }
+
+        self.in_flight = true;
+
+        Some(HostIntent::Send(self.next_command()))
+    }
+
+    fn handle_event(&mut self, event: &HostEvent) {
+        match event {
+            HostEvent::Reply(reply) if self.in_flight => {
+                self.in_flight = false;
+                self.handle_reply(reply);
+            }
+            HostEvent::CommandFailed(command, err) if self.in_flight => {
+                self.in_flight = false;
+
+                if self.phase == DownloadPhase::BaudRateChange {
+                    log::debug!("Baud rate change failed: {err}");
+                    self.lower_baud_rate();
+                } else {
+                    log::warn!("Firmware download command failed: {command}");
+                    self.fail(err.clone());
+                }
+            }
+            _ => (),
+        }
+    }
+
+    fn is_finished(&self) -> bool {
+        FirmwareDownloader::is_finished(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        BaudRateChangeReply, ErrorCode, FlashDownloadReply7bit, FlashDownloadReply8bit,
+        StartDownloadReply,
+    };
+    use alloc::vec::Vec;
+
+    fn send(downloader: &mut FirmwareDownloader) -> CommandVariant {
+        match downloader.poll_intent(0) {
+            Some(HostIntent::Send(command)) => command,
+            intent => panic!("expected a command, have: {intent:?}"),
+        }
+    }
+
+    fn reply(downloader: &mut FirmwareDownloader, reply: ReplyVariant) {
+        downloader.handle_event(&HostEvent::Reply(reply));
+    }
+
+    fn start_download(downloader: &mut FirmwareDownloader) {
+        send(downloader);
+        let mut start = StartDownloadReply::new();
+        start.set_download_ready(DownloadReady::Set);
+        reply(downloader, ReplyVariant::StartDownloadReply(start));
+    }
+
+    fn drain(downloader: &mut FirmwareDownloader) -> Vec<DownloadEvent> {
+        let mut events = Vec::new();
+        while let Some(event) = downloader.poll_event() {
+            events.push(event);
+        }
+        events
+    }
+
+    #[test]
+    fn test_packet_count() {
+        assert!(download_packet_count(0, SerialProtocol::_7bit).is_err());
+        assert!(download_packet_count(48, SerialProtocol::_7bit).is_err());
+
+        assert_eq!(download_packet_count(96, SerialProtocol::_7bit), Ok(3));
+        assert_eq!(download_packet_count(96, SerialProtocol::_8bit), Ok(2));
+
+        let max_7bit = max_download_len(SerialProtocol::_7bit);
+        assert_eq!(
+            download_packet_count(max_7bit, SerialProtocol::_7bit),
+            Ok(0xffff)
+        );
+        assert!(download_packet_count(max_7bit + 32, SerialProtocol::_7bit).is_err());
+        assert!(download_packet_count(max_7bit + 32, SerialProtocol::_8bit).is_ok());
+    }
+
+    #[test]
+    fn test_7bit_download_follows_packet_number() {
+        let image: Vec<u8> = (0..96u8).collect();
+        let mut downloader = FirmwareDownloader::new(&image, SerialProtocol::_7bit).unwrap();
+
+        start_download(&mut downloader);
+        assert_eq!(downloader.phase(), DownloadPhase::Download);
+
+        // device acknowledges packet 0
+        let command = send(&mut downloader);
+        let packet = command.as_flash_download_message7bit().unwrap();
+        assert_eq!(packet.packet_number(), 0);
+        assert_eq!(packet.data().as_ref(), &image[..32]);
+
+        // one message in flight at a time
+        assert_eq!(downloader.poll_intent(0), None);
+
+        let mut packet_reply = FlashDownloadReply7bit::new();
+        packet_reply.set_packet_number(0);
+        reply(
+            &mut downloader,
+            ReplyVariant::FlashDownloadReply7bit(packet_reply),
+        );
+
+        // device loses packet 1, and reports packet 0 again
+        send(&mut downloader);
+        reply(
+            &mut downloader,
+            ReplyVariant::FlashDownloadReply7bit(packet_reply),
+        );
+        assert_eq!(downloader.progress().packets_acked(), 1);
+
+        // device loses power, restart from the first packet
+        send(&mut downloader);
+        packet_reply.set_packet_number(0xffff);
+        reply(
+            &mut downloader,
+            ReplyVariant::FlashDownloadReply7bit(packet_reply),
+        );
+        assert_eq!(downloader.progress().packets_acked(), 0);
+        assert_eq!(downloader.progress().restarts(), 1);
+
+        for n in 0..3 {
+            let command = send(&mut downloader);
+            let packet = command.as_flash_download_message7bit().unwrap();
+            assert_eq!(packet.packet_number(), n);
+
+            packet_reply.set_packet_number(n);
+            reply(
+                &mut downloader,
+                ReplyVariant::FlashDownloadReply7bit(packet_reply),
+            );
+        }
+
+        assert_eq!(downloader.phase(), DownloadPhase::Complete);
+        assert!(HostSession::is_finished(&downloader));
+        assert_eq!(downloader.poll_intent(0), None);
+        assert_eq!(
+            drain(&mut downloader).last(),
+            Some(&DownloadEvent::Complete)
+        );
+    }
+
+    #[test]
+    fn test_8bit_download_changes_baud_rate() {
+        let image: Vec<u8> = (0..96u8).collect();
+        let mut downloader = FirmwareDownloader::new(&image, SerialProtocol::_8bit)
+            .unwrap()
+            .with_baud_rate(BaudRate::_115200);
+
+        start_download(&mut downloader);
+        assert_eq!(downloader.phase(), DownloadPhase::BaudRateChange);
+
+        // device NAKs the request until the protocol gives up, request the next lower rate
+        let command = send(&mut downloader);
+        let request = command.as_baud_rate_change_request().unwrap();
+        assert_eq!(request.baud_rate(), BaudRate::_115200);
+        downloader.handle_event(&HostEvent::CommandFailed(
+            command,
+            Error::timeout("timed out waiting for reply"),
+        ));
+        assert_eq!(downloader.baud_rate(), BaudRate::_38400);
+
+        let command = send(&mut downloader);
+        let request = command.as_baud_rate_change_request().unwrap();
+        assert_eq!(request.baud_rate(), BaudRate::_38400);
+
+        let mut baud_reply = BaudRateChangeReply::new();
+        baud_reply.set_baud_rate(BaudRate::_38400);
+        reply(
+            &mut downloader,
+            ReplyVariant::BaudRateChangeReply(baud_reply),
+        );
+        assert_eq!(
+            drain(&mut downloader),
+            [DownloadEvent::SetLineSettings(
+                BaudRate::_38400,
+                SerialProtocol::_8bit
+            )]
+        );
+
+        // one full 64-byte packet, and a final 32-byte packet
+        let mut packet_reply = FlashDownloadReply8bit::new();
+
+        let command = send(&mut downloader);
+        let packet_64 = command.as_flash_download_message8bit64().unwrap();
+        assert_eq!(packet_64.data().as_ref(), &image[..64]);
+        packet_reply.set_packet_number(0);
+        reply(
+            &mut downloader,
+            ReplyVariant::FlashDownloadReply8bit(packet_reply),
+        );
+
+        let command = send(&mut downloader);
+        let packet_32 = command.as_flash_download_message8bit32().unwrap();
+        assert_eq!(packet_32.packet_number(), 1);
+        assert_eq!(packet_32.data().as_ref(), &image[64..]);
+        packet_reply.set_packet_number(1);
+        reply(
+            &mut downloader,
+            ReplyVariant::FlashDownloadReply8bit(packet_reply),
+        );
+
+        let events = drain(&mut downloader);
+        assert_eq!(
+            events[events.len() - 2..],
+            [
+                DownloadEvent::SetLineSettings(BaudRate::_9600, SerialProtocol::_7bit),
+                DownloadEvent::Complete,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_8bit_download_falls_back_to_7bit() {
+        let image: Vec<u8> = (0..96u8).collect();
+        let mut downloader = FirmwareDownloader::new(&image, SerialProtocol::_8bit)
+            .unwrap()
+            .with_baud_rate(BaudRate::_19200);
+
+        start_download(&mut downloader);
+
+        // device ignores the baud rate change requests
+        for _ in 0..2 {
+            let command = send(&mut downloader);
+            downloader.handle_event(&HostEvent::CommandFailed(
+                command,
+                Error::timeout("timed out waiting for reply"),
+            ));
+        }
+
+        assert_eq!(downloader.phase(), DownloadPhase::Download);
+        assert_eq!(downloader.protocol(), SerialProtocol::_7bit);
+        assert_eq!(downloader.progress().total_packets(), 3);
+        assert_eq!(drain(&mut downloader), []);
+        assert!(send(&mut downloader).is_flash_download_message7bit());
+    }
+
+    #[test]
+    fn test_failure_keeps_cause() {
+        let image: Vec<u8> = (0..96u8).collect();
+
+        // device never reports download ready
+        let mut downloader = FirmwareDownloader::new(&image, SerialProtocol::_7bit)
+            .unwrap()
+            .with_max_retries(1);
+        for _ in 0..2 {
+            send(&mut downloader);
+            reply(
+                &mut downloader,
+                ReplyVariant::StartDownloadReply(StartDownloadReply::new()),
+            );
+        }
+        assert_eq!(downloader.phase(), DownloadPhase::Failed);
+        assert_eq!(
+            drain(&mut downloader),
+            [DownloadEvent::Failed(Error::failure(
+                "device did not enter download mode"
+            ))]
+        );
+
+        // the protocol gives up on a packet after invalid replies
+        let mut downloader = FirmwareDownloader::new(&image, SerialProtocol::_7bit).unwrap();
+        start_download(&mut downloader);
+
+        let command = send(&mut downloader);
+        downloader.handle_event(&HostEvent::CommandFailed(
+            command,
+            Error::failure("invalid ACK/NAK reply: Mismatch"),
+        ));
+        assert_eq!(downloader.phase(), DownloadPhase::Failed);
+
+        match drain(&mut downloader).as_slice() {
+            [DownloadEvent::Failed(err)] => {
+                assert_eq!(err.code(), ErrorCode::Failure);
+                assert_eq!(err.message(), "invalid ACK/NAK reply: Mismatch");
+            }
+            events => panic!("expected a failure, have: {events:?}"),
+        }
+    }
+
+    #[test]
+    fn test_resume_follows_device() {
+        let image: Vec<u8> = (0..160u8).collect();
+        let sha256 = FirmwareDownloader::new(&image, SerialProtocol::_7bit)
+            .unwrap()
+            .sha256_hex();
+
+        // the record is ahead of the device
+        let record = DownloadRecord::new(&sha256, SerialProtocol::_7bit, Some(3), BaudRate::_9600);
+        let mut downloader = FirmwareDownloader::resume(&image, &record).unwrap();
+        start_download(&mut downloader);
+
+        let command = send(&mut downloader);
+        let packet = command.as_flash_download_message7bit().unwrap();
+        assert_eq!(packet.packet_number(), 4);
+
+        // the device only received packet 1, the download continues from packet 2
+        let mut packet_reply = FlashDownloadReply7bit::new();
+        packet_reply.set_packet_number(1);
+        reply(
+            &mut downloader,
+            ReplyVariant::FlashDownloadReply7bit(packet_reply),
+        );
+        assert_eq!(downloader.progress().packets_acked(), 2);
+
+        let command = send(&mut downloader);
+        let packet = command.as_flash_download_message7bit().unwrap();
+        assert_eq!(packet.packet_number(), 2);
+    }
+}
@@ -68,18 +68,6 @@ impl FlashDownloadMessage<FLASH_DATA_PACKET_64> for FlashDownloadMessage8bit_64
         self.buf[index::PACKET0..=index::PACKET1].copy_from_slice(n.to_le_bytes().as_ref());
     }
 
-    fn increment_packet_number(&mut self) -> u16 {
-        // FIXME: the desired behavior of an increment past the max is unclear.
-        //
-        // C behavior is to overflow, restarting at 0, but it isn't obvious that's what CPI
-        // intends.
-        //
-        // FWIW their firmware files all appear to be below the limit.
-        let packet_number = self.packet_number().overflowing_add(1).0;
-        self.set_packet_number(packet_number);
-        packet_number
-    }
-
     fn data(&self) -> [u8; FLASH_DATA_PACKET_64] {
         // The unwrap is safe here, and can never panic because the slice is guaranteed to be the
         // correct length.
@@ -159,18 +147,6 @@ impl FlashDownloadMessage<FLASH_DATA_PACKET> for FlashDownloadMessage8bit_32 {
         self.buf[index::PACKET0..=index::PACKET1].copy_from_slice(n.to_le_bytes().as_ref());
     }
 
-    fn increment_packet_number(&mut self) -> u16 {
-        // FIXME: the desired behavior of an increment past the max is unclear.
-        //
-        // C behavior is to overflow, restarting at 0, but it isn't obvious that's what CPI
-        // intends.
-        //
-        // FWIW their firmware files all appear to be below the limit.
-        let packet_number = self.packet_number().overflowing_add(1).0;
-        self.set_packet_number(packet_number);
-        packet_number
-    }
-
     fn data(&self) -> [u8; FLASH_DATA_PACKET] {
         // The unwrap is safe here, and can never panic because the slice is guaranteed to be the
         // correct length.
@@ -1,3 +1,6 @@
+use crate::std;
+use std::fmt;
+
 use crate::{
     impl_message_ops, len::START_DOWNLOAD_COMMAND, ExtendedNoteReporting, MessageOps, MessageType,
 };
@@ -50,6 +53,19 @@ impl StartDownloadCommand {
 
 impl_message_ops!(StartDownloadCommand);
 
+impl fmt::Display for StartDownloadCommand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "AckNak: {}, DeviceType: {}, MessageType: {}, ExtendedNote: {}",
+            self.acknak(),
+            self.device_type(),
+            self.message_type(),
+            self.extended_note(),
+        )
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,344 @@
+use crate::std;
+use std::fmt;
+
+use crate::{checksum, index, len, AuxCommand, MessageOps, ReplyVariant, Result, ETX, STX};
+
+/// A complete, validated EBDS message frame.
+///
+/// The frame has a valid `STX`, `LEN`, `ETX`, and checksum, but the message contents are not parsed.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Frame {
+    buf: [u8; len::MAX_MESSAGE],
+    len: usize,
+}
+
+impl Frame {
+    pub(crate) fn new(bytes: &[u8]) -> Self {
+        let len = bytes.len();
+        let mut buf = [0u8; len::MAX_MESSAGE];
+        buf[..len].copy_from_slice(bytes);
+
+        Self { buf, len }
+    }
+
+    /// Parses the [Frame] into a [ReplyVariant].
+    ///
+    /// See [ReplyVariant::from_buf].
+    pub fn to_reply(&self) -> Result<ReplyVariant> {
+        ReplyVariant::from_buf(self.buf())
+    }
+
+    /// Parses the [Frame] into a [ReplyVariant], using the [AuxCommand] of the sent message.
+    ///
+    /// See [ReplyVariant::from_aux_buf].
+    pub fn to_aux_reply(&self, command: AuxCommand) -> Result<ReplyVariant> {
+        ReplyVariant::from_aux_buf(self.buf(), command)
+    }
+}
+
+impl MessageOps for Frame {
+    fn buf(&self) -> &[u8] {
+        self.buf[..self.len].as_ref()
+    }
+
+    fn buf_mut(&mut self) -> &mut [u8] {
+        self.buf[..self.len].as_mut()
+    }
+}
+
+impl fmt::Display for Frame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:02x?}", self.buf())
+    }
+}
+
+/// Incremental decoder for EBDS message frames.
+///
+/// Serial reads arrive fragmented, and occasionally contain line noise. The decoder accepts
+/// arbitrary byte chunks, scans for the `STX` byte, and uses the `LEN` byte to wait for a full
+/// frame. Complete frames are checked for a valid `ETX` byte and checksum.
+///
+/// Garbage bytes, and frames failing validation, are dropped until the next plausible `STX` byte.
+/// The number of dropped bytes is reported by [discarded](Self::discarded).
+///
+/// Example:
+///
+/// ```rust
+/// # use ebds::FrameDecoder;
+/// let mut decoder = FrameDecoder::new();
+///
+/// let chunk1 = [0xff, 0x02, 0x0b, 0x20, 0x01, 0x10];
+/// let chunk2 = [0x00, 0x00, 0x00, 0x00, 0x03, 0x3a];
+///
+/// assert_eq!(decoder.frames(chunk1.as_ref()).count(), 0);
+/// assert_eq!(decoder.frames(chunk2.as_ref()).count(), 1);
+/// assert_eq!(decoder.discarded(), 1);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct FrameDecoder {
+    buf: [u8; len::MAX_MESSAGE],
+    len: usize,
+    discarded: usize,
+}
+
+impl FrameDecoder {
+    /// Creates a new [FrameDecoder].
+    pub const fn new() -> Self {
+        Self {
+            buf: [0u8; len::MAX_MESSAGE],
+            len: 0,
+            discarded: 0,
+        }
+    }
+
+    /// Gets the number of buffered bytes belonging to an incomplete frame.
+    pub fn pending(&self) -> usize {
+        self.len
+    }
+
+    /// Gets the total number of bytes discarded since the last call to [take_discarded](Self::take_discarded).
+    pub fn discarded(&self) -> usize {
+        self.discarded
+    }
+
+    /// Gets the number of discarded bytes, and resets the counter.
+    pub fn take_discarded(&mut self) -> usize {
+        let discarded = self.discarded;
+        self.discarded = 0;
+        discarded
+    }
+
+    /// Clears any buffered bytes, and resets the discarded byte counter.
+    pub fn reset(&mut self) {
+        self.len = 0;
+        self.discarded = 0;
+    }
+
+    /// Decodes bytes from the input, stopping after the first complete [Frame].
+    ///
+    /// Returns the number of input bytes consumed, and the [Frame] (if any).
+    ///
+    /// Callers should continue decoding the remaining input after a [Frame] is returned,
+    /// or use the [frames](Self::frames) iterator.
+    pub fn decode(&mut self, input: &[u8]) -> (usize, Option<Frame>) {
+        // a previous resync may have left a complete frame in the buffer
+        if let Some(frame) = self.process() {
+            return (0, Some(frame));
+        }
+
+        for (i, &b) in input.iter().enumerate() {
+            if self.len == 0 && b != STX {
+                self.discarded += 1;
+                continue;
+            }
+
+            self.buf[self.len] = b;
+            self.len += 1;
+
+            if let Some(frame) = self.process() {
+                return (i + 1, Some(frame));
+            }
+        }
+
+        (input.len(), None)
+    }
+
+    /// Gets an iterator over the complete [Frame]s decoded from the input.
+    pub fn frames<'d, 'i>(&'d mut self, input: &'i [u8]) -> Frames<'d, 'i> {
+        Frames {
+            decoder: self,
+            input,
+        }
+    }
+
+    /// Gets an iterator over the [ReplyVariant]s parsed from the complete frames in the input.
+    ///
+    /// Auxilliary replies cannot be parsed without the sent command, use [Frame::to_aux_reply] instead.
+    pub fn replies<'d, 'i>(&'d mut self, input: &'i [u8]) -> Replies<'d, 'i> {
+        Replies {
+            frames: self.frames(input),
+        }
+    }
+
+    fn process(&mut self) -> Option<Frame> {
+        loop {
+            if self.len < 2 {
+                return None;
+            }
+
+            let frame_len = self.buf[1] as usize;
+            if !(len::MIN_MESSAGE..=len::MAX_MESSAGE).contains(&frame_len) {
+                log::trace!("Invalid frame length: {frame_len}, resyncing");
+                self.resync();
+                continue;
+            }
+
+            if self.len < frame_len {
+                return None;
+            }
+
+            let frame_buf = &self.buf[..frame_len];
+            let etx = frame_buf[frame_len - 2];
+
+            // compare the checksum directly, the validation error would allocate its message
+            if etx == ETX
+                && checksum(&frame_buf[index::LEN..frame_len - 2]) == frame_buf[frame_len - 1]
+            {
+                let frame = Frame::new(frame_buf);
+
+                self.buf.copy_within(frame_len..self.len, 0);
+                self.len -= frame_len;
+                self.align();
+
+                return Some(frame);
+            }
+
+            log::trace!("Invalid frame: {frame_buf:02x?}, resyncing");
+            self.resync();
+        }
+    }
+
+    // Drops the leading STX byte, and any bytes up to the next STX byte.
+    fn resync(&mut self) {
+        self.buf.copy_within(1..self.len, 0);
+        self.len -= 1;
+        self.discarded += 1;
+        self.align();
+    }
+
+    // Drops any bytes before the first STX byte in the buffer.
+    fn align(&mut self) {
+        let start = self.buf[..self.len]
+            .iter()
+            .position(|&b| b == STX)
+            .unwrap_or(self.len);
+
+        if start > 0 {
+            self.buf.copy_within(start..self.len, 0);
+            self.len -= start;
+            self.discarded += start;
+        }
+    }
+}
+
+impl Default for FrameDecoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Iterator over the complete [Frame]s decoded from an input chunk.
+///
+/// Created by [FrameDecoder::frames].
+pub struct Frames<'d, 'i> {
+    decoder: &'d mut FrameDecoder,
+    input: &'i [u8],
+}
+
+impl Iterator for Frames<'_, '_> {
+    type Item = Frame;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (consumed, frame) = self.decoder.decode(self.input);
+        self.input = &self.input[consumed..];
+        frame
+    }
+}
+
+/// Iterator over the [ReplyVariant]s parsed from an input chunk.
+///
+/// Created by [FrameDecoder::replies].
+pub struct Replies<'d, 'i> {
+    frames: Frames<'d, 'i>,
+}
+
+impl Iterator for Replies<'_, '_> {
+    type Item = Result<ReplyVariant>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.frames.next().map(|frame| frame.to_reply())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{MessageType, ReplyVariant};
+    use alloc::{vec, vec::Vec};
+
+    #[rustfmt::skip]
+    const OMNIBUS_REPLY: [u8; 11] = [
+        // STX | LEN | Message type
+        0x02, 0x0b, 0x20,
+        // Data
+        0b0000_0001, 0b0001_0000, 0b0000_0000, 0b0000_0000, 0b0000_0000, 0b0000_0000,
+        // ETX | Checksum
+        0x03, 0x3a,
+    ];
+
+    #[test]
+    fn test_decode_fragmented() -> Result<()> {
+        let mut decoder = FrameDecoder::new();
+
+        for b in OMNIBUS_REPLY[..10].iter() {
+            assert_eq!(decoder.frames([*b].as_ref()).count(), 0);
+        }
+        assert_eq!(decoder.pending(), 10);
+
+        let frame = decoder.frames(OMNIBUS_REPLY[10..].as_ref()).next().unwrap();
+
+        assert_eq!(frame.buf(), OMNIBUS_REPLY.as_ref());
+        assert_eq!(frame.message_type(), MessageType::OmnibusReply);
+        assert!(matches!(frame.to_reply()?, ReplyVariant::OmnibusReply(_)));
+        assert_eq!(decoder.pending(), 0);
+        assert_eq!(decoder.discarded(), 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_resync() -> Result<()> {
+        let mut decoder = FrameDecoder::new();
+
+        let mut bad_checksum = OMNIBUS_REPLY;
+        bad_checksum[10] = 0x00;
+
+        let mut input = vec![0xff, 0x00];
+        // frame with a bad checksum
+        input.extend_from_slice(bad_checksum.as_ref());
+        // STX with an invalid length
+        input.extend_from_slice([0x02, 0x01].as_ref());
+        input.extend_from_slice(OMNIBUS_REPLY.as_ref());
+        input.extend_from_slice(OMNIBUS_REPLY.as_ref());
+
+        let replies: Vec<Result<ReplyVariant>> = decoder.replies(input.as_ref()).collect();
+
+        assert_eq!(replies.len(), 2);
+        for reply in replies {
+            assert!(matches!(reply?, ReplyVariant::OmnibusReply(_)));
+        }
+
+        assert_eq!(decoder.take_discarded(), 2 + bad_checksum.len() + 2);
+        assert_eq!(decoder.discarded(), 0);
+        assert_eq!(decoder.pending(), 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_embedded_frame() {
+        let mut decoder = FrameDecoder::new();
+
+        // a truncated frame, followed by a complete frame: the complete frame is only
+        // found after the truncated frame fails validation
+        let mut input = vec![0x02, 0x0b, 0x20, 0x01];
+        input.extend_from_slice(OMNIBUS_REPLY.as_ref());
+        input.extend_from_slice(OMNIBUS_REPLY.as_ref());
+
+        let frames: Vec<Frame> = decoder.frames(input.as_ref()).collect();
+
+        assert_eq!(frames.len(), 2);
+        assert_eq!(decoder.discarded(), 4);
+        assert_eq!(decoder.pending(), 0);
+    }
+}
@@ -368,18 +368,13 @@ impl Serialize for BillAcceptorStatusDetails {
 /// HardwareStatusDetails represents the type of hardware details are provided for in
 /// HardwareStatus
 #[repr(C)]
-#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize, Serialize)]
 pub enum HardwareStatusDetails {
+    #[default]
     NULL,
     BAU,
 }
 
-impl Default for HardwareStatusDetails {
-    fn default() -> Self {
-        Self::NULL
-    }
-}
-
 impl From<HardwareStatusDetails> for &'static str {
     fn from(h: HardwareStatusDetails) -> Self {
         match h {
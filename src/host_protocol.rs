@@ -0,0 +1,811 @@
+use heapless::Deque;
+
+use crate::std;
+use std::fmt;
+
+use crate::{
+    AckNakSequencer, CommandVariant, DocumentReturn, DocumentStack, Error, Frame, FrameDecoder,
+    MessageOps, OmnibusCommand, OmnibusCommandOps, ReplyCheck, ReplyVariant,
+    DEFAULT_REPLY_TIMEOUT_MS,
+};
+
+/// Default interval between Omnibus polls (milliseconds).
+pub const DEFAULT_POLL_INTERVAL_MS: u64 = 200;
+/// Maximum number of commands queued by the [HostProtocol], waiting to be sent.
+pub const MAX_QUEUED_COMMANDS: usize = 8;
+/// Maximum number of [HostEvent]s held by the [HostProtocol], waiting to be polled.
+pub const MAX_QUEUED_EVENTS: usize = 8;
+
+/// User intents for the [HostProtocol].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum HostIntent {
+    /// Queues a command to send before the next poll.
+    Send(CommandVariant),
+    /// Starts sending periodic Omnibus polls.
+    StartPolling,
+    /// Stops sending periodic Omnibus polls.
+    StopPolling,
+    /// Replaces the [OmnibusCommand] used for periodic polls.
+    SetPollCommand(OmnibusCommand),
+    /// Sets the `stack` bit on the next poll, to stack an escrowed document.
+    Stack,
+    /// Sets the `return` bit on the next poll, to return an escrowed document.
+    Return,
+}
+
+impl fmt::Display for HostIntent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Send(cmd) => write!(f, "Send({cmd})"),
+            Self::StartPolling => write!(f, "StartPolling"),
+            Self::StopPolling => write!(f, "StopPolling"),
+            Self::SetPollCommand(cmd) => write!(f, "SetPollCommand({cmd})"),
+            Self::Stack => write!(f, "Stack"),
+            Self::Return => write!(f, "Return"),
+        }
+    }
+}
+
+/// Events emitted by the [HostProtocol].
+#[derive(Debug, PartialEq)]
+pub enum HostEvent {
+    /// An accepted reply to the last sent command.
+    Reply(ReplyVariant),
+    /// The command failed after exhausting all retries.
+    CommandFailed(CommandVariant, Error),
+    /// Bytes discarded while framing replies (line noise, invalid frames, etc.).
+    BytesDiscarded(usize),
+}
+
+impl fmt::Display for HostEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Reply(reply) => write!(f, "Reply({reply})"),
+            Self::CommandFailed(cmd, err) => write!(f, "CommandFailed({cmd}, {err})"),
+            Self::BytesDiscarded(count) => write!(f, "BytesDiscarded({count})"),
+        }
+    }
+}
+
+/// Sans-IO state machines driven over a port by the I/O layer.
+///
+/// Implemented by the [HostProtocol], and by the state machines wrapping one, e.g. the
+/// [DevicePoller](crate::DevicePoller), so the same blocking, and async I/O loops drive all of
+/// them. See [HostProtocol] for the meaning of each function.
+pub trait HostMachine {
+    /// Gets a reference to the [HostProtocol].
+    fn protocol(&self) -> &HostProtocol;
+
+    /// Gets a mutable reference to the [HostProtocol].
+    fn protocol_mut(&mut self) -> &mut HostProtocol;
+
+    /// Handles bytes received from the device.
+    fn handle_input(&mut self, bytes: &[u8]);
+
+    /// Handles the passage of time (milliseconds).
+    fn handle_elapsed(&mut self, elapsed_ms: u64);
+
+    /// Gets the next message to write to the device, if any.
+    fn poll_transmit(&mut self) -> Option<Frame>;
+
+    /// Gets the time until the next deadline (milliseconds), if any.
+    fn poll_timeout(&self) -> Option<u64>;
+}
+
+/// Multi-step host workflows driven through a [HostProtocol], e.g. firmware download, and
+/// calibration.
+///
+/// A session sends its commands as [HostIntent]s, and follows their outcome through the
+/// [HostEvent]s, so it shares the ACK/NAK sequencing, and retries, of the protocol driving it.
+///
+/// The I/O layer forwards every [HostEvent] to [handle_event](Self::handle_event), and passes the
+/// next intent from [poll_intent](Self::poll_intent) to the protocol whenever the protocol is idle,
+/// until the session [is_finished](Self::is_finished).
+pub trait HostSession {
+    /// Gets the next intent for the [HostProtocol], if any.
+    ///
+    /// `now_ms` is the protocol time, see [HostProtocol::now_ms].
+    fn poll_intent(&mut self, now_ms: u64) -> Option<HostIntent>;
+
+    /// Gets the time until the session has a new intent (milliseconds), if it is waiting.
+    fn poll_timeout(&self, _now_ms: u64) -> Option<u64> {
+        None
+    }
+
+    /// Handles an event from the [HostProtocol].
+    fn handle_event(&mut self, event: &HostEvent);
+
+    /// Gets whether the session finished, either successfully or with a failure.
+    fn is_finished(&self) -> bool;
+}
+
+// A command waiting for an accepted reply.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct InFlight {
+    command: CommandVariant,
+    is_poll: bool,
+    stack: bool,
+    document_return: bool,
+    attempts: u8,
+    deadline: u64,
+}
+
+impl InFlight {
+    fn new(command: CommandVariant) -> Self {
+        Self {
+            command,
+            is_poll: false,
+            stack: false,
+            document_return: false,
+            attempts: 0,
+            deadline: 0,
+        }
+    }
+}
+
+/// Sans-IO host protocol state machine.
+///
+/// The state machine performs no I/O, and keeps no clock. Instead, the I/O layer (blocking, async,
+/// embedded, etc.) feeds it inputs:
+///
+/// - bytes received: [handle_input](Self::handle_input)
+/// - time elapsed: [handle_elapsed](Self::handle_elapsed)
+/// - user intent: [handle_intent](Self::handle_intent)
+///
+/// and drains its outputs:
+///
+/// - bytes to send: [poll_transmit](Self::poll_transmit)
+/// - timeout deadline: [poll_timeout](Self::poll_timeout)
+/// - events: [poll_event](Self::poll_event)
+///
+/// The state machine owns the Omnibus poll cadence, ACK/NAK toggling, and retries.
+///
+/// Example:
+///
+/// ```rust
+/// # use ebds::{HostEvent, HostIntent, HostProtocol, MessageOps};
+/// let mut protocol = HostProtocol::new();
+/// protocol.handle_intent(HostIntent::StartPolling);
+///
+/// // write the first poll to the device
+/// let poll = protocol.poll_transmit().unwrap();
+/// assert_eq!(poll.message_type(), ebds::MessageType::OmnibusCommand);
+///
+/// // device reply arrives
+/// protocol.handle_input(&[0x02, 0x0b, 0x20, 0x01, 0x10, 0x00, 0x00, 0x00, 0x00, 0x03, 0x3a]);
+/// assert!(matches!(protocol.poll_event(), Some(HostEvent::Reply(_))));
+///
+/// // wait until the next poll is due
+/// let wait_ms = protocol.poll_timeout().unwrap();
+/// protocol.handle_elapsed(wait_ms);
+/// assert!(protocol.poll_transmit().is_some());
+/// ```
+#[derive(Debug)]
+pub struct HostProtocol {
+    now: u64,
+    decoder: FrameDecoder,
+    sequencer: AckNakSequencer,
+    poll_command: OmnibusCommand,
+    bookmark_mode: bool,
+    poll_interval_ms: u64,
+    reply_timeout_ms: u64,
+    polling: bool,
+    next_poll: u64,
+    stack_pending: bool,
+    return_pending: bool,
+    queue: Deque<CommandVariant, MAX_QUEUED_COMMANDS>,
+    in_flight: Option<InFlight>,
+    transmit: Option<Frame>,
+    events: Deque<HostEvent, MAX_QUEUED_EVENTS>,
+}
+
+impl HostProtocol {
+    /// Creates a new [HostProtocol].
+    pub fn new() -> Self {
+        Self {
+            now: 0,
+            decoder: FrameDecoder::new(),
+            sequencer: AckNakSequencer::new(),
+            poll_command: OmnibusCommand::new(),
+            bookmark_mode: false,
+            poll_interval_ms: DEFAULT_POLL_INTERVAL_MS,
+            reply_timeout_ms: DEFAULT_REPLY_TIMEOUT_MS,
+            polling: false,
+            next_poll: 0,
+            stack_pending: false,
+            return_pending: false,
+            queue: Deque::new(),
+            in_flight: None,
+            transmit: None,
+            events: Deque::new(),
+        }
+    }
+
+    /// Gets the time elapsed since the [HostProtocol] was created (milliseconds).
+    pub fn now_ms(&self) -> u64 {
+        self.now
+    }
+
+    /// Gets the interval between Omnibus polls (milliseconds).
+    pub fn poll_interval_ms(&self) -> u64 {
+        self.poll_interval_ms
+    }
+
+    /// Sets the interval between Omnibus polls (milliseconds).
+    pub fn set_poll_interval_ms(&mut self, poll_interval_ms: u64) {
+        self.poll_interval_ms = poll_interval_ms;
+    }
+
+    /// Builder function to set the interval between Omnibus polls (milliseconds).
+    pub fn with_poll_interval_ms(mut self, poll_interval_ms: u64) -> Self {
+        self.set_poll_interval_ms(poll_interval_ms);
+        self
+    }
+
+    /// Gets the reply timeout (milliseconds).
+    pub fn reply_timeout_ms(&self) -> u64 {
+        self.reply_timeout_ms
+    }
+
+    /// Sets the reply timeout (milliseconds).
+    pub fn set_reply_timeout_ms(&mut self, reply_timeout_ms: u64) {
+        self.reply_timeout_ms = reply_timeout_ms;
+    }
+
+    /// Builder function to set the reply timeout (milliseconds).
+    pub fn with_reply_timeout_ms(mut self, reply_timeout_ms: u64) -> Self {
+        self.set_reply_timeout_ms(reply_timeout_ms);
+        self
+    }
+
+    /// Builder function to set the maximum number of times a command is re-sent.
+    pub fn with_max_retries(mut self, max_retries: u8) -> Self {
+        self.sequencer.set_max_retries(max_retries);
+        self
+    }
+
+    /// Gets a reference to the [AckNakSequencer].
+    pub fn sequencer(&self) -> &AckNakSequencer {
+        &self.sequencer
+    }
+
+    /// Gets a mutable reference to the [AckNakSequencer].
+    pub fn sequencer_mut(&mut self) -> &mut AckNakSequencer {
+        &mut self.sequencer
+    }
+
+    /// Gets a reference to the [OmnibusCommand] used for periodic polls.
+    pub fn poll_command(&self) -> &OmnibusCommand {
+        &self.poll_command
+    }
+
+    /// Gets whether periodic polls are sent as [OmnibusBookmarkCommand](crate::OmnibusBookmarkCommand)s.
+    pub fn bookmark_mode(&self) -> bool {
+        self.bookmark_mode
+    }
+
+    /// Sets whether periodic polls are sent as [OmnibusBookmarkCommand](crate::OmnibusBookmarkCommand)s.
+    ///
+    /// In bookmark mode, the device accepts bookmarks (receipts, slips) along with banknotes.
+    pub fn set_bookmark_mode(&mut self, bookmark_mode: bool) {
+        self.bookmark_mode = bookmark_mode;
+    }
+
+    /// Builder function to set whether periodic polls are sent as [OmnibusBookmarkCommand](crate::OmnibusBookmarkCommand)s.
+    pub fn with_bookmark_mode(mut self, bookmark_mode: bool) -> Self {
+        self.set_bookmark_mode(bookmark_mode);
+        self
+    }
+
+    /// Gets whether periodic polling is enabled.
+    pub fn polling(&self) -> bool {
+        self.polling
+    }
+
+    /// Gets whether a command is waiting for a reply.
+    pub fn is_busy(&self) -> bool {
+        self.in_flight.is_some()
+    }
+
+    /// Handles a user intent.
+    pub fn handle_intent(&mut self, intent: HostIntent) {
+        log::trace!("Handling intent: {intent}");
+
+        match intent {
+            HostIntent::Send(command) => {
+                if let Err(command) = self.queue.push_back(command) {
+                    self.push_event(HostEvent::CommandFailed(
+                        command,
+                        Error::failure("command queue is full"),
+                    ));
+                }
+            }
+            HostIntent::StartPolling => {
+                self.polling = true;
+                self.next_poll = self.now;
+            }
+            HostIntent::StopPolling => self.polling = false,
+            HostIntent::SetPollCommand(command) => self.poll_command = command,
+            HostIntent::Stack => self.stack_pending = true,
+            HostIntent::Return => self.return_pending = true,
+        }
+
+        self.advance();
+    }
+
+    /// Handles bytes received from the device.
+    pub fn handle_input(&mut self, bytes: &[u8]) {
+        let mut input = bytes;
+
+        while !input.is_empty() {
+            let (consumed, frame) = self.decoder.decode(input);
+            input = &input[consumed..];
+
+            if let Some(frame) = frame {
+                self.handle_frame(frame);
+            }
+        }
+
+        let discarded = self.decoder.take_discarded();
+        if discarded > 0 {
+            self.push_event(HostEvent::BytesDiscarded(discarded));
+        }
+
+        self.advance();
+    }
+
+    /// Handles the passage of time (milliseconds).
+    pub fn handle_elapsed(&mut self, elapsed_ms: u64) {
+        self.now = self.now.saturating_add(elapsed_ms);
+
+        if let Some(in_flight) = self.in_flight {
+            if self.now >= in_flight.deadline {
+                log::debug!("Timed out waiting for reply to: {}", in_flight.command);
+                self.retry(Error::timeout("timed out waiting for reply"));
+            }
+        }
+
+        self.advance();
+    }
+
+    /// Gets the next message to write to the device, if any.
+    pub fn poll_transmit(&mut self) -> Option<Frame> {
+        self.transmit.take()
+    }
+
+    /// Gets the time until the next deadline (milliseconds), if any.
+    ///
+    /// The I/O layer should call [handle_elapsed](Self::handle_elapsed) once the deadline passes,
+    /// even if no bytes were received.
+    pub fn poll_timeout(&self) -> Option<u64> {
+        let deadline = match self.in_flight {
+            Some(in_flight) => Some(in_flight.deadline),
+            None if self.polling => Some(self.next_poll),
+            None => None,
+        };
+
+        deadline.map(|d| d.saturating_sub(self.now))
+    }
+
+    /// Gets the next event, if any.
+    pub fn poll_event(&mut self) -> Option<HostEvent> {
+        self.events.pop_front()
+    }
+
+    fn handle_frame(&mut self, frame: Frame) {
+        let in_flight = match self.in_flight {
+            Some(in_flight) => in_flight,
+            None => {
+                log::debug!("Ignoring unsolicited message: {frame}");
+                return;
+            }
+        };
+
+        // a replayed reply to a previous command may not parse as a reply to this command
+        if self.sequencer.classify_reply(&frame) == ReplyCheck::Duplicate {
+            log::debug!("Discarding duplicate reply: {frame}");
+            return;
+        }
+
+        let reply =
+            match ReplyVariant::from_command_buf(frame.buf(), in_flight.command.as_message()) {
+                Ok(reply) => reply,
+                Err(err) => {
+                    log::debug!("Invalid reply: {err}");
+                    self.retry(err);
+                    return;
+                }
+            };
+
+        match self.sequencer.check_reply(reply.as_message()) {
+            ReplyCheck::Accepted => {
+                self.complete(in_flight);
+                self.push_event(HostEvent::Reply(reply));
+            }
+            ReplyCheck::Duplicate => self.retry(Error::failure("invalid ACK/NAK reply: Duplicate")),
+            ReplyCheck::Mismatch => self.retry(Error::failure("invalid ACK/NAK reply: Mismatch")),
+        }
+    }
+
+    // Queues an event, dropping the oldest event if the queue is full.
+    fn push_event(&mut self, event: HostEvent) {
+        if self.events.is_full() {
+            if let Some(dropped) = self.events.pop_front() {
+                log::warn!("Event queue full, dropping event: {dropped}");
+            }
+        }
+
+        // cannot fail, the queue has room
+        let _ = self.events.push_back(event);
+    }
+
+    fn retry(&mut self, err: Error) {
+        let mut in_flight = match self.in_flight {
+            Some(in_flight) => in_flight,
+            None => return,
+        };
+
+        if in_flight.attempts >= self.sequencer.max_retries() {
+            // the device never saw the stack/return request, keep it for the next poll
+            if in_flight.stack {
+                self.stack_pending = true;
+            }
+            if in_flight.document_return {
+                self.return_pending = true;
+            }
+
+            self.complete(in_flight);
+            self.push_event(HostEvent::CommandFailed(in_flight.command, err));
+        } else {
+            in_flight.attempts += 1;
+            log::debug!(
+                "Re-sending message, attempt {} of {}",
+                in_flight.attempts,
+                self.sequencer.max_retries()
+            );
+            self.transmit(in_flight);
+        }
+    }
+
+    fn complete(&mut self, in_flight: InFlight) {
+        self.in_flight = None;
+
+        if in_flight.is_poll {
+            self.next_poll = self.now.saturating_add(self.poll_interval_ms);
+        }
+    }
+
+    fn advance(&mut self) {
+        if self.in_flight.is_some() {
+            return;
+        }
+
+        if let Some(command) = self.queue.pop_front() {
+            self.start(InFlight::new(command));
+        } else if self.polling && self.now >= self.next_poll {
+            let mut poll = self.poll_command;
+
+            let stack = core::mem::take(&mut self.stack_pending);
+            if stack {
+                poll.set_document_stack(DocumentStack::Set);
+            }
+            let document_return = core::mem::take(&mut self.return_pending);
+            if document_return {
+                poll.set_document_return(DocumentReturn::Set);
+            }
+
+            let poll = if self.bookmark_mode {
+                CommandVariant::OmnibusBookmarkCommand(poll.into())
+            } else {
+                CommandVariant::OmnibusCommand(poll)
+            };
+
+            self.start(InFlight {
+                is_poll: true,
+                stack,
+                document_return,
+                ..InFlight::new(poll)
+            });
+        }
+    }
+
+    fn start(&mut self, mut in_flight: InFlight) {
+        let message = in_flight.command.as_message_mut();
+        self.sequencer.stamp(message);
+        message.calculate_checksum();
+
+        self.transmit(in_flight);
+    }
+
+    // Stale bytes left from a previous exchange are not cleared: a partial frame fails
+    // validation once the reply arrives, and the decoder resyncs to the reply (counting the
+    // stale bytes as discarded).
+    fn transmit(&mut self, mut in_flight: InFlight) {
+        in_flight.deadline = self.now.saturating_add(self.reply_timeout_ms);
+
+        self.transmit = Some(Frame::new(in_flight.command.as_message().buf()));
+        self.in_flight = Some(in_flight);
+    }
+}
+
+impl HostMachine for HostProtocol {
+    fn protocol(&self) -> &HostProtocol {
+        self
+    }
+
+    fn protocol_mut(&mut self) -> &mut HostProtocol {
+        self
+    }
+
+    fn handle_input(&mut self, bytes: &[u8]) {
+        HostProtocol::handle_input(self, bytes)
+    }
+
+    fn handle_elapsed(&mut self, elapsed_ms: u64) {
+        HostProtocol::handle_elapsed(self, elapsed_ms)
+    }
+
+    fn poll_transmit(&mut self) -> Option<Frame> {
+        HostProtocol::poll_transmit(self)
+    }
+
+    fn poll_timeout(&self) -> Option<u64> {
+        HostProtocol::poll_timeout(self)
+    }
+}
+
+impl Default for HostProtocol {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        index, AckNak, DocumentType, ErrorCode, MessageType, OmnibusBookmarkCommand,
+        QueryBootPartNumberCommand,
+    };
+
+    #[rustfmt::skip]
+    const OMNIBUS_REPLY_ACK: [u8; 11] = [
+        // STX | LEN | Message type
+        0x02, 0x0b, 0x20,
+        // Data
+        0b0000_0001, 0b0001_0000, 0b0000_0000, 0b0000_0000, 0b0000_0000, 0b0000_0000,
+        // ETX | Checksum
+        0x03, 0x3a,
+    ];
+
+    #[rustfmt::skip]
+    const OMNIBUS_REPLY_NAK: [u8; 11] = [
+        // STX | LEN | Message type
+        0x02, 0x0b, 0x21,
+        // Data
+        0b0000_0001, 0b0001_0000, 0b0000_0000, 0b0000_0000, 0b0000_0000, 0b0000_0000,
+        // ETX | Checksum
+        0x03, 0x3b,
+    ];
+
+    #[test]
+    fn test_poll_cadence() {
+        let mut protocol = HostProtocol::new().with_poll_interval_ms(100);
+
+        assert_eq!(protocol.poll_transmit(), None);
+        assert_eq!(protocol.poll_timeout(), None);
+
+        protocol.handle_intent(HostIntent::StartPolling);
+
+        let poll = protocol.poll_transmit().unwrap();
+        assert_eq!(poll.acknak(), AckNak::Ack);
+        assert_eq!(protocol.poll_timeout(), Some(DEFAULT_REPLY_TIMEOUT_MS));
+
+        // reply arrives in two fragments
+        protocol.handle_elapsed(20);
+        protocol.handle_input(OMNIBUS_REPLY_ACK[..5].as_ref());
+        assert_eq!(protocol.poll_event(), None);
+        protocol.handle_input(OMNIBUS_REPLY_ACK[5..].as_ref());
+
+        assert!(matches!(
+            protocol.poll_event(),
+            Some(HostEvent::Reply(ReplyVariant::OmnibusReply(_)))
+        ));
+        assert!(!protocol.is_busy());
+        assert_eq!(protocol.poll_timeout(), Some(100));
+
+        // next poll is not due yet
+        protocol.handle_elapsed(50);
+        assert_eq!(protocol.poll_transmit(), None);
+
+        protocol.handle_elapsed(50);
+        let poll = protocol.poll_transmit().unwrap();
+        assert_eq!(poll.acknak(), AckNak::Nak);
+    }
+
+    #[test]
+    fn test_retry_and_duplicate() {
+        let mut protocol = HostProtocol::new();
+        protocol.handle_intent(HostIntent::StartPolling);
+
+        protocol.poll_transmit().unwrap();
+        protocol.handle_input(OMNIBUS_REPLY_ACK.as_ref());
+        protocol.poll_event().unwrap();
+
+        protocol.handle_elapsed(protocol.poll_interval_ms());
+        let poll = protocol.poll_transmit().unwrap();
+
+        // duplicate reply is discarded, and the poll is not re-sent
+        protocol.handle_input(OMNIBUS_REPLY_ACK.as_ref());
+        assert_eq!(protocol.poll_event(), None);
+        assert_eq!(protocol.poll_transmit(), None);
+        assert!(protocol.is_busy());
+
+        // missing reply, the poll is re-sent with the same toggle
+        protocol.handle_elapsed(protocol.reply_timeout_ms());
+        assert_eq!(protocol.poll_transmit(), Some(poll));
+
+        protocol.handle_input(OMNIBUS_REPLY_NAK.as_ref());
+        assert!(matches!(protocol.poll_event(), Some(HostEvent::Reply(_))));
+    }
+
+    #[test]
+    fn test_replayed_reply_discarded() {
+        let mut protocol = HostProtocol::new();
+        protocol.handle_intent(HostIntent::StartPolling);
+
+        protocol.poll_transmit().unwrap();
+        protocol.handle_input(OMNIBUS_REPLY_ACK.as_ref());
+        protocol.poll_event().unwrap();
+
+        let command = CommandVariant::QueryBootPartNumberCommand(QueryBootPartNumberCommand::new());
+        protocol.handle_intent(HostIntent::Send(command));
+        protocol.poll_transmit().unwrap();
+
+        // the replayed poll reply is not a valid reply to the query, but is still discarded
+        protocol.handle_input(OMNIBUS_REPLY_ACK.as_ref());
+        assert_eq!(protocol.poll_event(), None);
+        assert_eq!(protocol.poll_transmit(), None);
+        assert!(protocol.is_busy());
+    }
+
+    #[test]
+    fn test_retries_exhausted() {
+        let mut protocol = HostProtocol::new().with_max_retries(1);
+        let command = CommandVariant::QueryBootPartNumberCommand(QueryBootPartNumberCommand::new());
+
+        protocol.handle_intent(HostIntent::Send(command));
+
+        assert!(protocol.poll_transmit().is_some());
+        protocol.handle_elapsed(protocol.reply_timeout_ms());
+        assert!(protocol.poll_transmit().is_some());
+        protocol.handle_elapsed(protocol.reply_timeout_ms());
+        assert_eq!(protocol.poll_transmit(), None);
+
+        match protocol.poll_event() {
+            Some(HostEvent::CommandFailed(_, err)) => assert_eq!(err.code(), ErrorCode::Timeout),
+            event => panic!("unexpected event: {event:?}"),
+        }
+        assert!(!protocol.is_busy());
+    }
+
+    #[test]
+    fn test_queue_full() {
+        let mut protocol = HostProtocol::new();
+        let command = CommandVariant::QueryBootPartNumberCommand(QueryBootPartNumberCommand::new());
+
+        // the first command is in flight, the rest fill the queue
+        for _ in 0..=MAX_QUEUED_COMMANDS {
+            protocol.handle_intent(HostIntent::Send(command));
+        }
+        assert_eq!(protocol.poll_event(), None);
+
+        protocol.handle_intent(HostIntent::Send(command));
+
+        match protocol.poll_event() {
+            Some(HostEvent::CommandFailed(failed, err)) => {
+                assert_eq!(failed, command);
+                assert_eq!(err.message(), "command queue is full");
+            }
+            event => panic!("unexpected event: {event:?}"),
+        }
+    }
+
+    #[test]
+    fn test_stack_intent() {
+        let mut protocol = HostProtocol::new();
+
+        protocol.handle_intent(HostIntent::Stack);
+        protocol.handle_intent(HostIntent::StartPolling);
+
+        let poll = protocol.poll_transmit().unwrap();
+        let mut cmd = OmnibusCommand::new();
+        cmd.from_buf(poll.buf()).unwrap();
+        assert_eq!(cmd.document_stack(), DocumentStack::Set);
+
+        protocol.handle_input(OMNIBUS_REPLY_ACK.as_ref());
+        protocol.handle_elapsed(protocol.poll_interval_ms());
+
+        // the stack bit is only set for a single poll
+        let poll = protocol.poll_transmit().unwrap();
+        cmd.from_buf(poll.buf()).unwrap();
+        assert_eq!(cmd.document_stack(), DocumentStack::Unset);
+        assert_eq!(poll.buf()[index::CONTROL] & 0b1, 1);
+    }
+
+    #[test]
+    fn test_stack_intent_requeued() {
+        let mut protocol = HostProtocol::new().with_max_retries(0);
+
+        protocol.handle_intent(HostIntent::Stack);
+        protocol.handle_intent(HostIntent::StartPolling);
+        protocol.poll_transmit().unwrap();
+
+        protocol.handle_elapsed(protocol.reply_timeout_ms());
+        assert!(matches!(
+            protocol.poll_event(),
+            Some(HostEvent::CommandFailed(..))
+        ));
+
+        // the failed poll never reached the device, so the next poll carries the stack bit
+        protocol.handle_elapsed(protocol.poll_interval_ms());
+        let poll = protocol.poll_transmit().unwrap();
+        let mut cmd = OmnibusCommand::new();
+        cmd.from_buf(poll.buf()).unwrap();
+        assert_eq!(cmd.document_stack(), DocumentStack::Set);
+    }
+
+    #[test]
+    fn test_stale_bytes_discarded() {
+        let mut protocol = HostProtocol::new().with_max_retries(0);
+        protocol.handle_intent(HostIntent::StartPolling);
+        protocol.poll_transmit().unwrap();
+
+        // a truncated reply, then the poll times out
+        protocol.handle_input(OMNIBUS_REPLY_ACK[..4].as_ref());
+        protocol.handle_elapsed(protocol.reply_timeout_ms());
+        assert!(matches!(
+            protocol.poll_event(),
+            Some(HostEvent::CommandFailed(..))
+        ));
+
+        // the stale bytes are counted once the reply to the next poll arrives
+        protocol.handle_elapsed(protocol.poll_interval_ms());
+        protocol.poll_transmit().unwrap();
+        protocol.handle_input(OMNIBUS_REPLY_ACK.as_ref());
+
+        assert!(matches!(protocol.poll_event(), Some(HostEvent::Reply(_))));
+        assert_eq!(protocol.poll_event(), Some(HostEvent::BytesDiscarded(4)));
+    }
+
+    #[test]
+    fn test_bookmark_mode() {
+        let mut protocol = HostProtocol::new().with_bookmark_mode(true);
+
+        protocol.handle_intent(HostIntent::Stack);
+        protocol.handle_intent(HostIntent::StartPolling);
+
+        let poll = protocol.poll_transmit().unwrap();
+        assert_eq!(poll.message_type(), MessageType::OmnibusBookmark);
+
+        let mut cmd = OmnibusBookmarkCommand::new();
+        cmd.from_buf(poll.buf()).unwrap();
+        assert_eq!(cmd.document_stack(), DocumentStack::Set);
+
+        // bookmark escrowed, without a denomination
+        protocol.handle_input(&[
+            0x02, 0x0b, 0x30, 0x04, 0x10, 0x00, 0x00, 0x00, 0x00, 0x03, 0x2f,
+        ]);
+
+        match protocol.poll_event() {
+            Some(HostEvent::Reply(reply)) => {
+                assert!(reply.is_omnibus_bookmark_reply());
+                assert_eq!(
+                    reply.document_status().document_type(),
+                    DocumentType::Bookmark
+                );
+            }
+            event => panic!("expected a bookmark reply, have: {event:?}"),
+        }
+    }
+}
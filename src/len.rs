@@ -1,6 +1,9 @@
 pub const OMNIBUS_COMMAND: usize = 8;
 pub const OMNIBUS_REPLY: usize = 11;
 
+pub const OMNIBUS_BOOKMARK_COMMAND: usize = 8;
+pub const OMNIBUS_BOOKMARK_REPLY: usize = 11;
+
 pub const AUX_COMMAND: usize = 8;
 pub const AUX_REPLY: usize = 11;
 
@@ -42,15 +45,41 @@ pub const QUERY_VALUE_TABLE_REPLY: usize = 82;
 pub const APPLICATION_ID_COMMAND: usize = 8;
 pub const APPLICATION_ID_REPLY: usize = 14;
 
+pub const EXTENDED_BARCODE_REPLY: usize = 36;
+
 pub const ADVANCED_BOOKMARK_MODE_COMMAND: usize = 10;
 pub const ADVANCED_BOOKMARK_MODE_REPLY: usize = 13;
 
 pub const QUERY_SOFTWARE_CRC_COMMAND: usize = 8;
 pub const QUERY_SOFTWARE_CRC_REPLY: usize = 11;
 
+pub const QUERY_ACCEPTOR_TYPE_COMMAND: usize = 8;
+pub const QUERY_ACCEPTOR_TYPE_REPLY: usize = 25;
+
+pub const QUERY_ACCEPTOR_SERIAL_NUMBER_COMMAND: usize = 8;
+pub const QUERY_ACCEPTOR_SERIAL_NUMBER_REPLY: usize = 25;
+
+pub const QUERY_AUDIT_LIFE_TIME_TOTALS_COMMAND: usize = 8;
+pub const QUERY_AUDIT_LIFE_TIME_TOTALS_REPLY: usize = 53;
+
+pub const QUERY_AUDIT_QP_MEASURES_COMMAND: usize = 8;
+pub const QUERY_AUDIT_QP_MEASURES_REPLY: usize = 37;
+
+pub const QUERY_AUDIT_PERFORMANCE_MEASURES_COMMAND: usize = 8;
+pub const QUERY_AUDIT_PERFORMANCE_MEASURES_REPLY: usize = 65;
+
 pub const QUERY_BOOT_PART_NUMBER_COMMAND: usize = 8;
 pub const QUERY_BOOT_PART_NUMBER_REPLY: usize = 14;
 
+pub const QUERY_CASH_BOX_TOTAL_COMMAND: usize = 8;
+pub const QUERY_CASH_BOX_TOTAL_REPLY: usize = 11;
+
+pub const QUERY_DEVICE_RESETS_COMMAND: usize = 8;
+pub const QUERY_DEVICE_RESETS_REPLY: usize = 11;
+
+pub const CLEAR_CASH_BOX_TOTAL_COMMAND: usize = 8;
+pub const CLEAR_CASH_BOX_TOTAL_REPLY: usize = 11;
+
 pub const QUERY_APPLICATION_PART_NUMBER_COMMAND: usize = 8;
 pub const QUERY_APPLICATION_PART_NUMBER_REPLY: usize = 14;
 
@@ -71,6 +100,7 @@ pub const SOFT_RESET: usize = 8;
 
 pub const SET_ESCROW_TIMEOUT_COMMAND: usize = 11;
 pub const SET_ESCROW_TIMEOUT_REPLY: usize = 12;
+pub const EXTENDED_COUPON_REPLY: usize = 17;
 
 pub const NOTE_RETRIEVED_COMMAND: usize = 10;
 pub const NOTE_RETRIEVED_REPLY: usize = 13;
@@ -41,6 +41,22 @@
 //! ```toml
 //! ebds = { version = "0.1", features = ["std"] }
 //! ```
+//!
+//! ## Using with `tokio`
+//!
+//! An async device handle, `AsyncDevice`, is available with the `tokio` feature (implies `std`):
+//!
+//! ```toml
+//! ebds = { version = "0.1", features = ["tokio"] }
+//! ```
+//!
+//! ## Using with `embedded-io`
+//!
+//! A heap-free transport for microcontrollers, `EmbeddedTransport`, is available with the `embedded-io` feature:
+//!
+//! ```toml
+//! ebds = { version = "0.1", features = ["embedded-io"] }
+//! ```
 
 #[macro_use(format)]
 extern crate alloc;
@@ -84,20 +100,46 @@ pub use status::*;
 
 /// Advanced Bookmark Mode - Extended (Type 0x07, Subtype 0x0D)
 pub mod advanced_bookmark_mode;
+/// Async device handle for host-device communication
+#[cfg(feature = "tokio")]
+pub mod async_device;
 /// Generic types for Auxilliary Command/Reply messages - Auxilliary (Type 0x06)
 pub mod aux_command;
+/// Calibrate - Calibrate (Type 0x04)
+pub mod calibrate;
 /// Clear Audit Data - Extended (Type 0x07, Subtype 0x1D)
 pub mod clear_audit_data;
+/// Clear Cash Box Total - Auxilliary (Type 0x06, Subtype 0x03)
+pub mod clear_cash_box_total;
+/// Typed device events detected from successive Omnibus replies
+pub mod device_event;
+/// Omnibus poll loop emitting typed device events
+pub mod device_poller;
+/// Transport for `no_std` targets over `embedded-io` serial traits
+#[cfg(feature = "embedded-io")]
+pub mod embedded_transport;
+/// Escrow session driving escrowed documents to a final outcome
+pub mod escrow_session;
+/// Extended Barcode - Extended (Type 0x07, Subtype 0x01)
+pub mod extended_barcode;
 /// Generic types for Extended Command messages - Extended (Type 0x07)
 pub mod extended_command;
+/// Extended Coupon - Extended (Type 0x07, Subtype 0x04)
+pub mod extended_coupon;
 /// Extended Note Inhibits - Extended (Type 0x07, Subtype 0x03)
 pub mod extended_note_inhibits;
 /// Extended Note Specification - Extended (Type 0x07, Subtype 0x02)
 pub mod extended_note_specification;
 /// Generic types for Extended Reply messages - Extended (Type 0x07)
 pub mod extended_reply;
+/// Firmware image validation, and manifest verification
+pub mod firmware_image;
 /// Flash Download - (Type 0x05)
 pub mod flash_download;
+/// Incremental frame decoder for raw byte streams
+pub mod frame_decoder;
+/// Sans-IO host protocol state machine
+pub mod host_protocol;
 /// Total message lengths for various messages
 ///
 /// IMPORTANT: this is the total byte length of the packet,
@@ -117,16 +159,32 @@ pub mod len;
 pub mod note_retrieved;
 /// Omnibus - Command (Type 0x01), Reply (Type 0x02)
 pub mod omnibus;
+/// Omnibus Bookmark - Omnibus Bookmark (Type 0x03)
+pub mod omnibus_bookmark;
 /// Part number type definitions, used across multiple messages
 pub mod part_number;
+/// Query Acceptor Serial Number - Auxilliary (Type 0x06, Subtype 0x05)
+pub mod query_acceptor_serial_number;
+/// Query Acceptor Type - Auxilliary (Type 0x06, Subtype 0x04)
+pub mod query_acceptor_type;
 /// Query Application ID - Auxilliary (Type 0x06, Subtype 0x0E)
 pub mod query_application_id;
 /// Query Application Part Number - Auxilliary (Type 0x06, Subtype 0x07)
 pub mod query_application_part_number;
+/// Query Audit Life Time Totals - Auxilliary (Type 0x06, Subtype 0x0A)
+pub mod query_audit_life_time_totals;
+/// Query Audit Performance Measures - Auxilliary (Type 0x06, Subtype 0x0C)
+pub mod query_audit_performance_measures;
+/// Query Audit QP Measures - Auxilliary (Type 0x06, Subtype 0x0B)
+pub mod query_audit_qp_measures;
 /// Query Boot Part Number - Auxilliary (Type 0x06, Subtype 0x06)
 pub mod query_boot_part_number;
+/// Query Cash Box Total - Auxilliary (Type 0x06, Subtype 0x01)
+pub mod query_cash_box_total;
 /// Query Device Capabilities - Auxilliary (Type 0x06, Subtype 0x0D)
 pub mod query_device_capabilities;
+/// Query Device Resets - Auxilliary (Type 0x06, Subtype 0x02)
+pub mod query_device_resets;
 /// Query Software CRC - Auxilliary (Type 0x06, Subtype 0x00)
 pub mod query_software_crc;
 /// Query Value Table - Extended (Type 0x07, Subtype 0x06)
@@ -137,35 +195,65 @@ pub mod query_variant_id;
 pub mod query_variant_name;
 /// Query Variant Part Number - Auxilliary (Type 0x06, Subtype 0x09)
 pub mod query_variant_part_number;
+/// ACK/NAK sequencing for host-device message exchanges
+pub mod sequencer;
 /// Set Escrow Timeout - Extended (Type 0x07, Subtype 0x04)
 pub mod set_escrow_timeout;
 /// Soft Reset - Auxilliary (Type 0x06, Subtype 0x7F)
 pub mod soft_reset;
+/// Blocking serial transport for host-device communication
+#[cfg(feature = "std")]
+pub mod transport;
 /// Message variant for building messages from raw bytes
 pub mod variant;
 
 pub use advanced_bookmark_mode::*;
+#[cfg(feature = "tokio")]
+pub use async_device::*;
 pub use aux_command::*;
+pub use calibrate::*;
 pub use clear_audit_data::*;
+pub use clear_cash_box_total::*;
+pub use device_event::*;
+pub use device_poller::*;
+#[cfg(feature = "embedded-io")]
+pub use embedded_transport::*;
+pub use escrow_session::*;
+pub use extended_barcode::*;
 pub use extended_command::*;
+pub use extended_coupon::*;
 pub use extended_note_inhibits::*;
 pub use extended_note_specification::*;
 pub use extended_reply::*;
+pub use firmware_image::*;
 pub use flash_download::*;
+pub use frame_decoder::*;
+pub use host_protocol::*;
 pub use note_retrieved::*;
 pub use omnibus::*;
+pub use omnibus_bookmark::*;
 pub use part_number::*;
+pub use query_acceptor_serial_number::*;
+pub use query_acceptor_type::*;
 pub use query_application_id::*;
 pub use query_application_part_number::*;
+pub use query_audit_life_time_totals::*;
+pub use query_audit_performance_measures::*;
+pub use query_audit_qp_measures::*;
 pub use query_boot_part_number::*;
+pub use query_cash_box_total::*;
 pub use query_device_capabilities::*;
+pub use query_device_resets::*;
 pub use query_software_crc::*;
 pub use query_value_table::*;
 pub use query_variant_id::*;
 pub use query_variant_name::*;
 pub use query_variant_part_number::*;
+pub use sequencer::*;
 pub use set_escrow_timeout::*;
 pub use soft_reset::*;
+#[cfg(feature = "std")]
+pub use transport::*;
 pub use variant::*;
 
 pub use crate::error::{Error, JsonRpcError, JsonRpcResult, Result};
@@ -260,6 +348,40 @@ pub(crate) fn u8_seven_bit(n: u8) -> [u8; 2] {
     [n >> 4, n & 0xf]
 }
 
+// Under the 7-bit protocol, constructs a 32-bit number from a 8-byte slice.
+//
+// Each byte stores the significant bits in the lower nibble (4-bits),
+// most significant nibble first (big-endian).
+pub(crate) fn seven_bit_u32(b: &[u8]) -> u32 {
+    debug_assert_eq!(b.len(), 8);
+
+    b.iter().fold(0u32, |acc, &n| (acc << 4) | (n & 0xf) as u32)
+}
+
+// Under the 7-bit protocol, constructs a 24-bit number from a 6-byte slice.
+//
+// Each byte stores the significant bits in the lower nibble (4-bits),
+// most significant nibble first (big-endian).
+pub(crate) fn seven_bit_u24(b: &[u8]) -> u32 {
+    debug_assert_eq!(b.len(), 6);
+
+    b.iter().fold(0u32, |acc, &n| (acc << 4) | (n & 0xf) as u32)
+}
+
+// Under the 7-bit protocol, transforms a 24-bit number into a 6-byte slice.
+//
+// Each byte stores the significant bits in the lower nibble (4-bits),
+// most significant nibble first (big-endian). Bits above 24 are truncated.
+pub(crate) fn u24_seven_bit(n: u32) -> [u8; 6] {
+    let mut b = [0u8; 6];
+
+    for (i, nibble) in b.iter_mut().enumerate() {
+        *nibble = ((n >> (20 - (i * 4))) & 0xf) as u8;
+    }
+
+    b
+}
+
 bitfield! {
     /// Control field for EBDS messages
     pub struct Control(u8);
@@ -405,9 +527,9 @@ pub enum MessageType {
     OmnibusCommand = 0b001,
     /// Generic omnibus reply message, see [OmnibusReply](crate::OmnibusReply)
     OmnibusReply = 0b010,
-    /// Generic omnibus bookmark message, see [OmnibusBookmark](crate::OmnibusBookmark)
+    /// Generic omnibus bookmark message, see [OmnibusBookmarkCommand](crate::OmnibusBookmarkCommand)
     OmnibusBookmark = 0b011,
-    /// Calibrate message, see [Calibrate](crate::Calibrate)
+    /// Calibrate message, see [CalibrateCommand](crate::CalibrateCommand)
     Calibrate = 0b100,
     /// Firmware download message (response and reply), see [FirmwareDownload](crate::flash_download)
     FirmwareDownload = 0b101,
@@ -724,4 +846,24 @@ mod tests {
         assert_eq!(u8_seven_bit(expected), expected_bytes);
         assert_eq!(seven_bit_u8(expected_bytes.as_ref()), expected);
     }
+
+    #[test]
+    fn test_u32_seven_bit() {
+        let expected = 0x1234_5678;
+        let expected_bytes = [0x1, 0x2, 0x3, 0x4, 0x5, 0x6, 0x7, 0x8];
+
+        assert_eq!(seven_bit_u32(expected_bytes.as_ref()), expected);
+        assert_eq!(seven_bit_u32([0xf; 8].as_ref()), u32::MAX);
+    }
+
+    #[test]
+    fn test_u24_seven_bit() {
+        let expected = 0x12_3456;
+        let expected_bytes = [0x1, 0x2, 0x3, 0x4, 0x5, 0x6];
+
+        assert_eq!(u24_seven_bit(expected), expected_bytes);
+        assert_eq!(seven_bit_u24(expected_bytes.as_ref()), expected);
+        assert_eq!(seven_bit_u24(u24_seven_bit(0xff_ffff).as_ref()), 0xff_ffff);
+        assert_eq!(u24_seven_bit(0x100_0000), [0u8; 6]);
+    }
 }
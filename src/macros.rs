@@ -599,7 +599,7 @@ macro_rules! inner_enum {
 
                     match self {
                         $ty::$var(ty) => Ok(ty),
-                        _ => Err(Error::failure(format!("have variant: {self}, expected: {}", $crate::std::any::type_name::<$var>()))),
+                        _ => Err(Error::failure(format!("have variant: {self}, expected: {}", $crate::std::any::type_name::<$var_ty>()))),
                     }
                 }
 
@@ -609,7 +609,7 @@ macro_rules! inner_enum {
 
                     match self {
                         $ty::$var(ty) => Ok(ty),
-                        _ => Err(Error::failure(format!("have variant: {self}, expected: {}", $crate::std::any::type_name::<$var>()))),
+                        _ => Err(Error::failure(format!("have variant: {self}, expected: {}", $crate::std::any::type_name::<$var_ty>()))),
                     }
                 }
             }
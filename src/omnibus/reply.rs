@@ -4,11 +4,15 @@ use std::fmt;
 use crate::{
     banknote::*, cash::CurrencyDenomination, impl_from_for_omnibus_reply, impl_message_ops,
     impl_omnibus_reply_ops, len::OMNIBUS_REPLY, status::*, AdvancedBookmarkModeReply,
-    ClearAuditDataRequestAck, ClearAuditDataRequestResults, ExtendedNoteInhibitsReplyAlt,
-    ExtendedNoteReply, MessageOps, MessageType, NoteRetrievedEvent, NoteRetrievedReply,
-    QueryApplicationIdReply, QueryApplicationPartNumberReply, QueryBootPartNumberReply,
-    QueryDeviceCapabilitiesReply, QueryValueTableReply, QueryVariantIdReply, QueryVariantNameReply,
-    QueryVariantPartNumberReply, SetEscrowTimeoutReply, StandardDenomination,
+    CalibrateReply, ClearAuditDataRequestAck, ClearAuditDataRequestResults, ClearCashBoxTotalReply,
+    ExtendedBarcodeReply, ExtendedCouponReply, ExtendedNoteInhibitsReplyAlt, ExtendedNoteReply,
+    MessageOps, MessageType, NoteRetrievedEvent, NoteRetrievedReply, OmnibusBookmarkReply,
+    QueryAcceptorSerialNumberReply, QueryAcceptorTypeReply, QueryApplicationIdReply,
+    QueryApplicationPartNumberReply, QueryAuditLifeTimeTotalsReply,
+    QueryAuditPerformanceMeasuresReply, QueryAuditQpMeasuresReply, QueryBootPartNumberReply,
+    QueryCashBoxTotalReply, QueryDeviceCapabilitiesReply, QueryDeviceResetsReply,
+    QueryValueTableReply, QueryVariantIdReply, QueryVariantNameReply, QueryVariantPartNumberReply,
+    SetEscrowTimeoutReply, StandardDenomination,
 };
 
 pub mod index {
@@ -73,14 +77,26 @@ impl OmnibusReply {
 }
 
 impl_from_for_omnibus_reply!(AdvancedBookmarkModeReply);
+impl_from_for_omnibus_reply!(CalibrateReply);
+impl_from_for_omnibus_reply!(OmnibusBookmarkReply);
 impl_from_for_omnibus_reply!(ClearAuditDataRequestAck);
 impl_from_for_omnibus_reply!(ClearAuditDataRequestResults);
+impl_from_for_omnibus_reply!(ExtendedBarcodeReply);
+impl_from_for_omnibus_reply!(ExtendedCouponReply);
 impl_from_for_omnibus_reply!(ExtendedNoteReply);
 impl_from_for_omnibus_reply!(ExtendedNoteInhibitsReplyAlt);
 impl_from_for_omnibus_reply!(NoteRetrievedReply);
 impl_from_for_omnibus_reply!(NoteRetrievedEvent);
 impl_from_for_omnibus_reply!(QueryValueTableReply);
 impl_from_for_omnibus_reply!(SetEscrowTimeoutReply);
+impl_from_for_omnibus_reply!(QueryCashBoxTotalReply);
+impl_from_for_omnibus_reply!(QueryDeviceResetsReply);
+impl_from_for_omnibus_reply!(ClearCashBoxTotalReply);
+impl_from_for_omnibus_reply!(QueryAcceptorTypeReply);
+impl_from_for_omnibus_reply!(QueryAcceptorSerialNumberReply);
+impl_from_for_omnibus_reply!(QueryAuditLifeTimeTotalsReply);
+impl_from_for_omnibus_reply!(QueryAuditQpMeasuresReply);
+impl_from_for_omnibus_reply!(QueryAuditPerformanceMeasuresReply);
 impl_from_for_omnibus_reply!(QueryBootPartNumberReply);
 impl_from_for_omnibus_reply!(QueryApplicationPartNumberReply);
 impl_from_for_omnibus_reply!(QueryVariantNameReply);
@@ -493,7 +509,23 @@ impl From<&OmnibusReply> for Banknote {
 
 impl From<&dyn OmnibusReplyOps> for DocumentStatus {
     fn from(reply: &dyn OmnibusReplyOps) -> Self {
-        Self::default().with_standard_denomination(reply.note_value())
+        let note_value = reply.note_value();
+        let state = reply.device_state();
+
+        let document_type = if note_value != StandardDenomination::none() {
+            DocumentType::Banknote
+        } else if reply.message_type() == MessageType::OmnibusBookmark
+            && (state.escrowed_state() || state.stacked_event())
+        {
+            // bookmarks are reported without a denomination
+            DocumentType::Bookmark
+        } else {
+            DocumentType::NoDocument
+        };
+
+        Self::default()
+            .with_standard_denomination(note_value)
+            .with_document_type(document_type)
     }
 }
 
@@ -0,0 +1,125 @@
+use crate::{
+    impl_default, impl_message_ops, impl_omnibus_command_ops, len::OMNIBUS_BOOKMARK_COMMAND,
+    std::fmt, EscrowMode, MessageOps, MessageType, OmnibusCommand, OmnibusCommandOps,
+};
+
+/// Omnibus Bookmark - Command (Type 3)
+///
+/// | **S2K** | **CFSC** | **SC Adv** | **SCR** |
+/// |:-------:|:--------:|:----------:|:-------:|
+///
+/// The Omnibus Bookmark Command is identical to the [OmnibusCommand], except for the message type.
+/// Sending the bookmark message type in place of the omnibus command type puts the device in
+/// bookmark mode: along with banknotes, the device accepts bookmarks (receipts, slips, and other
+/// documents of the right size) for as long as the host keeps polling with this command.
+///
+/// Bookmarks have no value, and are reported in escrow without a denomination, see
+/// [DocumentType::Bookmark](crate::DocumentType::Bookmark). The host decides to stack or return a
+/// bookmark the same way as a banknote.
+///
+/// Unlike the [AdvancedBookmarkModeCommand](crate::AdvancedBookmarkModeCommand), valid banknotes
+/// are still accepted in bookmark mode.
+///
+/// The Omnibus Bookmark Command is formatted as follows:
+///
+/// | Name  | STX  | LEN  | CTRL | Data 0 | Data 1 | Data 2 | ETX  | CHK |
+/// |:------|:----:|:----:|:----:|:------:|:------:|:------:|:----:|:---:|
+/// | Byte  | 0    | 1    | 2    | 3      | 4      | 5      | 6    | 7   |
+/// | Value | 0x02 | 0x08 | 0x3n | nn     | nn     | nn     | 0x03 | zz  |
+///
+/// The data bytes are the same as the [OmnibusCommand] data bytes.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct OmnibusBookmarkCommand {
+    buf: [u8; OMNIBUS_BOOKMARK_COMMAND],
+}
+
+impl OmnibusBookmarkCommand {
+    /// Creates a new [OmnibusBookmarkCommand].
+    pub fn new() -> Self {
+        let mut message = Self {
+            buf: [0u8; OMNIBUS_BOOKMARK_COMMAND],
+        };
+
+        message.init();
+        message.set_message_type(MessageType::OmnibusBookmark);
+        message.set_escrow_mode(EscrowMode::Set);
+
+        message
+    }
+}
+
+impl_default!(OmnibusBookmarkCommand);
+impl_message_ops!(OmnibusBookmarkCommand);
+impl_omnibus_command_ops!(OmnibusBookmarkCommand);
+
+impl From<&OmnibusCommand> for OmnibusBookmarkCommand {
+    fn from(command: &OmnibusCommand) -> Self {
+        let mut message = Self::new();
+
+        message.set_acknak(command.acknak());
+        message.set_device_type(command.device_type());
+        message.set_denomination(command.denomination());
+        message.set_operational_mode(command.operational_mode());
+        message.set_configuration(command.configuration());
+
+        message
+    }
+}
+
+impl From<OmnibusCommand> for OmnibusBookmarkCommand {
+    fn from(command: OmnibusCommand) -> Self {
+        (&command).into()
+    }
+}
+
+impl fmt::Display for OmnibusBookmarkCommand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{{")?;
+        write!(f, r#""acknak": {}, "#, self.acknak())?;
+        write!(f, r#""device_type": {}, "#, self.device_type())?;
+        write!(f, r#""message_type": {}, "#, self.message_type())?;
+        write!(f, r#""denomination": {}, "#, self.denomination())?;
+        write!(f, r#""operational_mode": {}, "#, self.operational_mode())?;
+        write!(f, r#""configuration": {}"#, self.configuration())?;
+        write!(f, "}}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DocumentStack, Result, StandardDenomination};
+
+    #[test]
+    #[rustfmt::skip]
+    fn test_omnibus_bookmark_command_from_buf() -> Result<()> {
+        let msg_bytes = [
+            // STX | LEN | Message Type
+            0x02, 0x08, 0x30,
+            // Data
+            0x7f, 0x30, 0x10,
+            // ETX | Checksum
+            0x03, 0x67,
+        ];
+
+        let mut msg = OmnibusBookmarkCommand::new();
+        msg.from_buf(msg_bytes.as_ref())?;
+
+        assert_eq!(msg.message_type(), MessageType::OmnibusBookmark);
+        assert_eq!(msg.denomination(), StandardDenomination::all());
+        assert_eq!(msg.document_stack(), DocumentStack::Set);
+
+        let mut omnibus = OmnibusCommand::new();
+        omnibus.set_denomination(StandardDenomination::all());
+        omnibus.set_document_stack(DocumentStack::Set);
+        omnibus.set_configuration(msg.configuration());
+
+        assert_eq!(
+            OmnibusBookmarkCommand::from(omnibus).as_bytes(),
+            msg_bytes.as_ref()
+        );
+
+        Ok(())
+    }
+}
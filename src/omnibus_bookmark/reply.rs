@@ -0,0 +1,100 @@
+use crate::std;
+use std::fmt;
+
+use crate::{
+    impl_default, impl_message_ops, impl_omnibus_reply_ops, len::OMNIBUS_BOOKMARK_REPLY,
+    MessageOps, MessageType, OmnibusReplyOps,
+};
+
+/// Omnibus Bookmark - Reply (Type 3)
+///
+/// | **S2K** | **CFSC** | **SC Adv** | **SCR** |
+/// |:-------:|:--------:|:----------:|:-------:|
+///
+/// Reply to an [OmnibusBookmarkCommand](crate::OmnibusBookmarkCommand), carrying the standard
+/// Omnibus status bytes. Devices may also reply to the bookmark command with a standard
+/// [OmnibusReply](crate::OmnibusReply).
+///
+/// An escrowed or stacked document without a denomination is a bookmark, see
+/// [DocumentType::Bookmark](crate::DocumentType::Bookmark).
+///
+/// The Omnibus Bookmark Reply is formatted as follows:
+///
+/// | Name  | STX  | LEN  | CTRL | Data 0 | Data 1 | Data 2 | Data 3 | Data 4 | Data 5 | ETX  | CHK |
+/// |:------|:----:|:----:|:----:|:------:|:------:|:------:|:------:|:------:|:------:|:----:|:---:|
+/// | Byte  | 0    | 1    | 2    | 3      | 4      | 5      | 6      | 7      | 8      | 9    | 10  |
+/// | Value | 0x02 | 0x0B | 0x3n | nn     | nn     | nn     | nn     | nn     | nn     | 0x03 | zz  |
+///
+/// The data bytes are the same as the [OmnibusReply](crate::OmnibusReply) data bytes.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct OmnibusBookmarkReply {
+    buf: [u8; OMNIBUS_BOOKMARK_REPLY],
+}
+
+impl OmnibusBookmarkReply {
+    /// Creates a new [OmnibusBookmarkReply].
+    pub fn new() -> Self {
+        let mut message = Self {
+            buf: [0u8; OMNIBUS_BOOKMARK_REPLY],
+        };
+
+        message.init();
+        message.set_message_type(MessageType::OmnibusBookmark);
+
+        message
+    }
+}
+
+impl_default!(OmnibusBookmarkReply);
+impl_message_ops!(OmnibusBookmarkReply);
+impl_omnibus_reply_ops!(OmnibusBookmarkReply);
+
+impl fmt::Display for OmnibusBookmarkReply {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "AckNak: {}, DeviceType: {}, MessageType: {}, DeviceState: {}, DeviceStatus: {}, ExceptionStatus: {}, MiscDeviceState: {}, ModelNumber: {}, CodeRevision: {}",
+            self.acknak(),
+            self.device_type(),
+            self.message_type(),
+            self.device_state(),
+            self.device_status(),
+            self.exception_status(),
+            self.misc_device_state(),
+            self.model_number(),
+            self.code_revision(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DocumentStatus, DocumentType, Result, StandardDenomination};
+
+    #[test]
+    #[rustfmt::skip]
+    fn test_omnibus_bookmark_reply_from_buf() -> Result<()> {
+        let msg_bytes = [
+            // STX | LEN | Message Type
+            0x02, 0x0b, 0x30,
+            // Data (escrowed, no denomination)
+            0x04, 0x10, 0x00, 0x00, 0x00, 0x00,
+            // ETX | Checksum
+            0x03, 0x2f,
+        ];
+
+        let mut msg = OmnibusBookmarkReply::new();
+        msg.from_buf(msg_bytes.as_ref())?;
+
+        assert_eq!(msg.message_type(), MessageType::OmnibusBookmark);
+        assert!(msg.device_state().escrowed_state());
+        assert_eq!(msg.note_value(), StandardDenomination::none());
+
+        let document = DocumentStatus::from(&msg as &dyn OmnibusReplyOps);
+        assert_eq!(document.document_type(), DocumentType::Bookmark);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,221 @@
+use crate::std;
+use std::fmt;
+
+use crate::{
+    impl_default, impl_message_ops, impl_omnibus_nop_reply,
+    len::QUERY_ACCEPTOR_SERIAL_NUMBER_REPLY, Error, MessageOps, MessageType, Result,
+};
+
+/// Length of the serial number data field (in bytes).
+pub const SERIAL_NUMBER_LEN: usize = 20;
+
+pub mod index {
+    use super::SERIAL_NUMBER_LEN;
+
+    pub const DATA: usize = 3;
+    pub const DATA_END: usize = DATA + SERIAL_NUMBER_LEN;
+}
+
+/// A validated factory serial number of the acceptor.
+///
+/// The serial number is up to [SERIAL_NUMBER_LEN] ASCII alphanumeric characters, or `-`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SerialNumber {
+    buf: [u8; SERIAL_NUMBER_LEN],
+    len: usize,
+}
+
+impl SerialNumber {
+    /// Creates a new [SerialNumber] from the serial number characters.
+    ///
+    /// Returns an error if the serial number is empty, longer than [SERIAL_NUMBER_LEN] characters,
+    /// or contains characters other than ASCII alphanumerics, or `-`.
+    pub fn new(serial_number: &str) -> Result<Self> {
+        let bytes = serial_number.as_bytes();
+        let len = bytes.len();
+
+        if len == 0 {
+            return Err(Error::failure("empty serial number"));
+        }
+
+        if len > SERIAL_NUMBER_LEN {
+            return Err(Error::failure(format!(
+                "serial number too long, have: {len}, maximum: {SERIAL_NUMBER_LEN}"
+            )));
+        }
+
+        if let Some(b) = bytes
+            .iter()
+            .find(|&&b| !(b.is_ascii_alphanumeric() || b == b'-'))
+        {
+            return Err(Error::failure(format!(
+                "invalid serial number character: 0x{b:02x}"
+            )));
+        }
+
+        let mut buf = [0u8; SERIAL_NUMBER_LEN];
+        buf[..len].copy_from_slice(bytes);
+
+        Ok(Self { buf, len })
+    }
+
+    /// Parses a [SerialNumber] from the data field of a [QueryAcceptorSerialNumberReply].
+    ///
+    /// The serial number ends at the first non-printable character (`0x00`), and trailing spaces
+    /// are removed before validation.
+    pub fn from_field(field: &[u8]) -> Result<Self> {
+        let field = &field[..field.len().min(SERIAL_NUMBER_LEN)];
+        let end = field
+            .iter()
+            .position(|b| !b.is_ascii_graphic() && *b != b' ')
+            .unwrap_or(field.len());
+
+        let serial_number = std::str::from_utf8(&field[..end])
+            .map_err(|_| Error::failure("invalid serial number characters"))?;
+
+        Self::new(serial_number.trim_end())
+    }
+
+    /// Gets the serial number as a string.
+    pub fn as_str(&self) -> &str {
+        // only ASCII is accepted on construction
+        std::str::from_utf8(&self.buf[..self.len]).unwrap_or_default()
+    }
+
+    /// Gets the number of serial number characters.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Gets whether the serial number is empty.
+    ///
+    /// Always false for a validated serial number.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+impl fmt::Display for SerialNumber {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Query Acceptor Serial Number - Reply (Subtype 0x05)
+///
+/// | **S2K** | **CFSC** | **SC Adv** | **SCR** |
+/// |:-------:|:--------:|:----------:|:-------:|
+///
+/// The data returned by the device takes the form of an ASCII string that is either 20 bytes long or
+/// is terminated by a non-printable character (`0x00`).
+///
+/// The Query Acceptor Serial Number Reply is formatted as follows:
+///
+/// | Name  | STX  | LEN  | CTRL | Data 0 | Data 1 | ... | Data 19 | ETX  | CHK |
+/// |:------|:----:|:----:|:----:|:------:|:------:|:---:|:-------:|:----:|:---:|
+/// | Byte  | 0    | 1    | 2    | 3      | 4      | ... | 22      | 23   | 24  |
+/// | Value | 0x02 | 0x19 | 0x6n | nn     | nn     | nn  | nn      | 0x03 | zz  |
+///
+/// The serial number is validated on access, see [SerialNumber].
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct QueryAcceptorSerialNumberReply {
+    buf: [u8; QUERY_ACCEPTOR_SERIAL_NUMBER_REPLY],
+}
+
+impl QueryAcceptorSerialNumberReply {
+    /// Creates a new [QueryAcceptorSerialNumberReply].
+    pub fn new() -> Self {
+        let mut message = Self {
+            buf: [0u8; QUERY_ACCEPTOR_SERIAL_NUMBER_REPLY],
+        };
+
+        message.init();
+        message.set_message_type(MessageType::AuxCommand);
+
+        message
+    }
+
+    /// Gets the raw serial number data field, including padding.
+    pub fn serial_number_field(&self) -> &[u8] {
+        self.buf[index::DATA..index::DATA_END].as_ref()
+    }
+
+    /// Gets the validated [SerialNumber].
+    ///
+    /// Returns an error if the serial number data field is empty, or contains invalid characters.
+    pub fn serial_number(&self) -> Result<SerialNumber> {
+        SerialNumber::from_field(self.serial_number_field())
+    }
+
+    /// Sets the serial number data field.
+    pub fn set_serial_number(&mut self, serial_number: &SerialNumber) {
+        self.buf[index::DATA..index::DATA_END].copy_from_slice(serial_number.buf.as_ref());
+    }
+}
+
+impl_default!(QueryAcceptorSerialNumberReply);
+impl_message_ops!(QueryAcceptorSerialNumberReply);
+impl_omnibus_nop_reply!(QueryAcceptorSerialNumberReply);
+
+impl fmt::Display for QueryAcceptorSerialNumberReply {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "AckNak: {}, DeviceType: {}, MessageType: {}, SerialNumber: ",
+            self.acknak(),
+            self.device_type(),
+            self.message_type(),
+        )?;
+
+        match self.serial_number() {
+            Ok(serial_number) => write!(f, "{serial_number}"),
+            Err(_) => write!(f, "Invalid"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[rustfmt::skip]
+    fn test_query_acceptor_serial_number_reply_from_buf() -> Result<()> {
+        let msg_bytes = [
+            // STX | LEN | Message Type
+            0x02, 0x19, 0x60,
+            // Data (serial number in ASCII)
+            b'1', b'2', b'3', b'4', b'5', b'6', b'7', b'8', b'9', b'0',
+            b'\0', b'\0', b'\0', b'\0', b'\0', b'\0', b'\0', b'\0', b'\0', b'\0',
+            // ETX | Checksum
+            0x03, 0x78,
+        ];
+
+        let mut msg = QueryAcceptorSerialNumberReply::new();
+        msg.from_buf(msg_bytes.as_ref())?;
+
+        assert_eq!(msg.message_type(), MessageType::AuxCommand);
+        assert_eq!(msg.serial_number()?.as_str(), "1234567890");
+
+        let mut exp_msg = QueryAcceptorSerialNumberReply::new();
+        exp_msg.set_serial_number(&SerialNumber::new("1234567890")?);
+
+        assert_eq!(exp_msg.as_bytes(), msg_bytes.as_ref());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_serial_number_validation() {
+        assert!(SerialNumber::new("").is_err());
+        assert!(SerialNumber::new("12345 67890").is_err());
+        assert!(SerialNumber::new("123456789012345678901").is_err());
+        assert!(SerialNumber::from_field(&[0u8; SERIAL_NUMBER_LEN]).is_err());
+
+        assert_eq!(
+            SerialNumber::from_field(b"SN-0042   ").map(|s| s.len()),
+            Ok(7)
+        );
+    }
+}
@@ -0,0 +1,202 @@
+use crate::std;
+use std::fmt;
+
+use crate::{
+    impl_default, impl_message_ops, impl_omnibus_nop_reply, len::QUERY_ACCEPTOR_TYPE_REPLY,
+    MessageOps, MessageType,
+};
+
+/// Length of the acceptor type data field (in bytes).
+pub const ACCEPTOR_TYPE_LEN: usize = 20;
+
+pub mod index {
+    use super::ACCEPTOR_TYPE_LEN;
+
+    pub const DATA: usize = 3;
+    pub const DATA_END: usize = DATA + ACCEPTOR_TYPE_LEN;
+}
+
+/// The acceptor device family, parsed from the [QueryAcceptorTypeReply] type name.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum AcceptorType {
+    /// Series 2000 (S2K)
+    Series2000,
+    /// Cashflow SC (CFSC)
+    Cashflow,
+    /// SC Advance (SC Adv)
+    ScAdvance,
+    /// SC Advance Recycler (SCR)
+    ScRecycler,
+    /// Type name not recognized
+    #[default]
+    Unknown,
+}
+
+impl AcceptorType {
+    /// Parses the [AcceptorType] from the type name reported by the device.
+    ///
+    /// Matching is case-insensitive, on the leading characters of the name, e.g. `SCR` and
+    /// `SCR 8327` are both [AcceptorType::ScRecycler].
+    pub fn from_name(name: &str) -> Self {
+        let name = name.trim_start().as_bytes();
+        let starts_with = |prefix: &str| {
+            name.len() >= prefix.len()
+                && name[..prefix.len()].eq_ignore_ascii_case(prefix.as_bytes())
+        };
+
+        if starts_with("S2K") || starts_with("SERIES 2000") {
+            Self::Series2000
+        } else if starts_with("CFSC") || starts_with("CASHFLOW") {
+            Self::Cashflow
+        } else if starts_with("SCR") {
+            Self::ScRecycler
+        } else if starts_with("SC") {
+            Self::ScAdvance
+        } else {
+            Self::Unknown
+        }
+    }
+}
+
+impl From<AcceptorType> for &'static str {
+    fn from(a: AcceptorType) -> Self {
+        match a {
+            AcceptorType::Series2000 => "Series2000",
+            AcceptorType::Cashflow => "Cashflow",
+            AcceptorType::ScAdvance => "ScAdvance",
+            AcceptorType::ScRecycler => "ScRecycler",
+            AcceptorType::Unknown => "Unknown",
+        }
+    }
+}
+
+impl From<&AcceptorType> for &'static str {
+    fn from(a: &AcceptorType) -> Self {
+        (*a).into()
+    }
+}
+
+impl fmt::Display for AcceptorType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", <&str>::from(self))
+    }
+}
+
+/// Query Acceptor Type - Reply (Subtype 0x04)
+///
+/// | **S2K** | **CFSC** | **SC Adv** | **SCR** |
+/// |:-------:|:--------:|:----------:|:-------:|
+///
+/// The data returned by the device takes the form of an ASCII type name that is either 20 bytes long
+/// or is terminated by a non-printable character (`0x00`).
+///
+/// The Query Acceptor Type Reply is formatted as follows:
+///
+/// | Name  | STX  | LEN  | CTRL | Data 0 | Data 1 | ... | Data 19 | ETX  | CHK |
+/// |:------|:----:|:----:|:----:|:------:|:------:|:---:|:-------:|:----:|:---:|
+/// | Byte  | 0    | 1    | 2    | 3      | 4      | ... | 22      | 23   | 24  |
+/// | Value | 0x02 | 0x19 | 0x6n | nn     | nn     | nn  | nn      | 0x03 | zz  |
+///
+/// The type name is parsed into an [AcceptorType].
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct QueryAcceptorTypeReply {
+    buf: [u8; QUERY_ACCEPTOR_TYPE_REPLY],
+}
+
+impl QueryAcceptorTypeReply {
+    /// Creates a new [QueryAcceptorTypeReply].
+    pub fn new() -> Self {
+        let mut message = Self {
+            buf: [0u8; QUERY_ACCEPTOR_TYPE_REPLY],
+        };
+
+        message.init();
+        message.set_message_type(MessageType::AuxCommand);
+
+        message
+    }
+
+    /// Gets the acceptor type name reported by the device.
+    pub fn type_name(&self) -> &str {
+        let name = std::str::from_utf8(self.buf[index::DATA..index::DATA_END].as_ref())
+            .unwrap_or("Unknown");
+        let end = name.find('\0').unwrap_or(name.len());
+
+        name[..end].trim_end()
+    }
+
+    /// Sets the acceptor type name.
+    ///
+    /// Names longer than [ACCEPTOR_TYPE_LEN] bytes are truncated.
+    pub fn set_type_name(&mut self, name: &str) {
+        let name = name.as_bytes();
+        let len = name.len().min(ACCEPTOR_TYPE_LEN);
+
+        let field = &mut self.buf[index::DATA..index::DATA_END];
+        field.fill(0);
+        field[..len].copy_from_slice(&name[..len]);
+    }
+
+    /// Gets the [AcceptorType] parsed from the type name.
+    pub fn acceptor_type(&self) -> AcceptorType {
+        AcceptorType::from_name(self.type_name())
+    }
+}
+
+impl_default!(QueryAcceptorTypeReply);
+impl_message_ops!(QueryAcceptorTypeReply);
+impl_omnibus_nop_reply!(QueryAcceptorTypeReply);
+
+impl fmt::Display for QueryAcceptorTypeReply {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "AckNak: {}, DeviceType: {}, MessageType: {}, TypeName: {}, AcceptorType: {}",
+            self.acknak(),
+            self.device_type(),
+            self.message_type(),
+            self.type_name(),
+            self.acceptor_type(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Result;
+
+    #[test]
+    #[rustfmt::skip]
+    fn test_query_acceptor_type_reply_from_buf() -> Result<()> {
+        let msg_bytes = [
+            // STX | LEN | Message Type
+            0x02, 0x19, 0x60,
+            // Data (type name in ASCII)
+            b'S', b'C', b'R', b' ', b'8', b'3', b'2', b'7',
+            b'\0', b'\0', b'\0', b'\0', b'\0', b'\0', b'\0', b'\0', b'\0', b'\0', b'\0', b'\0',
+            // ETX | Checksum
+            0x03, 0x15,
+        ];
+
+        let mut msg = QueryAcceptorTypeReply::new();
+        msg.from_buf(msg_bytes.as_ref())?;
+
+        assert_eq!(msg.message_type(), MessageType::AuxCommand);
+        assert_eq!(msg.type_name(), "SCR 8327");
+        assert_eq!(msg.acceptor_type(), AcceptorType::ScRecycler);
+
+        let mut exp_msg = QueryAcceptorTypeReply::new();
+        exp_msg.set_type_name("SCR 8327");
+
+        assert_eq!(exp_msg.as_bytes(), msg_bytes.as_ref());
+
+        assert_eq!(AcceptorType::from_name("SC Advance"), AcceptorType::ScAdvance);
+        assert_eq!(AcceptorType::from_name("cfsc"), AcceptorType::Cashflow);
+        assert_eq!(AcceptorType::from_name("S2K"), AcceptorType::Series2000);
+        assert_eq!(AcceptorType::from_name(""), AcceptorType::Unknown);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,82 @@
+use crate::{
+    impl_aux_ops, impl_message_ops, len::QUERY_AUDIT_LIFE_TIME_TOTALS_COMMAND, std::fmt,
+    AuxCommand, AuxCommandOps, MessageOps, MessageType,
+};
+
+/// Query Audit Life Time Totals - Command (Subtype 0x0A)
+///
+/// | **S2K** | **CFSC** | **SC Adv** | **SCR** |
+/// |:-------:|:--------:|:----------:|:-------:|
+///
+/// This command is used to query the life-time audit totals of the device, e.g. operating hours,
+/// and documents stacked, over the life of the device.
+///
+/// The Query Audit Life Time Totals Command is formatted as follows:
+///
+/// | Name  | STX  | LEN  | CTRL | Data A | Data B | Command | ETX  | CHK |
+/// |:------|:----:|:----:|:----:|:------:|:------:|:-------:|:----:|:---:|
+/// | Byte  | 0    | 1    | 2    | 3      | 4      | 5       | 6    | 7   |
+/// | Value | 0x02 | 0x08 | 0x6n | 0x00   | 0x00   | 0x0A    | 0x03 | zz  |
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct QueryAuditLifeTimeTotalsCommand {
+    buf: [u8; QUERY_AUDIT_LIFE_TIME_TOTALS_COMMAND],
+}
+
+impl QueryAuditLifeTimeTotalsCommand {
+    /// Creates a new [QueryAuditLifeTimeTotalsCommand].
+    pub fn new() -> Self {
+        let mut message = Self {
+            buf: [0u8; QUERY_AUDIT_LIFE_TIME_TOTALS_COMMAND],
+        };
+
+        message.init();
+        message.set_message_type(MessageType::AuxCommand);
+        message.set_aux_command(AuxCommand::QueryAuditLifeTimeTotals);
+
+        message
+    }
+}
+
+impl_message_ops!(QueryAuditLifeTimeTotalsCommand);
+impl_aux_ops!(QueryAuditLifeTimeTotalsCommand);
+
+impl fmt::Display for QueryAuditLifeTimeTotalsCommand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{{")?;
+        write!(f, r#""acknak": {}, "#, self.acknak())?;
+        write!(f, r#""device_type": {}, "#, self.device_type())?;
+        write!(f, r#""message_type": {}, "#, self.message_type())?;
+        write!(f, r#""aux_command": {}"#, self.aux_command())?;
+        write!(f, "}}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Result;
+
+    #[test]
+    #[rustfmt::skip]
+    fn test_query_audit_life_time_totals_command_from_buf() -> Result<()> {
+        let msg_bytes = [
+            // STX | LEN | Message Type
+            0x02, 0x08, 0x60,
+            // Data
+            0x00, 0x00,
+            // Command
+            0x0a,
+            // ETX | Checksum
+            0x03, 0x62,
+        ];
+
+        let mut msg = QueryAuditLifeTimeTotalsCommand::new();
+        msg.from_buf(msg_bytes.as_ref())?;
+
+        assert_eq!(msg.message_type(), MessageType::AuxCommand);
+        assert_eq!(msg.aux_command(), AuxCommand::QueryAuditLifeTimeTotals);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,166 @@
+use crate::std;
+use std::fmt;
+
+use crate::{
+    impl_default, impl_message_ops, impl_omnibus_nop_reply,
+    len::QUERY_AUDIT_LIFE_TIME_TOTALS_REPLY, seven_bit_u32, MessageOps, MessageType,
+};
+
+/// Length of a life-time totals counter field (in bytes).
+pub const LIFE_TIME_TOTALS_FIELD_LEN: usize = 8;
+
+pub mod index {
+    pub const DATA: usize = 3;
+    // Field numbers, each field is `LIFE_TIME_TOTALS_FIELD_LEN` bytes long
+    pub const DATA_MAP: usize = 0;
+    pub const OPERATING_HOURS: usize = 1;
+    pub const MOTOR_STARTS: usize = 2;
+    pub const DOCUMENTS_REACHED_ESCROW: usize = 3;
+    pub const DOCUMENTS_PASSED_VALIDATION: usize = 4;
+    pub const DOCUMENTS_STACKED: usize = 5;
+}
+
+/// Query Audit Life Time Totals - Reply (Subtype 0x0A)
+///
+/// | **S2K** | **CFSC** | **SC Adv** | **SCR** |
+/// |:-------:|:--------:|:----------:|:-------:|
+///
+/// The life-time totals are counted over the life of the device, and are not cleared by a
+/// [ClearAuditDataRequest](crate::ClearAuditDataRequest).
+///
+/// The Query Audit Life Time Totals Reply is formatted as follows:
+///
+/// | Name  | STX  | LEN  | CTRL | Field 0 | Field 1 | ... | Field 5 | ETX  | CHK |
+/// |:------|:----:|:----:|:----:|:-------:|:-------:|:---:|:-------:|:----:|:---:|
+/// | Byte  | 0    | 1    | 2    | 3-10    | 11-18   | ... | 43-50   | 51   | 52  |
+/// | Value | 0x02 | 0x35 | 0x6n | nn      | nn      | nn  | nn      | 0x03 | zz  |
+///
+/// Each field is a 32-bit counter, sent in 8 bytes, four bits at a time, most significant nibble
+/// first:
+///
+/// | Field | Counter                     |
+/// |:-----:|:----------------------------|
+/// | 0     | Data map                    |
+/// | 1     | Operating hours             |
+/// | 2     | Motor starts                |
+/// | 3     | Documents reached escrow    |
+/// | 4     | Documents passed validation |
+/// | 5     | Documents stacked           |
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct QueryAuditLifeTimeTotalsReply {
+    buf: [u8; QUERY_AUDIT_LIFE_TIME_TOTALS_REPLY],
+}
+
+impl QueryAuditLifeTimeTotalsReply {
+    /// Creates a new [QueryAuditLifeTimeTotalsReply].
+    pub fn new() -> Self {
+        let mut message = Self {
+            buf: [0u8; QUERY_AUDIT_LIFE_TIME_TOTALS_REPLY],
+        };
+
+        message.init();
+        message.set_message_type(MessageType::AuxCommand);
+
+        message
+    }
+
+    fn field(&self, field: usize) -> u32 {
+        let start = index::DATA + (field * LIFE_TIME_TOTALS_FIELD_LEN);
+        seven_bit_u32(self.buf[start..start + LIFE_TIME_TOTALS_FIELD_LEN].as_ref())
+    }
+
+    /// Gets the data map, identifying the layout of the reported fields.
+    pub fn data_map(&self) -> u32 {
+        self.field(index::DATA_MAP)
+    }
+
+    /// Gets the total number of hours the device has been powered.
+    pub fn operating_hours(&self) -> u32 {
+        self.field(index::OPERATING_HOURS)
+    }
+
+    /// Gets the total number of transport motor starts.
+    pub fn motor_starts(&self) -> u32 {
+        self.field(index::MOTOR_STARTS)
+    }
+
+    /// Gets the total number of documents that reached the escrow position.
+    pub fn documents_reached_escrow(&self) -> u32 {
+        self.field(index::DOCUMENTS_REACHED_ESCROW)
+    }
+
+    /// Gets the total number of documents that passed validation.
+    pub fn documents_passed_validation(&self) -> u32 {
+        self.field(index::DOCUMENTS_PASSED_VALIDATION)
+    }
+
+    /// Gets the total number of documents stacked.
+    pub fn documents_stacked(&self) -> u32 {
+        self.field(index::DOCUMENTS_STACKED)
+    }
+}
+
+impl_default!(QueryAuditLifeTimeTotalsReply);
+impl_message_ops!(QueryAuditLifeTimeTotalsReply);
+impl_omnibus_nop_reply!(QueryAuditLifeTimeTotalsReply);
+
+impl fmt::Display for QueryAuditLifeTimeTotalsReply {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "AckNak: {}, DeviceType: {}, MessageType: {}, DataMap: {}, OperatingHours: {}, MotorStarts: {}, DocumentsReachedEscrow: {}, DocumentsPassedValidation: {}, DocumentsStacked: {}",
+            self.acknak(),
+            self.device_type(),
+            self.message_type(),
+            self.data_map(),
+            self.operating_hours(),
+            self.motor_starts(),
+            self.documents_reached_escrow(),
+            self.documents_passed_validation(),
+            self.documents_stacked(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Result;
+
+    #[test]
+    #[rustfmt::skip]
+    fn test_query_audit_life_time_totals_reply_from_buf() -> Result<()> {
+        let msg_bytes = [
+            // STX | LEN | Message Type
+            0x02, 0x35, 0x60,
+            // Data map
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01,
+            // Operating hours (0x3039 = 12345)
+            0x00, 0x00, 0x00, 0x00, 0x03, 0x00, 0x03, 0x09,
+            // Motor starts (0x0001_0000 = 65536)
+            0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00,
+            // Documents reached escrow (0x4d2 = 1234)
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x04, 0x0d, 0x02,
+            // Documents passed validation (0x4d0 = 1232)
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x04, 0x0d, 0x00,
+            // Documents stacked (0x4cf = 1231)
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x04, 0x0c, 0x0f,
+            // ETX | Checksum
+            0x03, 0x59,
+        ];
+
+        let mut msg = QueryAuditLifeTimeTotalsReply::new();
+        msg.from_buf(msg_bytes.as_ref())?;
+
+        assert_eq!(msg.message_type(), MessageType::AuxCommand);
+        assert_eq!(msg.data_map(), 1);
+        assert_eq!(msg.operating_hours(), 12345);
+        assert_eq!(msg.motor_starts(), 65536);
+        assert_eq!(msg.documents_reached_escrow(), 1234);
+        assert_eq!(msg.documents_passed_validation(), 1232);
+        assert_eq!(msg.documents_stacked(), 1231);
+
+        Ok(())
+    }
+}
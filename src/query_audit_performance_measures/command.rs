@@ -0,0 +1,83 @@
+use crate::{
+    impl_aux_ops, impl_message_ops, len::QUERY_AUDIT_PERFORMANCE_MEASURES_COMMAND, std::fmt,
+    AuxCommand, AuxCommandOps, MessageOps, MessageType,
+};
+
+/// Query Audit Performance Measures - Command (Subtype 0x0C)
+///
+/// | **S2K** | **CFSC** | **SC Adv** | **SCR** |
+/// |:-------:|:--------:|:----------:|:-------:|
+///
+/// This command is used to query the performance audit measures of the device, e.g. reject
+/// breakdowns, and jam recovery efforts, since the audit data was last cleared (see
+/// [ClearAuditDataRequest](crate::ClearAuditDataRequest)).
+///
+/// The Query Audit Performance Measures Command is formatted as follows:
+///
+/// | Name  | STX  | LEN  | CTRL | Data A | Data B | Command | ETX  | CHK |
+/// |:------|:----:|:----:|:----:|:------:|:------:|:-------:|:----:|:---:|
+/// | Byte  | 0    | 1    | 2    | 3      | 4      | 5       | 6    | 7   |
+/// | Value | 0x02 | 0x08 | 0x6n | 0x00   | 0x00   | 0x0C    | 0x03 | zz  |
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct QueryAuditPerformanceMeasuresCommand {
+    buf: [u8; QUERY_AUDIT_PERFORMANCE_MEASURES_COMMAND],
+}
+
+impl QueryAuditPerformanceMeasuresCommand {
+    /// Creates a new [QueryAuditPerformanceMeasuresCommand].
+    pub fn new() -> Self {
+        let mut message = Self {
+            buf: [0u8; QUERY_AUDIT_PERFORMANCE_MEASURES_COMMAND],
+        };
+
+        message.init();
+        message.set_message_type(MessageType::AuxCommand);
+        message.set_aux_command(AuxCommand::QueryAuditPerformanceMeasures);
+
+        message
+    }
+}
+
+impl_message_ops!(QueryAuditPerformanceMeasuresCommand);
+impl_aux_ops!(QueryAuditPerformanceMeasuresCommand);
+
+impl fmt::Display for QueryAuditPerformanceMeasuresCommand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{{")?;
+        write!(f, r#""acknak": {}, "#, self.acknak())?;
+        write!(f, r#""device_type": {}, "#, self.device_type())?;
+        write!(f, r#""message_type": {}, "#, self.message_type())?;
+        write!(f, r#""aux_command": {}"#, self.aux_command())?;
+        write!(f, "}}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Result;
+
+    #[test]
+    #[rustfmt::skip]
+    fn test_query_audit_performance_measures_command_from_buf() -> Result<()> {
+        let msg_bytes = [
+            // STX | LEN | Message Type
+            0x02, 0x08, 0x60,
+            // Data
+            0x00, 0x00,
+            // Command
+            0x0c,
+            // ETX | Checksum
+            0x03, 0x64,
+        ];
+
+        let mut msg = QueryAuditPerformanceMeasuresCommand::new();
+        msg.from_buf(msg_bytes.as_ref())?;
+
+        assert_eq!(msg.message_type(), MessageType::AuxCommand);
+        assert_eq!(msg.aux_command(), AuxCommand::QueryAuditPerformanceMeasures);
+
+        Ok(())
+    }
+}
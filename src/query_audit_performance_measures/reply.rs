@@ -0,0 +1,265 @@
+use crate::std;
+use std::fmt;
+
+use crate::{
+    impl_default, impl_message_ops, impl_omnibus_nop_reply,
+    len::QUERY_AUDIT_PERFORMANCE_MEASURES_REPLY, seven_bit_u16, MessageOps, MessageType,
+};
+
+/// Length of a performance measures counter field (in bytes).
+pub const PERFORMANCE_MEASURES_FIELD_LEN: usize = 4;
+
+pub mod index {
+    pub const DATA: usize = 3;
+    // Field numbers, each field is `PERFORMANCE_MEASURES_FIELD_LEN` bytes long
+    pub const DATA_MAP: usize = 0;
+    pub const CROSS_CHANNEL_0_REJECTS: usize = 1;
+    pub const CROSS_CHANNEL_1_REJECTS: usize = 2;
+    pub const SORT_REJECTS: usize = 3;
+    pub const VALIDATION_REJECTS: usize = 4;
+    pub const HOST_REJECTS: usize = 5;
+    pub const BARCODE_REJECTS: usize = 6;
+    pub const JAM_RECOVERY_EFFORTS: usize = 7;
+    pub const REJECT_REVERSE_OPERATIONS: usize = 8;
+    pub const OUT_OF_SERVICE_CONDITIONS: usize = 9;
+    pub const OUT_OF_ORDER_CONDITIONS: usize = 10;
+    pub const OPERATING_HOURS: usize = 11;
+    pub const DOCUMENTS_REACHED_ESCROW: usize = 12;
+    pub const DOCUMENTS_PASSED_VALIDATION: usize = 13;
+    pub const DOCUMENTS_STACKED: usize = 14;
+}
+
+/// Query Audit Performance Measures - Reply (Subtype 0x0C)
+///
+/// | **S2K** | **CFSC** | **SC Adv** | **SCR** |
+/// |:-------:|:--------:|:----------:|:-------:|
+///
+/// The performance measures are counted since the audit data was last cleared, see
+/// [ClearAuditDataRequest](crate::ClearAuditDataRequest).
+///
+/// The Query Audit Performance Measures Reply is formatted as follows:
+///
+/// | Name  | STX  | LEN  | CTRL | Field 0 | Field 1 | ... | Field 14 | ETX  | CHK |
+/// |:------|:----:|:----:|:----:|:-------:|:-------:|:---:|:--------:|:----:|:---:|
+/// | Byte  | 0    | 1    | 2    | 3-6     | 7-10    | ... | 59-62    | 63   | 64  |
+/// | Value | 0x02 | 0x41 | 0x6n | nn      | nn      | nn  | nn       | 0x03 | zz  |
+///
+/// Each field is a 16-bit counter, sent in 4 bytes, four bits at a time, most significant nibble
+/// first:
+///
+/// | Field | Counter                     |
+/// |:-----:|:----------------------------|
+/// | 0     | Data map                    |
+/// | 1     | Cross channel 0 rejects     |
+/// | 2     | Cross channel 1 rejects     |
+/// | 3     | Sort rejects                |
+/// | 4     | Validation rejects          |
+/// | 5     | Host rejects                |
+/// | 6     | Barcode rejects             |
+/// | 7     | Jam recovery efforts        |
+/// | 8     | Reject reverse operations   |
+/// | 9     | Out of service conditions   |
+/// | 10    | Out of order conditions     |
+/// | 11    | Operating hours             |
+/// | 12    | Documents reached escrow    |
+/// | 13    | Documents passed validation |
+/// | 14    | Documents stacked           |
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct QueryAuditPerformanceMeasuresReply {
+    buf: [u8; QUERY_AUDIT_PERFORMANCE_MEASURES_REPLY],
+}
+
+impl QueryAuditPerformanceMeasuresReply {
+    /// Creates a new [QueryAuditPerformanceMeasuresReply].
+    pub fn new() -> Self {
+        let mut message = Self {
+            buf: [0u8; QUERY_AUDIT_PERFORMANCE_MEASURES_REPLY],
+        };
+
+        message.init();
+        message.set_message_type(MessageType::AuxCommand);
+
+        message
+    }
+
+    fn field(&self, field: usize) -> u16 {
+        let start = index::DATA + (field * PERFORMANCE_MEASURES_FIELD_LEN);
+        seven_bit_u16(self.buf[start..start + PERFORMANCE_MEASURES_FIELD_LEN].as_ref())
+    }
+
+    /// Gets the data map, identifying the layout of the reported fields.
+    pub fn data_map(&self) -> u16 {
+        self.field(index::DATA_MAP)
+    }
+
+    /// Gets the number of documents rejected by the cross channel 0 sensors.
+    pub fn cross_channel_0_rejects(&self) -> u16 {
+        self.field(index::CROSS_CHANNEL_0_REJECTS)
+    }
+
+    /// Gets the number of documents rejected by the cross channel 1 sensors.
+    pub fn cross_channel_1_rejects(&self) -> u16 {
+        self.field(index::CROSS_CHANNEL_1_REJECTS)
+    }
+
+    /// Gets the number of documents rejected by denomination sorting.
+    pub fn sort_rejects(&self) -> u16 {
+        self.field(index::SORT_REJECTS)
+    }
+
+    /// Gets the number of documents rejected by validation.
+    pub fn validation_rejects(&self) -> u16 {
+        self.field(index::VALIDATION_REJECTS)
+    }
+
+    /// Gets the number of escrowed documents returned by the host.
+    pub fn host_rejects(&self) -> u16 {
+        self.field(index::HOST_REJECTS)
+    }
+
+    /// Gets the number of barcode vouchers rejected.
+    pub fn barcode_rejects(&self) -> u16 {
+        self.field(index::BARCODE_REJECTS)
+    }
+
+    /// Gets the number of jam recovery efforts.
+    pub fn jam_recovery_efforts(&self) -> u16 {
+        self.field(index::JAM_RECOVERY_EFFORTS)
+    }
+
+    /// Gets the number of times the transport reversed to reject a document.
+    pub fn reject_reverse_operations(&self) -> u16 {
+        self.field(index::REJECT_REVERSE_OPERATIONS)
+    }
+
+    /// Gets the number of times the device went out of service.
+    pub fn out_of_service_conditions(&self) -> u16 {
+        self.field(index::OUT_OF_SERVICE_CONDITIONS)
+    }
+
+    /// Gets the number of times the device went out of order (failure).
+    pub fn out_of_order_conditions(&self) -> u16 {
+        self.field(index::OUT_OF_ORDER_CONDITIONS)
+    }
+
+    /// Gets the number of hours the device has been powered.
+    pub fn operating_hours(&self) -> u16 {
+        self.field(index::OPERATING_HOURS)
+    }
+
+    /// Gets the number of documents that reached the escrow position.
+    pub fn documents_reached_escrow(&self) -> u16 {
+        self.field(index::DOCUMENTS_REACHED_ESCROW)
+    }
+
+    /// Gets the number of documents that passed validation.
+    pub fn documents_passed_validation(&self) -> u16 {
+        self.field(index::DOCUMENTS_PASSED_VALIDATION)
+    }
+
+    /// Gets the number of documents stacked.
+    pub fn documents_stacked(&self) -> u16 {
+        self.field(index::DOCUMENTS_STACKED)
+    }
+}
+
+impl_default!(QueryAuditPerformanceMeasuresReply);
+impl_message_ops!(QueryAuditPerformanceMeasuresReply);
+impl_omnibus_nop_reply!(QueryAuditPerformanceMeasuresReply);
+
+impl fmt::Display for QueryAuditPerformanceMeasuresReply {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "AckNak: {}, DeviceType: {}, MessageType: {}, DataMap: {}, CrossChannel0Rejects: {}, CrossChannel1Rejects: {}, SortRejects: {}, ValidationRejects: {}, HostRejects: {}, BarcodeRejects: {}, JamRecoveryEfforts: {}, RejectReverseOperations: {}, OutOfServiceConditions: {}, OutOfOrderConditions: {}, OperatingHours: {}, DocumentsReachedEscrow: {}, DocumentsPassedValidation: {}, DocumentsStacked: {}",
+            self.acknak(),
+            self.device_type(),
+            self.message_type(),
+            self.data_map(),
+            self.cross_channel_0_rejects(),
+            self.cross_channel_1_rejects(),
+            self.sort_rejects(),
+            self.validation_rejects(),
+            self.host_rejects(),
+            self.barcode_rejects(),
+            self.jam_recovery_efforts(),
+            self.reject_reverse_operations(),
+            self.out_of_service_conditions(),
+            self.out_of_order_conditions(),
+            self.operating_hours(),
+            self.documents_reached_escrow(),
+            self.documents_passed_validation(),
+            self.documents_stacked(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Result;
+
+    #[test]
+    #[rustfmt::skip]
+    fn test_query_audit_performance_measures_reply_from_buf() -> Result<()> {
+        let msg_bytes = [
+            // STX | LEN | Message Type
+            0x02, 0x41, 0x60,
+            // Data map
+            0x00, 0x00, 0x00, 0x01,
+            // Cross channel 0 rejects
+            0x00, 0x00, 0x00, 0x01,
+            // Cross channel 1 rejects
+            0x00, 0x00, 0x00, 0x02,
+            // Sort rejects
+            0x00, 0x00, 0x00, 0x03,
+            // Validation rejects
+            0x00, 0x00, 0x00, 0x04,
+            // Host rejects
+            0x00, 0x00, 0x00, 0x05,
+            // Barcode rejects
+            0x00, 0x00, 0x00, 0x06,
+            // Jam recovery efforts
+            0x00, 0x00, 0x00, 0x07,
+            // Reject reverse operations
+            0x00, 0x00, 0x00, 0x08,
+            // Out of service conditions
+            0x00, 0x00, 0x00, 0x09,
+            // Out of order conditions
+            0x00, 0x00, 0x00, 0x0a,
+            // Operating hours (0x1a4 = 420)
+            0x00, 0x01, 0x0a, 0x04,
+            // Documents reached escrow (0x4d2 = 1234)
+            0x00, 0x04, 0x0d, 0x02,
+            // Documents passed validation (0x4d0 = 1232)
+            0x00, 0x04, 0x0d, 0x00,
+            // Documents stacked (0x4cf = 1231)
+            0x00, 0x04, 0x0c, 0x0f,
+            // ETX | Checksum
+            0x03, 0x21,
+        ];
+
+        let mut msg = QueryAuditPerformanceMeasuresReply::new();
+        msg.from_buf(msg_bytes.as_ref())?;
+
+        assert_eq!(msg.message_type(), MessageType::AuxCommand);
+        assert_eq!(msg.data_map(), 1);
+        assert_eq!(msg.cross_channel_0_rejects(), 1);
+        assert_eq!(msg.cross_channel_1_rejects(), 2);
+        assert_eq!(msg.sort_rejects(), 3);
+        assert_eq!(msg.validation_rejects(), 4);
+        assert_eq!(msg.host_rejects(), 5);
+        assert_eq!(msg.barcode_rejects(), 6);
+        assert_eq!(msg.jam_recovery_efforts(), 7);
+        assert_eq!(msg.reject_reverse_operations(), 8);
+        assert_eq!(msg.out_of_service_conditions(), 9);
+        assert_eq!(msg.out_of_order_conditions(), 10);
+        assert_eq!(msg.operating_hours(), 420);
+        assert_eq!(msg.documents_reached_escrow(), 1234);
+        assert_eq!(msg.documents_passed_validation(), 1232);
+        assert_eq!(msg.documents_stacked(), 1231);
+
+        Ok(())
+    }
+}
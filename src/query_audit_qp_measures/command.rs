@@ -0,0 +1,82 @@
+use crate::{
+    impl_aux_ops, impl_message_ops, len::QUERY_AUDIT_QP_MEASURES_COMMAND, std::fmt, AuxCommand,
+    AuxCommandOps, MessageOps, MessageType,
+};
+
+/// Query Audit QP Measures - Command (Subtype 0x0B)
+///
+/// | **S2K** | **CFSC** | **SC Adv** | **SCR** |
+/// |:-------:|:--------:|:----------:|:-------:|
+///
+/// This command is used to query the quick-performance (QP) audit measures of the device, a
+/// breakdown of the most recently processed documents.
+///
+/// The Query Audit QP Measures Command is formatted as follows:
+///
+/// | Name  | STX  | LEN  | CTRL | Data A | Data B | Command | ETX  | CHK |
+/// |:------|:----:|:----:|:----:|:------:|:------:|:-------:|:----:|:---:|
+/// | Byte  | 0    | 1    | 2    | 3      | 4      | 5       | 6    | 7   |
+/// | Value | 0x02 | 0x08 | 0x6n | 0x00   | 0x00   | 0x0B    | 0x03 | zz  |
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct QueryAuditQpMeasuresCommand {
+    buf: [u8; QUERY_AUDIT_QP_MEASURES_COMMAND],
+}
+
+impl QueryAuditQpMeasuresCommand {
+    /// Creates a new [QueryAuditQpMeasuresCommand].
+    pub fn new() -> Self {
+        let mut message = Self {
+            buf: [0u8; QUERY_AUDIT_QP_MEASURES_COMMAND],
+        };
+
+        message.init();
+        message.set_message_type(MessageType::AuxCommand);
+        message.set_aux_command(AuxCommand::QueryAuditQpMeasures);
+
+        message
+    }
+}
+
+impl_message_ops!(QueryAuditQpMeasuresCommand);
+impl_aux_ops!(QueryAuditQpMeasuresCommand);
+
+impl fmt::Display for QueryAuditQpMeasuresCommand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{{")?;
+        write!(f, r#""acknak": {}, "#, self.acknak())?;
+        write!(f, r#""device_type": {}, "#, self.device_type())?;
+        write!(f, r#""message_type": {}, "#, self.message_type())?;
+        write!(f, r#""aux_command": {}"#, self.aux_command())?;
+        write!(f, "}}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Result;
+
+    #[test]
+    #[rustfmt::skip]
+    fn test_query_audit_qp_measures_command_from_buf() -> Result<()> {
+        let msg_bytes = [
+            // STX | LEN | Message Type
+            0x02, 0x08, 0x60,
+            // Data
+            0x00, 0x00,
+            // Command
+            0x0b,
+            // ETX | Checksum
+            0x03, 0x63,
+        ];
+
+        let mut msg = QueryAuditQpMeasuresCommand::new();
+        msg.from_buf(msg_bytes.as_ref())?;
+
+        assert_eq!(msg.message_type(), MessageType::AuxCommand);
+        assert_eq!(msg.aux_command(), AuxCommand::QueryAuditQpMeasures);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,188 @@
+use crate::std;
+use std::fmt;
+
+use crate::{
+    impl_default, impl_message_ops, impl_omnibus_nop_reply, len::QUERY_AUDIT_QP_MEASURES_REPLY,
+    seven_bit_u16, MessageOps, MessageType,
+};
+
+/// Length of a QP measures counter field (in bytes).
+pub const QP_MEASURES_FIELD_LEN: usize = 4;
+
+pub mod index {
+    pub const DATA: usize = 3;
+    // Field numbers, each field is `QP_MEASURES_FIELD_LEN` bytes long
+    pub const DATA_MAP: usize = 0;
+    pub const DOCUMENTS_PROCESSED: usize = 1;
+    pub const DOCUMENTS_ACCEPTED: usize = 2;
+    pub const CROSS_CHANNEL_REJECTS: usize = 3;
+    pub const SORT_REJECTS: usize = 4;
+    pub const VALIDATION_REJECTS: usize = 5;
+    pub const HOST_REJECTS: usize = 6;
+    pub const JAM_RECOVERY_EFFORTS: usize = 7;
+}
+
+/// Query Audit QP Measures - Reply (Subtype 0x0B)
+///
+/// | **S2K** | **CFSC** | **SC Adv** | **SCR** |
+/// |:-------:|:--------:|:----------:|:-------:|
+///
+/// The quick-performance (QP) measures are a breakdown of the most recently processed documents,
+/// for a quick check of the device health without clearing the audit data.
+///
+/// The Query Audit QP Measures Reply is formatted as follows:
+///
+/// | Name  | STX  | LEN  | CTRL | Field 0 | Field 1 | ... | Field 7 | ETX  | CHK |
+/// |:------|:----:|:----:|:----:|:-------:|:-------:|:---:|:-------:|:----:|:---:|
+/// | Byte  | 0    | 1    | 2    | 3-6     | 7-10    | ... | 31-34   | 35   | 36  |
+/// | Value | 0x02 | 0x25 | 0x6n | nn      | nn      | nn  | nn      | 0x03 | zz  |
+///
+/// Each field is a 16-bit counter, sent in 4 bytes, four bits at a time, most significant nibble
+/// first:
+///
+/// | Field | Counter               |
+/// |:-----:|:----------------------|
+/// | 0     | Data map              |
+/// | 1     | Documents processed   |
+/// | 2     | Documents accepted    |
+/// | 3     | Cross channel rejects |
+/// | 4     | Sort rejects          |
+/// | 5     | Validation rejects    |
+/// | 6     | Host rejects          |
+/// | 7     | Jam recovery efforts  |
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct QueryAuditQpMeasuresReply {
+    buf: [u8; QUERY_AUDIT_QP_MEASURES_REPLY],
+}
+
+impl QueryAuditQpMeasuresReply {
+    /// Creates a new [QueryAuditQpMeasuresReply].
+    pub fn new() -> Self {
+        let mut message = Self {
+            buf: [0u8; QUERY_AUDIT_QP_MEASURES_REPLY],
+        };
+
+        message.init();
+        message.set_message_type(MessageType::AuxCommand);
+
+        message
+    }
+
+    fn field(&self, field: usize) -> u16 {
+        let start = index::DATA + (field * QP_MEASURES_FIELD_LEN);
+        seven_bit_u16(self.buf[start..start + QP_MEASURES_FIELD_LEN].as_ref())
+    }
+
+    /// Gets the data map, identifying the layout of the reported fields.
+    pub fn data_map(&self) -> u16 {
+        self.field(index::DATA_MAP)
+    }
+
+    /// Gets the number of documents processed in the measurement window.
+    pub fn documents_processed(&self) -> u16 {
+        self.field(index::DOCUMENTS_PROCESSED)
+    }
+
+    /// Gets the number of processed documents that were accepted.
+    pub fn documents_accepted(&self) -> u16 {
+        self.field(index::DOCUMENTS_ACCEPTED)
+    }
+
+    /// Gets the number of documents rejected by the cross channel sensors.
+    pub fn cross_channel_rejects(&self) -> u16 {
+        self.field(index::CROSS_CHANNEL_REJECTS)
+    }
+
+    /// Gets the number of documents rejected by denomination sorting.
+    pub fn sort_rejects(&self) -> u16 {
+        self.field(index::SORT_REJECTS)
+    }
+
+    /// Gets the number of documents rejected by validation.
+    pub fn validation_rejects(&self) -> u16 {
+        self.field(index::VALIDATION_REJECTS)
+    }
+
+    /// Gets the number of escrowed documents returned by the host.
+    pub fn host_rejects(&self) -> u16 {
+        self.field(index::HOST_REJECTS)
+    }
+
+    /// Gets the number of jam recovery efforts.
+    pub fn jam_recovery_efforts(&self) -> u16 {
+        self.field(index::JAM_RECOVERY_EFFORTS)
+    }
+}
+
+impl_default!(QueryAuditQpMeasuresReply);
+impl_message_ops!(QueryAuditQpMeasuresReply);
+impl_omnibus_nop_reply!(QueryAuditQpMeasuresReply);
+
+impl fmt::Display for QueryAuditQpMeasuresReply {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "AckNak: {}, DeviceType: {}, MessageType: {}, DataMap: {}, DocumentsProcessed: {}, DocumentsAccepted: {}, CrossChannelRejects: {}, SortRejects: {}, ValidationRejects: {}, HostRejects: {}, JamRecoveryEfforts: {}",
+            self.acknak(),
+            self.device_type(),
+            self.message_type(),
+            self.data_map(),
+            self.documents_processed(),
+            self.documents_accepted(),
+            self.cross_channel_rejects(),
+            self.sort_rejects(),
+            self.validation_rejects(),
+            self.host_rejects(),
+            self.jam_recovery_efforts(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Result;
+
+    #[test]
+    #[rustfmt::skip]
+    fn test_query_audit_qp_measures_reply_from_buf() -> Result<()> {
+        let msg_bytes = [
+            // STX | LEN | Message Type
+            0x02, 0x25, 0x60,
+            // Data map
+            0x00, 0x00, 0x00, 0x01,
+            // Documents processed (0x64 = 100)
+            0x00, 0x00, 0x06, 0x04,
+            // Documents accepted (0x5f = 95)
+            0x00, 0x00, 0x05, 0x0f,
+            // Cross channel rejects
+            0x00, 0x00, 0x00, 0x01,
+            // Sort rejects
+            0x00, 0x00, 0x00, 0x02,
+            // Validation rejects
+            0x00, 0x00, 0x00, 0x01,
+            // Host rejects
+            0x00, 0x00, 0x00, 0x01,
+            // Jam recovery efforts
+            0x00, 0x00, 0x00, 0x00,
+            // ETX | Checksum
+            0x03, 0x4f,
+        ];
+
+        let mut msg = QueryAuditQpMeasuresReply::new();
+        msg.from_buf(msg_bytes.as_ref())?;
+
+        assert_eq!(msg.message_type(), MessageType::AuxCommand);
+        assert_eq!(msg.data_map(), 1);
+        assert_eq!(msg.documents_processed(), 100);
+        assert_eq!(msg.documents_accepted(), 95);
+        assert_eq!(msg.cross_channel_rejects(), 1);
+        assert_eq!(msg.sort_rejects(), 2);
+        assert_eq!(msg.validation_rejects(), 1);
+        assert_eq!(msg.host_rejects(), 1);
+        assert_eq!(msg.jam_recovery_efforts(), 0);
+
+        Ok(())
+    }
+}
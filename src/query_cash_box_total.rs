@@ -0,0 +1,5 @@
+pub(crate) mod command;
+pub(crate) mod reply;
+
+pub use command::*;
+pub use reply::*;
@@ -0,0 +1,106 @@
+use crate::std;
+use std::fmt;
+
+use crate::{
+    impl_message_ops, impl_omnibus_nop_reply, len::QUERY_CASH_BOX_TOTAL_REPLY, seven_bit_u24,
+    u24_seven_bit, MessageOps, MessageType,
+};
+
+pub mod index {
+    pub const TOTAL: usize = 3;
+    pub const TOTAL_END: usize = 9;
+}
+
+/// Query Cash Box Total - Reply (Subtype 0x01)
+///
+/// | **S2K** | **CFSC** | **SC Adv** | **SCR** |
+/// |:-------:|:--------:|:----------:|:-------:|
+///
+/// The Query Cash Box Total Reply is formatted as follows:
+///
+/// | Name  | STX  | LEN  | CTRL | Data 0 | Data 1 | Data 2 | Data 3 | Data 4 | Data 5 | ETX  | CHK |
+/// |:------|:----:|:----:|:----:|:------:|:------:|:------:|:------:|:------:|:------:|:----:|:---:|
+/// | Byte  | 0    | 1    | 2    | 3      | 4      | 5      | 6      | 7      | 8      | 9    | 10  |
+/// | Value | 0x02 | 0x0B | 0x6n | 0x0n   | 0x0n   | 0x0n   | 0x0n   | 0x0n   | 0x0n   | 0x03 | zz  |
+///
+/// The 24-bit note count is sent in bytes 3 through 8, four bits at a time, most significant
+/// nibble first.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct QueryCashBoxTotalReply {
+    buf: [u8; QUERY_CASH_BOX_TOTAL_REPLY],
+}
+
+impl QueryCashBoxTotalReply {
+    /// Creates a new [QueryCashBoxTotalReply].
+    pub fn new() -> Self {
+        let mut message = Self {
+            buf: [0u8; QUERY_CASH_BOX_TOTAL_REPLY],
+        };
+
+        message.init();
+        message.set_message_type(MessageType::AuxCommand);
+
+        message
+    }
+
+    /// Gets the number of notes stacked in the cash box since the total was last cleared.
+    pub fn cash_box_total(&self) -> u32 {
+        seven_bit_u24(self.buf[index::TOTAL..index::TOTAL_END].as_ref())
+    }
+
+    /// Sets the number of notes stacked in the cash box.
+    ///
+    /// The count is truncated to 24 bits.
+    pub fn set_cash_box_total(&mut self, total: u32) {
+        self.buf[index::TOTAL..index::TOTAL_END].copy_from_slice(u24_seven_bit(total).as_ref());
+    }
+}
+
+impl_message_ops!(QueryCashBoxTotalReply);
+impl_omnibus_nop_reply!(QueryCashBoxTotalReply);
+
+impl fmt::Display for QueryCashBoxTotalReply {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "AckNak: {}, DeviceType: {}, MessageType: {}, CashBoxTotal: {}",
+            self.acknak(),
+            self.device_type(),
+            self.message_type(),
+            self.cash_box_total(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Result;
+
+    #[test]
+    #[rustfmt::skip]
+    fn test_query_cash_box_total_reply_from_buf() -> Result<()> {
+        let msg_bytes = [
+            // STX | LEN | Message Type
+            0x02, 0x0b, 0x60,
+            // Cash box total (0x00_04d2 = 1234)
+            0x00, 0x00, 0x00, 0x04, 0x0d, 0x02,
+            // ETX | Checksum
+            0x03, 0x60,
+        ];
+
+        let mut msg = QueryCashBoxTotalReply::new();
+        msg.from_buf(msg_bytes.as_ref())?;
+
+        assert_eq!(msg.message_type(), MessageType::AuxCommand);
+        assert_eq!(msg.cash_box_total(), 1234);
+
+        let mut exp_msg = QueryCashBoxTotalReply::new();
+        exp_msg.set_cash_box_total(1234);
+
+        assert_eq!(exp_msg.as_bytes(), msg_bytes.as_ref());
+
+        Ok(())
+    }
+}
@@ -1,6 +1,6 @@
 use crate::{
-    impl_aux_ops, impl_message_ops, len::QUERY_SOFTWARE_CRC_COMMAND, AuxCommand, AuxCommandOps,
-    MessageOps, MessageType,
+    impl_aux_ops, impl_message_ops, len::QUERY_SOFTWARE_CRC_COMMAND, std::fmt, AuxCommand,
+    AuxCommandOps, MessageOps, MessageType,
 };
 
 /// Query Software CRC - Command (Subtype 0x00)
@@ -38,3 +38,14 @@ impl QuerySoftwareCrcCommand {
 
 impl_message_ops!(QuerySoftwareCrcCommand);
 impl_aux_ops!(QuerySoftwareCrcCommand);
+
+impl fmt::Display for QuerySoftwareCrcCommand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{{")?;
+        write!(f, r#""acknak": {}, "#, self.acknak())?;
+        write!(f, r#""device_type": {}, "#, self.device_type())?;
+        write!(f, r#""message_type": {}, "#, self.message_type())?;
+        write!(f, r#""aux_command": {}"#, self.aux_command())?;
+        write!(f, "}}")
+    }
+}
@@ -0,0 +1,198 @@
+use crate::std;
+use std::fmt;
+
+use crate::{AckNak, MessageOps};
+
+/// Default number of times a command is re-sent before giving up.
+pub const DEFAULT_MAX_RETRIES: u8 = 3;
+/// Default time to wait for a complete reply from the device (milliseconds).
+pub const DEFAULT_REPLY_TIMEOUT_MS: u64 = 200;
+
+/// Result of checking a device reply against the expected ACK/NAK toggle.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ReplyCheck {
+    /// The reply echoes the toggle of the last sent command.
+    Accepted,
+    /// The reply repeats the toggle of the previously accepted reply.
+    ///
+    /// The reply is a stale copy of the previous reply (e.g. a late reply to a re-sent command).
+    /// Discard it, and keep waiting for the reply to the last sent command.
+    Duplicate,
+    /// The reply does not echo the toggle of the last sent command.
+    Mismatch,
+}
+
+impl ReplyCheck {
+    /// Gets whether the reply was accepted.
+    pub fn is_accepted(&self) -> bool {
+        *self == Self::Accepted
+    }
+}
+
+impl From<ReplyCheck> for &'static str {
+    fn from(r: ReplyCheck) -> Self {
+        match r {
+            ReplyCheck::Accepted => "Accepted",
+            ReplyCheck::Duplicate => "Duplicate",
+            ReplyCheck::Mismatch => "Mismatch",
+        }
+    }
+}
+
+impl From<&ReplyCheck> for &'static str {
+    fn from(r: &ReplyCheck) -> Self {
+        (*r).into()
+    }
+}
+
+impl fmt::Display for ReplyCheck {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", <&'static str>::from(self))
+    }
+}
+
+/// Tracks the alternating ACK/NAK bit across a host-device session.
+///
+/// Each new command is stamped with the current toggle, and the device echoes the toggle in its reply.
+/// The toggle only switches after a reply is accepted, so re-sending a command (after a timeout,
+/// checksum failure, etc.) re-uses the same toggle value.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AckNakSequencer {
+    acknak: AckNak,
+    last_accepted: Option<AckNak>,
+    max_retries: u8,
+}
+
+impl AckNakSequencer {
+    /// Creates a new [AckNakSequencer].
+    pub const fn new() -> Self {
+        Self {
+            acknak: AckNak::Ack,
+            last_accepted: None,
+            max_retries: DEFAULT_MAX_RETRIES,
+        }
+    }
+
+    /// Gets the ACK/NAK toggle for the next outgoing command.
+    pub fn acknak(&self) -> AckNak {
+        self.acknak
+    }
+
+    /// Gets the ACK/NAK toggle of the last accepted reply, if any.
+    pub fn last_accepted(&self) -> Option<AckNak> {
+        self.last_accepted
+    }
+
+    /// Gets the maximum number of times a command is re-sent.
+    pub fn max_retries(&self) -> u8 {
+        self.max_retries
+    }
+
+    /// Sets the maximum number of times a command is re-sent.
+    pub fn set_max_retries(&mut self, max_retries: u8) {
+        self.max_retries = max_retries;
+    }
+
+    /// Builder function to set the maximum number of times a command is re-sent.
+    pub fn with_max_retries(mut self, max_retries: u8) -> Self {
+        self.set_max_retries(max_retries);
+        self
+    }
+
+    /// Stamps the outgoing message with the current ACK/NAK toggle.
+    pub fn stamp(&self, message: &mut dyn MessageOps) {
+        message.set_acknak(self.acknak);
+    }
+
+    /// Classifies the reply against the current ACK/NAK toggle, without switching the toggle.
+    ///
+    /// Useful to drop a duplicate reply before parsing it, see [check_reply](Self::check_reply).
+    pub fn classify_reply(&self, reply: &dyn MessageOps) -> ReplyCheck {
+        let reply_acknak = reply.acknak();
+
+        if reply_acknak == self.acknak {
+            ReplyCheck::Accepted
+        } else if self.last_accepted == Some(reply_acknak) {
+            ReplyCheck::Duplicate
+        } else {
+            ReplyCheck::Mismatch
+        }
+    }
+
+    /// Checks the reply echoes the current ACK/NAK toggle.
+    ///
+    /// On acceptance, the toggle is switched for the next outgoing command.
+    pub fn check_reply(&mut self, reply: &dyn MessageOps) -> ReplyCheck {
+        let reply_acknak = reply.acknak();
+        let check = self.classify_reply(reply);
+
+        match check {
+            ReplyCheck::Accepted => {
+                self.last_accepted = Some(reply_acknak);
+                self.acknak = !self.acknak;
+            }
+            ReplyCheck::Duplicate => {
+                log::debug!("Suppressing duplicate reply, ACK/NAK: {reply_acknak}")
+            }
+            ReplyCheck::Mismatch => log::debug!(
+                "ACK/NAK mismatch, expected: {}, have: {reply_acknak}",
+                self.acknak
+            ),
+        }
+
+        check
+    }
+
+    /// Resets the session state, e.g. after the device is reset.
+    pub fn reset(&mut self) {
+        self.acknak = AckNak::Ack;
+        self.last_accepted = None;
+    }
+}
+
+impl Default for AckNakSequencer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{OmnibusCommand, OmnibusReply};
+
+    #[test]
+    fn test_acknak_sequence() {
+        let mut sequencer = AckNakSequencer::new();
+        let mut command = OmnibusCommand::new();
+        let mut reply = OmnibusReply::new();
+
+        sequencer.stamp(&mut command);
+        assert_eq!(command.acknak(), AckNak::Ack);
+
+        // device echoes the wrong toggle before any reply was accepted
+        reply.set_acknak(AckNak::Nak);
+        assert_eq!(sequencer.check_reply(&reply), ReplyCheck::Mismatch);
+        assert_eq!(sequencer.acknak(), AckNak::Ack);
+
+        reply.set_acknak(AckNak::Ack);
+        assert_eq!(sequencer.check_reply(&reply), ReplyCheck::Accepted);
+        assert_eq!(sequencer.acknak(), AckNak::Nak);
+
+        sequencer.stamp(&mut command);
+        assert_eq!(command.acknak(), AckNak::Nak);
+
+        // same toggle seen twice
+        assert_eq!(sequencer.check_reply(&reply), ReplyCheck::Duplicate);
+        assert_eq!(sequencer.acknak(), AckNak::Nak);
+
+        reply.set_acknak(AckNak::Nak);
+        assert!(sequencer.check_reply(&reply).is_accepted());
+        assert_eq!(sequencer.acknak(), AckNak::Ack);
+
+        sequencer.reset();
+        assert_eq!(sequencer.acknak(), AckNak::Ack);
+        assert_eq!(sequencer.last_accepted(), None);
+    }
+}
@@ -1,6 +1,7 @@
 use crate::{
     impl_extended_ops, impl_message_ops, impl_omnibus_extended_command,
-    len::SET_ESCROW_TIMEOUT_COMMAND, ExtendedCommand, ExtendedCommandOps, MessageOps, MessageType,
+    len::SET_ESCROW_TIMEOUT_COMMAND, std::fmt, ExtendedCommand, ExtendedCommandOps, MessageOps,
+    MessageType,
 };
 
 pub mod index {
@@ -78,6 +79,19 @@ impl_message_ops!(SetEscrowTimeoutCommand);
 impl_extended_ops!(SetEscrowTimeoutCommand);
 impl_omnibus_extended_command!(SetEscrowTimeoutCommand);
 
+impl fmt::Display for SetEscrowTimeoutCommand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{{")?;
+        write!(f, r#""acknak": {}, "#, self.acknak())?;
+        write!(f, r#""device_type": {}, "#, self.device_type())?;
+        write!(f, r#""message_type": {}, "#, self.message_type())?;
+        write!(f, r#""extended_command": {}, "#, self.extended_command())?;
+        write!(f, r#""notes_timeout": {}, "#, self.notes_timeout())?;
+        write!(f, r#""barcodes_timeout": {}"#, self.barcodes_timeout())?;
+        write!(f, "}}")
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -2,8 +2,10 @@ use crate::std::fmt;
 
 use crate::{bool_enum, StandardDenomination};
 
+mod device_state_model;
 mod document_status;
 
+pub use device_state_model::*;
 pub use document_status::*;
 
 bitfield! {
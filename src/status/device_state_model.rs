@@ -0,0 +1,470 @@
+use crate::std::fmt;
+
+use crate::{DeviceStateFlags, OmnibusCommandOps, OmnibusReplyOps, StandardDenomination};
+
+/// Host-side context needed to interpret an Omnibus reply.
+///
+/// Some [DeviceStateFlags] cannot be determined from the reply bitfields alone, e.g. an idling
+/// device is [HostDisabled](DeviceStateFlags::HostDisabled) when the host inhibits all notes.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct HostContext {
+    host_disabled: bool,
+    escrow_session: bool,
+}
+
+impl HostContext {
+    /// Creates a new [HostContext].
+    pub const fn new() -> Self {
+        Self {
+            host_disabled: false,
+            escrow_session: false,
+        }
+    }
+
+    /// Creates a [HostContext] from the last [OmnibusCommand](crate::OmnibusCommand) sent to the device.
+    ///
+    /// The host is considered to have disabled the device if no denominations are enabled.
+    pub fn from_command(command: &dyn OmnibusCommandOps) -> Self {
+        Self::new().with_host_disabled(command.denomination() == StandardDenomination::none())
+    }
+
+    /// Gets whether the host disabled all notes.
+    pub fn host_disabled(&self) -> bool {
+        self.host_disabled
+    }
+
+    /// Sets whether the host disabled all notes.
+    pub fn set_host_disabled(&mut self, host_disabled: bool) {
+        self.host_disabled = host_disabled;
+    }
+
+    /// Builder function to set whether the host disabled all notes.
+    pub fn with_host_disabled(mut self, host_disabled: bool) -> Self {
+        self.set_host_disabled(host_disabled);
+        self
+    }
+
+    /// Gets whether a multi-note escrow session is in progress.
+    pub fn escrow_session(&self) -> bool {
+        self.escrow_session
+    }
+
+    /// Sets whether a multi-note escrow session is in progress.
+    pub fn set_escrow_session(&mut self, escrow_session: bool) {
+        self.escrow_session = escrow_session;
+    }
+
+    /// Builder function to set whether a multi-note escrow session is in progress.
+    pub fn with_escrow_session(mut self, escrow_session: bool) -> Self {
+        self.set_escrow_session(escrow_session);
+        self
+    }
+}
+
+impl DeviceStateFlags {
+    /// Derives the [DeviceStateFlags] from an Omnibus reply, and the [HostContext].
+    ///
+    /// When multiple conditions are reported, the most severe condition wins, in order:
+    ///
+    /// - download, power up, failure
+    /// - jammed (disabled and jammed, if the host disabled all notes), cash box removed,
+    ///   transport opened, stacker full, cheated, calibration, stalled, paused
+    /// - escrowed, stacking, returning, accepting
+    /// - disabled, host disabled, idle
+    ///
+    /// States that are not reported in the Omnibus reply bitfields (e.g.
+    /// [Dispensing](Self::Dispensing), [FloatingDown](Self::FloatingDown)) are never returned.
+    pub fn from_reply(reply: &dyn OmnibusReplyOps, context: &HostContext) -> Self {
+        let state = reply.device_state();
+        let status = reply.device_status();
+        let exception = reply.exception_status();
+        let misc = reply.misc_device_state();
+
+        let host_disabled = context.host_disabled();
+        let escrow_session = context.escrow_session();
+
+        if misc.flash_download() {
+            Self::Download
+        } else if exception.power_up() {
+            Self::PowerUp
+        } else if exception.failure() {
+            Self::Failure
+        } else if status.jammed() && (host_disabled || misc.disabled()) {
+            Self::DisabledAndJammed
+        } else if status.jammed() {
+            Self::Jammed
+        } else if !status.cassette_attached() {
+            Self::CashBoxRemoved
+        } else if exception.transport_open() {
+            Self::TransportOpened
+        } else if status.stacker_full() {
+            Self::StackerFull
+        } else if status.cheated() {
+            Self::Cheated
+        } else if status.calibration() {
+            Self::Calibration
+        } else if misc.stalled() {
+            Self::Stalled
+        } else if status.paused() {
+            Self::Paused
+        } else if state.escrowed_state() {
+            Self::Escrowed
+        } else if state.stacking() {
+            Self::Stacking
+        } else if state.returning() {
+            Self::Returning
+        } else if state.accepting() {
+            Self::Accepting
+        } else if misc.disabled() || state.out_of_service() {
+            Self::Disabled
+        } else if host_disabled && escrow_session {
+            Self::HostDisabledInEscrowSession
+        } else if host_disabled {
+            Self::HostDisabled
+        } else if escrow_session {
+            Self::IdleInEscrowSession
+        } else {
+            Self::Idle
+        }
+    }
+
+    /// Gets whether the state requires intervention, and the device will not accept documents.
+    pub fn is_out_of_service(&self) -> bool {
+        matches!(
+            self,
+            Self::Failure
+                | Self::Jammed
+                | Self::DisabledAndJammed
+                | Self::CashBoxRemoved
+                | Self::TransportOpened
+                | Self::StackerFull
+                | Self::Cheated
+                | Self::Stalled
+        )
+    }
+
+    /// Gets whether the device is processing a document.
+    pub fn is_processing_document(&self) -> bool {
+        matches!(
+            self,
+            Self::Accepting | Self::Escrowed | Self::Stacking | Self::Returning
+        )
+    }
+}
+
+/// Classification of a transition between two [DeviceStateFlags].
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum TransitionCheck {
+    /// The transition is part of normal device operation.
+    #[default]
+    Expected,
+    /// The transition is possible, but indicates a problem worth reporting.
+    Suspicious,
+    /// The transition should never be reported by a correctly functioning device.
+    Impossible,
+}
+
+impl From<TransitionCheck> for &'static str {
+    fn from(t: TransitionCheck) -> Self {
+        match t {
+            TransitionCheck::Expected => "Expected",
+            TransitionCheck::Suspicious => "Suspicious",
+            TransitionCheck::Impossible => "Impossible",
+        }
+    }
+}
+
+impl From<&TransitionCheck> for &'static str {
+    fn from(t: &TransitionCheck) -> Self {
+        (*t).into()
+    }
+}
+
+impl fmt::Display for TransitionCheck {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", <&'static str>::from(self))
+    }
+}
+
+/// A transition between two [DeviceStateFlags].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DeviceStateTransition {
+    from: DeviceStateFlags,
+    to: DeviceStateFlags,
+    check: TransitionCheck,
+}
+
+impl DeviceStateTransition {
+    /// Creates a new [DeviceStateTransition], classifying the transition.
+    pub fn new(from: DeviceStateFlags, to: DeviceStateFlags) -> Self {
+        Self {
+            from,
+            to,
+            check: check_transition(from, to),
+        }
+    }
+
+    /// Gets the previous [DeviceStateFlags].
+    pub fn from(&self) -> DeviceStateFlags {
+        self.from
+    }
+
+    /// Gets the new [DeviceStateFlags].
+    pub fn to(&self) -> DeviceStateFlags {
+        self.to
+    }
+
+    /// Gets the [TransitionCheck] classification.
+    pub fn check(&self) -> TransitionCheck {
+        self.check
+    }
+
+    /// Gets whether the transition is part of normal device operation.
+    pub fn is_expected(&self) -> bool {
+        self.check == TransitionCheck::Expected
+    }
+}
+
+impl fmt::Display for DeviceStateTransition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} -> {} ({})", self.from, self.to, self.check)
+    }
+}
+
+fn check_transition(from: DeviceStateFlags, to: DeviceStateFlags) -> TransitionCheck {
+    use DeviceStateFlags::*;
+
+    if from == to {
+        return TransitionCheck::Expected;
+    }
+
+    match (from, to) {
+        // a document cannot leave the stacker, or reverse out of a return
+        (Stacking, Escrowed) | (Returning, Stacking) | (Escrowed, Accepting) => {
+            TransitionCheck::Impossible
+        }
+        // stack failed, and the document was returned
+        (Stacking, Returning) => TransitionCheck::Suspicious,
+        // the device accepted a document while the host disabled all notes
+        (HostDisabled, Accepting | Escrowed | Stacking) => TransitionCheck::Suspicious,
+        // unexpected reset
+        (Disconnected, PowerUp) => TransitionCheck::Expected,
+        (_, PowerUp) => TransitionCheck::Suspicious,
+        // download started in the middle of processing a document
+        (from, Download) if from.is_processing_document() => TransitionCheck::Suspicious,
+        // document processing resumed without recovering from an out-of-service condition
+        (from, Escrowed | Stacking) if from.is_out_of_service() => TransitionCheck::Suspicious,
+        _ => TransitionCheck::Expected,
+    }
+}
+
+/// Tracks the [DeviceStateFlags] of the device across successive replies.
+///
+/// Each state change is classified with a [TransitionCheck], and non-expected transitions are
+/// logged as warnings, so operators can see when the device state machine misbehaves.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DeviceStateModel {
+    state: DeviceStateFlags,
+    context: HostContext,
+    suspicious: u32,
+}
+
+impl DeviceStateModel {
+    /// Creates a new [DeviceStateModel].
+    ///
+    /// The device is [Disconnected](DeviceStateFlags::Disconnected) until the first reply.
+    pub const fn new() -> Self {
+        Self {
+            state: DeviceStateFlags::Disconnected,
+            context: HostContext::new(),
+            suspicious: 0,
+        }
+    }
+
+    /// Gets the current [DeviceStateFlags].
+    pub fn state(&self) -> DeviceStateFlags {
+        self.state
+    }
+
+    /// Gets the [HostContext].
+    pub fn context(&self) -> &HostContext {
+        &self.context
+    }
+
+    /// Sets the [HostContext].
+    pub fn set_context(&mut self, context: HostContext) {
+        self.context = context;
+    }
+
+    /// Builder function to set the [HostContext].
+    pub fn with_context(mut self, context: HostContext) -> Self {
+        self.set_context(context);
+        self
+    }
+
+    /// Gets the number of suspicious, or impossible transitions seen.
+    pub fn suspicious(&self) -> u32 {
+        self.suspicious
+    }
+
+    /// Updates the model from an Omnibus reply.
+    ///
+    /// Returns the [DeviceStateTransition] if the state changed.
+    pub fn handle_reply(&mut self, reply: &dyn OmnibusReplyOps) -> Option<DeviceStateTransition> {
+        self.transition(DeviceStateFlags::from_reply(reply, &self.context))
+    }
+
+    /// Updates the model to a new [DeviceStateFlags].
+    ///
+    /// Returns the [DeviceStateTransition] if the state changed.
+    pub fn transition(&mut self, state: DeviceStateFlags) -> Option<DeviceStateTransition> {
+        if state == self.state {
+            return None;
+        }
+
+        let transition = DeviceStateTransition::new(self.state, state);
+        self.state = state;
+
+        if !transition.is_expected() {
+            self.suspicious = self.suspicious.saturating_add(1);
+            log::warn!("Device state transition: {transition}");
+        }
+
+        Some(transition)
+    }
+
+    /// Resets the model to [Disconnected](DeviceStateFlags::Disconnected), e.g. after losing
+    /// communication with the device.
+    ///
+    /// Also clears the count of [suspicious](Self::suspicious) transitions.
+    pub fn reset(&mut self) {
+        self.state = DeviceStateFlags::Disconnected;
+        self.suspicious = 0;
+    }
+}
+
+impl Default for DeviceStateModel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{OmnibusCommand, OmnibusReply};
+
+    fn reply(device_state: u8, device_status: u8, exception_status: u8, misc: u8) -> OmnibusReply {
+        let mut reply = OmnibusReply::new();
+
+        reply.set_device_state(device_state.into());
+        reply.set_device_status(device_status.into());
+        reply.set_exception_status(exception_status.into());
+        reply.set_misc_device_state(misc.into());
+
+        reply
+    }
+
+    #[test]
+    fn test_state_from_reply() {
+        let ctx = HostContext::new();
+        let disabled = HostContext::new().with_host_disabled(true);
+
+        let idle = reply(0b0000_0001, 0b0001_0000, 0, 0);
+        assert_eq!(
+            DeviceStateFlags::from_reply(&idle, &ctx),
+            DeviceStateFlags::Idle
+        );
+        assert_eq!(
+            DeviceStateFlags::from_reply(&idle, &disabled),
+            DeviceStateFlags::HostDisabled
+        );
+        assert_eq!(
+            DeviceStateFlags::from_reply(&idle, &ctx.with_escrow_session(true)),
+            DeviceStateFlags::IdleInEscrowSession
+        );
+
+        let escrowed = reply(0b0000_0100, 0b0001_0000, 0, 0);
+        assert_eq!(
+            DeviceStateFlags::from_reply(&escrowed, &ctx),
+            DeviceStateFlags::Escrowed
+        );
+
+        // jammed, and the cash box removed: jammed takes priority
+        let jammed = reply(0, 0b0000_0100, 0, 0);
+        assert_eq!(
+            DeviceStateFlags::from_reply(&jammed, &ctx),
+            DeviceStateFlags::Jammed
+        );
+        assert_eq!(
+            DeviceStateFlags::from_reply(&jammed, &disabled),
+            DeviceStateFlags::DisabledAndJammed
+        );
+
+        let removed = reply(0b0000_0001, 0, 0, 0);
+        assert_eq!(
+            DeviceStateFlags::from_reply(&removed, &ctx),
+            DeviceStateFlags::CashBoxRemoved
+        );
+
+        let power_up = reply(0, 0b0001_0000, 0b0000_0001, 0);
+        assert_eq!(
+            DeviceStateFlags::from_reply(&power_up, &ctx),
+            DeviceStateFlags::PowerUp
+        );
+
+        let download = reply(0, 0b0001_0000, 0, 0b0000_0010);
+        assert_eq!(
+            DeviceStateFlags::from_reply(&download, &ctx),
+            DeviceStateFlags::Download
+        );
+
+        let mut command = OmnibusCommand::new();
+        command.set_denomination(StandardDenomination::none());
+        assert!(HostContext::from_command(&command).host_disabled());
+        command.set_denomination(StandardDenomination::all());
+        assert!(!HostContext::from_command(&command).host_disabled());
+    }
+
+    #[test]
+    fn test_state_transitions() {
+        let mut model = DeviceStateModel::new();
+
+        let transition = model
+            .handle_reply(&reply(0, 0b0001_0000, 0b0000_0001, 0))
+            .unwrap();
+        assert_eq!(transition.from(), DeviceStateFlags::Disconnected);
+        assert_eq!(transition.to(), DeviceStateFlags::PowerUp);
+        assert!(transition.is_expected());
+
+        for state in [
+            DeviceStateFlags::Idle,
+            DeviceStateFlags::Accepting,
+            DeviceStateFlags::Escrowed,
+            DeviceStateFlags::Stacking,
+            DeviceStateFlags::Idle,
+        ] {
+            assert!(model.transition(state).unwrap().is_expected());
+        }
+        assert_eq!(model.transition(DeviceStateFlags::Idle), None);
+
+        // stack failed, note returned
+        model.transition(DeviceStateFlags::Stacking);
+        let transition = model.transition(DeviceStateFlags::Returning).unwrap();
+        assert_eq!(transition.check(), TransitionCheck::Suspicious);
+
+        let transition = model.transition(DeviceStateFlags::Stacking).unwrap();
+        assert_eq!(transition.check(), TransitionCheck::Impossible);
+
+        // unexpected reset
+        let transition = model.transition(DeviceStateFlags::PowerUp).unwrap();
+        assert_eq!(transition.check(), TransitionCheck::Suspicious);
+
+        assert_eq!(model.suspicious(), 3);
+
+        model.reset();
+        assert_eq!(model.state(), DeviceStateFlags::Disconnected);
+        assert_eq!(model.suspicious(), 0);
+    }
+}
@@ -3,6 +3,7 @@ use crate::std::fmt;
 use crate::{
     banknote::{Banknote, BanknoteOrientation, NoteTableItem},
     denomination::StandardDenomination,
+    BarcodeVoucher, Coupon,
 };
 
 /// An accepted [NoteTableItem].
@@ -168,6 +169,49 @@ impl fmt::Display for DocumentRouting {
     }
 }
 
+/// Values that represent the type of a document.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum DocumentType {
+    #[default]
+    NoDocument = 0,
+    Banknote = 1,
+    Bookmark = 2,
+    Barcode = 3,
+    Coupon = 4,
+}
+
+impl DocumentType {
+    /// Creates a default [DocumentType].
+    pub const fn new() -> Self {
+        Self::NoDocument
+    }
+}
+
+impl From<DocumentType> for &'static str {
+    fn from(d: DocumentType) -> Self {
+        match d {
+            DocumentType::NoDocument => "No document",
+            DocumentType::Banknote => "Banknote",
+            DocumentType::Bookmark => "Bookmark",
+            DocumentType::Barcode => "Barcode",
+            DocumentType::Coupon => "Coupon",
+        }
+    }
+}
+
+impl From<&DocumentType> for &'static str {
+    fn from(d: &DocumentType) -> Self {
+        (*d).into()
+    }
+}
+
+impl fmt::Display for DocumentType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", <&'static str>::from(self))
+    }
+}
+
 /// A document status.
 #[repr(C)]
 #[derive(Clone, Copy, Debug, Default, PartialEq)]
@@ -180,18 +224,35 @@ pub struct DocumentStatus {
     accepted_note_table_item: AcceptedNoteTableItem,
     /// The [StandardDenomination].
     standard_denomination: StandardDenomination,
+    /// The [DocumentType].
+    document_type: DocumentType,
+    /// The [BarcodeVoucher], for barcode documents.
+    barcode: Option<BarcodeVoucher>,
+    /// The [Coupon], for coupon documents.
+    coupon: Option<Coupon>,
 }
 
 impl fmt::Display for DocumentStatus {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let (event, routing, item, denom) = (
+        let (event, routing, item, denom, doc_type) = (
             &self.document_event,
             &self.document_routing,
             &self.accepted_note_table_item,
             &self.standard_denomination,
+            &self.document_type,
         );
 
-        write!(f, "Document event: {event}, Document routing: {routing}, Accepted note table item: {item}, Standard denomination: {denom}")
+        write!(f, "Document event: {event}, Document routing: {routing}, Accepted note table item: {item}, Standard denomination: {denom}, Document type: {doc_type}")?;
+
+        if let Some(barcode) = self.barcode.as_ref() {
+            write!(f, ", Barcode: {barcode}")?;
+        }
+
+        if let Some(coupon) = self.coupon.as_ref() {
+            write!(f, ", Coupon: {coupon}")?;
+        }
+
+        Ok(())
     }
 }
 
@@ -208,6 +269,9 @@ impl DocumentStatus {
             document_routing,
             accepted_note_table_item,
             standard_denomination,
+            document_type: DocumentType::new(),
+            barcode: None,
+            coupon: None,
         }
     }
 
@@ -283,4 +347,52 @@ impl DocumentStatus {
         self.standard_denomination = standard_denomination;
         self
     }
+
+    /// Gets the [DocumentType].
+    pub fn document_type(&self) -> DocumentType {
+        self.document_type
+    }
+
+    /// Sets the [DocumentType].
+    pub fn set_document_type(&mut self, document_type: DocumentType) {
+        self.document_type = document_type;
+    }
+
+    /// Sets the [DocumentType], consumes and returns the [DocumentStatus].
+    pub fn with_document_type(mut self, document_type: DocumentType) -> Self {
+        self.document_type = document_type;
+        self
+    }
+
+    /// Gets the [BarcodeVoucher], for barcode documents.
+    pub fn barcode(&self) -> Option<&BarcodeVoucher> {
+        self.barcode.as_ref()
+    }
+
+    /// Sets the [BarcodeVoucher].
+    pub fn set_barcode(&mut self, barcode: BarcodeVoucher) {
+        self.barcode = Some(barcode);
+    }
+
+    /// Sets the [BarcodeVoucher], consumes and returns the [DocumentStatus].
+    pub fn with_barcode(mut self, barcode: BarcodeVoucher) -> Self {
+        self.barcode = Some(barcode);
+        self
+    }
+
+    /// Gets the [Coupon], for coupon documents.
+    pub fn coupon(&self) -> Option<Coupon> {
+        self.coupon
+    }
+
+    /// Sets the [Coupon].
+    pub fn set_coupon(&mut self, coupon: Coupon) {
+        self.coupon = Some(coupon);
+    }
+
+    /// Sets the [Coupon], consumes and returns the [DocumentStatus].
+    pub fn with_coupon(mut self, coupon: Coupon) -> Self {
+        self.coupon = Some(coupon);
+        self
+    }
 }
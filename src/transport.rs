@@ -0,0 +1,1186 @@
+mod acceptor_info;
+mod audit;
+mod cash_box;
+mod software_crc;
+
+#[cfg(test)]
+mod mock;
+
+use crate::std;
+use std::{
+    io::{self, Read, Write},
+    ops::ControlFlow,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    len, AckNakSequencer, BaudRate, CalibrationEvent, CalibrationSession, CommandVariant,
+    DeviceEvent, DevicePoller, DocumentStatus, DownloadEvent, DownloadProgress, DownloadRecord,
+    Error, EscrowDecision, EscrowOutcome, EscrowSession, FirmwareDownloader, Frame, FrameDecoder,
+    HostEvent, HostIntent, HostMachine, HostProtocol, HostSession, MessageOps, ReplyVariant,
+    Result, SerialProtocol, DEFAULT_REPLY_TIMEOUT_MS, DOWNLOAD_RECORD_INTERVAL,
+};
+
+/// Blocking transport for host-to-device communication.
+///
+/// Writes a command message, and waits for the device reply.
+///
+/// The transport is generic over the underlying port, so any [Read] + [Write] implementation can
+/// be used. Use [SerialTransport::open] to open a serial port with the EBDS line settings.
+pub struct SerialTransport<P> {
+    port: P,
+    decoder: FrameDecoder,
+    pending: Vec<u8>,
+    reply_timeout: Duration,
+}
+
+impl SerialTransport<Box<dyn serialport::SerialPort>> {
+    /// Opens the serial port at the provided path with the default EBDS line settings.
+    ///
+    /// The default settings are 9600 baud, 7 data bits, even parity, and 1 stop bit.
+    pub fn open(path: &str) -> Result<Self> {
+        Self::open_with(path, BaudRate::_9600, SerialProtocol::_7bit)
+    }
+
+    /// Opens the serial port at the provided path with the provided [BaudRate] and
+    /// [SerialProtocol] line settings.
+    pub fn open_with(path: &str, baud_rate: BaudRate, protocol: SerialProtocol) -> Result<Self> {
+        let reply_timeout = Duration::from_millis(DEFAULT_REPLY_TIMEOUT_MS);
+
+        let port = serialport::new(path, baud_rate.into())
+            .data_bits(data_bits(protocol))
+            .parity(parity(protocol))
+            .stop_bits(serialport::StopBits::One)
+            .flow_control(serialport::FlowControl::None)
+            .timeout(reply_timeout)
+            .open()?;
+
+        log::debug!("Opened serial port: {path}, baud rate: {baud_rate}, protocol: {protocol}");
+
+        Ok(Self::new(port).with_reply_timeout(reply_timeout))
+    }
+}
+
+/// Ports supporting changes to the EBDS serial line settings.
+///
+/// Needed to switch to (and back from) the fast serial download baud rate, see
+/// [SequencedTransport::download_firmware].
+pub trait SetLineSettings {
+    /// Changes the [BaudRate] and [SerialProtocol] line settings of the port.
+    fn set_line_settings(&mut self, baud_rate: BaudRate, protocol: SerialProtocol) -> Result<()>;
+}
+
+impl SetLineSettings for Box<dyn serialport::SerialPort> {
+    fn set_line_settings(&mut self, baud_rate: BaudRate, protocol: SerialProtocol) -> Result<()> {
+        self.set_baud_rate(baud_rate.into())?;
+        self.set_data_bits(data_bits(protocol))?;
+        self.set_parity(parity(protocol))?;
+        self.set_stop_bits(serialport::StopBits::One)?;
+
+        Ok(())
+    }
+}
+
+impl<P: Read + Write + SetLineSettings> SerialTransport<P> {
+    /// Changes the [BaudRate] and [SerialProtocol] line settings of the open serial port.
+    pub fn set_line_settings(
+        &mut self,
+        baud_rate: BaudRate,
+        protocol: SerialProtocol,
+    ) -> Result<()> {
+        log::debug!("Changing line settings, baud rate: {baud_rate}, protocol: {protocol}");
+
+        self.port.set_line_settings(baud_rate, protocol)
+    }
+}
+
+impl<P: Read + Write> SerialTransport<P> {
+    /// Creates a new [SerialTransport] over an already opened port.
+    pub fn new(port: P) -> Self {
+        Self {
+            port,
+            decoder: FrameDecoder::new(),
+            pending: Vec::new(),
+            reply_timeout: Duration::from_millis(DEFAULT_REPLY_TIMEOUT_MS),
+        }
+    }
+
+    /// Gets the reply timeout.
+    pub fn reply_timeout(&self) -> Duration {
+        self.reply_timeout
+    }
+
+    /// Sets the reply timeout.
+    pub fn set_reply_timeout(&mut self, reply_timeout: Duration) {
+        self.reply_timeout = reply_timeout;
+    }
+
+    /// Builder function to set the reply timeout.
+    pub fn with_reply_timeout(mut self, reply_timeout: Duration) -> Self {
+        self.set_reply_timeout(reply_timeout);
+        self
+    }
+
+    /// Gets a reference to the underlying port.
+    pub fn port(&self) -> &P {
+        &self.port
+    }
+
+    /// Gets a mutable reference to the underlying port.
+    pub fn port_mut(&mut self) -> &mut P {
+        &mut self.port
+    }
+
+    /// Consumes the [SerialTransport], returning the underlying port.
+    pub fn into_inner(self) -> P {
+        self.port
+    }
+
+    /// Sends a [CommandVariant] to the device, and waits for the reply.
+    pub fn send(&mut self, command: &mut CommandVariant) -> Result<ReplyVariant> {
+        self.send_message(command.as_message_mut())
+    }
+
+    /// Sends a generic message to the device, and waits for the reply.
+    ///
+    /// Auxilliary replies are parsed using the [AuxCommand](crate::AuxCommand) from the sent message,
+    /// see [ReplyVariant::from_command_buf].
+    pub fn send_message(&mut self, message: &mut dyn MessageOps) -> Result<ReplyVariant> {
+        self.write_message(message)?;
+
+        let reply = self.read_frame()?;
+
+        ReplyVariant::from_command_buf(reply.buf(), message)
+    }
+
+    /// Writes a message to the device.
+    ///
+    /// Calculates the message checksum before writing.
+    pub fn write_message(&mut self, message: &mut dyn MessageOps) -> Result<()> {
+        let bytes = message.as_bytes();
+
+        log::trace!("Sending message: {bytes:x?}");
+
+        self.port.write_all(bytes)?;
+        self.port.flush()?;
+
+        Ok(())
+    }
+
+    /// Reads a single message frame from the device.
+    ///
+    /// Line noise, and frames failing validation, are discarded by the [FrameDecoder]. Returns an
+    /// error if no valid frame is received before the reply timeout, or the port reaches EOF.
+    pub fn read_frame(&mut self) -> Result<Frame> {
+        let deadline = Instant::now() + self.reply_timeout;
+
+        loop {
+            if let Some(frame) = self.decode_pending() {
+                log::trace!("Received message: {frame}");
+                return Ok(frame);
+            }
+
+            if Instant::now() >= deadline {
+                return Err(Error::timeout(format!(
+                    "timed out waiting for reply, received {} bytes of a partial frame",
+                    self.decoder.pending()
+                )));
+            }
+
+            let mut chunk = [0u8; len::MAX_MESSAGE];
+            let read = self.read_input(&mut chunk)?;
+            self.pending.extend_from_slice(&chunk[..read]);
+        }
+    }
+
+    fn decode_pending(&mut self) -> Option<Frame> {
+        let (consumed, frame) = self.decoder.decode(&self.pending);
+        self.pending.drain(..consumed);
+
+        let discarded = self.decoder.take_discarded();
+        if discarded > 0 {
+            log::debug!("Discarded {discarded} bytes of invalid input");
+        }
+
+        frame
+    }
+
+    // Reads the available input, treating a port timeout as an empty read.
+    fn read_input(&mut self, buf: &mut [u8]) -> Result<usize> {
+        match self.port.read(buf) {
+            Ok(0) => Err(Error::serial("device connection closed")),
+            Ok(read) => Ok(read),
+            Err(err)
+                if matches!(
+                    err.kind(),
+                    io::ErrorKind::TimedOut
+                        | io::ErrorKind::WouldBlock
+                        | io::ErrorKind::Interrupted
+                ) =>
+            {
+                Ok(0)
+            }
+            Err(err) => Err(err.into()),
+        }
+    }
+}
+
+/// Blocking transport that sequences the ACK/NAK toggle across a session.
+///
+/// The transport drives a [HostProtocol], which stamps each command with the expected toggle, and
+/// re-sends it on a missing reply, invalid reply, or toggle mismatch, up to the [AckNakSequencer]
+/// retry limit.
+///
+/// Duplicate replies are suppressed, and never returned to the caller.
+///
+/// The transport can also drive a state machine wrapping the [HostProtocol], e.g. a
+/// [DevicePoller], see [with_machine](Self::with_machine).
+pub struct SequencedTransport<P, M = HostProtocol> {
+    transport: SerialTransport<P>,
+    machine: M,
+}
+
+impl<P: Read + Write> SequencedTransport<P> {
+    /// Creates a new [SequencedTransport].
+    ///
+    /// The [HostProtocol] uses the reply timeout of the [SerialTransport].
+    pub fn new(transport: SerialTransport<P>) -> Self {
+        Self::with_machine(transport, HostProtocol::new())
+    }
+
+    /// Sends a [CommandVariant] to the device, and waits for an accepted reply.
+    ///
+    /// The command is stamped with the ACK/NAK toggle it was sent with.
+    pub fn send(&mut self, command: &mut CommandVariant) -> Result<ReplyVariant> {
+        self.machine.handle_intent(HostIntent::Send(*command));
+        self.drive(command.as_message_mut())
+    }
+
+    /// Sends a generic message to the device, and waits for an accepted reply.
+    ///
+    /// The message is stamped with the ACK/NAK toggle it was sent with.
+    pub fn send_message(&mut self, message: &mut dyn MessageOps) -> Result<ReplyVariant> {
+        let command = CommandVariant::try_from(message.as_bytes())?;
+
+        self.machine.handle_intent(HostIntent::Send(command));
+        self.drive(message)
+    }
+
+    /// Runs a guided field calibration, see [CalibrationSession].
+    ///
+    /// Sends a [CalibrateCommand](crate::CalibrateCommand) every poll interval of the session,
+    /// continuing the ACK/NAK sequence of the transport, and passes each [CalibrationEvent] to the
+    /// callback, e.g. to prompt the operator for the calibration document on
+    /// [CalibrationEvent::InsertDocument].
+    ///
+    /// Returns an error if calibration fails.
+    pub fn calibrate<F>(&mut self, mut session: CalibrationSession, mut on_event: F) -> Result<()>
+    where
+        F: FnMut(&CalibrationEvent),
+    {
+        self.run_session(&mut session, |session, _| {
+            while let Some(event) = session.poll_event() {
+                on_event(&event);
+
+                if let CalibrationEvent::Failed(failure) = event {
+                    return Err(Error::failure(format!("calibration failed: {failure}")));
+                }
+            }
+
+            Ok(())
+        })
+    }
+
+    /// Runs a [HostSession] to completion, e.g. a [FirmwareDownloader].
+    ///
+    /// Each [HostEvent] is passed to the session, then `on_step` is called, e.g. to drain the
+    /// session's own events. An error from `on_step` ends the session.
+    ///
+    /// Returns an error if the session stalls, with no intent to send, and nothing to wait for.
+    pub fn run_session<S, F>(&mut self, session: &mut S, mut on_step: F) -> Result<()>
+    where
+        S: HostSession,
+        F: FnMut(&mut S, &mut SerialTransport<P>) -> Result<()>,
+    {
+        let clock = Clock::new(&self.machine);
+
+        loop {
+            while let Some(event) = self.machine.poll_event() {
+                if let HostEvent::BytesDiscarded(count) = event {
+                    log::debug!("Discarded {count} bytes of invalid input");
+                }
+                session.handle_event(&event);
+            }
+
+            on_step(session, &mut self.transport)?;
+
+            if session.is_finished() {
+                return Ok(());
+            }
+
+            if !self.machine.is_busy() {
+                let now_ms = self.machine.now_ms();
+
+                match session.poll_intent(now_ms) {
+                    Some(intent) => self.machine.handle_intent(intent),
+                    None => match session.poll_timeout(now_ms) {
+                        Some(wait_ms) => {
+                            std::thread::sleep(Duration::from_millis(wait_ms));
+                            self.tick(&clock);
+                            continue;
+                        }
+                        None => return Err(Error::failure("session stalled, nothing to send")),
+                    },
+                }
+            }
+
+            self.step(&clock)?;
+        }
+    }
+
+    // Drives the protocol until the queued command is accepted, or fails.
+    fn drive(&mut self, message: &mut dyn MessageOps) -> Result<ReplyVariant> {
+        let clock = Clock::new(&self.machine);
+
+        loop {
+            while let Some(event) = self.machine.poll_event() {
+                match event {
+                    HostEvent::Reply(reply) => return Ok(reply),
+                    HostEvent::CommandFailed(_, err) => return Err(err),
+                    HostEvent::BytesDiscarded(count) => {
+                        log::debug!("Discarded {count} bytes of invalid input")
+                    }
+                }
+            }
+
+            if !self.machine.is_busy() {
+                return Err(Error::failure("no command waiting for a reply"));
+            }
+
+            if let Some(frame) = self.step(&clock)? {
+                message.buf_mut().copy_from_slice(frame.buf());
+            }
+        }
+    }
+}
+
+impl<P: Read + Write, M: HostMachine> SequencedTransport<P, M> {
+    /// Creates a new [SequencedTransport] driving a [HostMachine], e.g. a [DevicePoller].
+    ///
+    /// The [HostProtocol] of the machine uses the reply timeout of the [SerialTransport].
+    pub fn with_machine(transport: SerialTransport<P>, mut machine: M) -> Self {
+        let reply_timeout_ms = transport.reply_timeout().as_millis() as u64;
+        machine
+            .protocol_mut()
+            .set_reply_timeout_ms(reply_timeout_ms);
+
+        Self { transport, machine }
+    }
+
+    /// Gets a reference to the [SerialTransport].
+    pub fn transport(&self) -> &SerialTransport<P> {
+        &self.transport
+    }
+
+    /// Gets a mutable reference to the [SerialTransport].
+    pub fn transport_mut(&mut self) -> &mut SerialTransport<P> {
+        &mut self.transport
+    }
+
+    /// Gets a reference to the [HostMachine].
+    pub fn machine(&self) -> &M {
+        &self.machine
+    }
+
+    /// Gets a mutable reference to the [HostMachine].
+    pub fn machine_mut(&mut self) -> &mut M {
+        &mut self.machine
+    }
+
+    /// Gets a reference to the [HostProtocol].
+    pub fn protocol(&self) -> &HostProtocol {
+        self.machine.protocol()
+    }
+
+    /// Gets a mutable reference to the [HostProtocol].
+    pub fn protocol_mut(&mut self) -> &mut HostProtocol {
+        self.machine.protocol_mut()
+    }
+
+    /// Gets a reference to the [AckNakSequencer].
+    pub fn sequencer(&self) -> &AckNakSequencer {
+        self.protocol().sequencer()
+    }
+
+    /// Gets a mutable reference to the [AckNakSequencer].
+    pub fn sequencer_mut(&mut self) -> &mut AckNakSequencer {
+        self.protocol_mut().sequencer_mut()
+    }
+
+    /// Builder function to set the maximum number of times a command is re-sent.
+    pub fn with_max_retries(mut self, max_retries: u8) -> Self {
+        self.sequencer_mut().set_max_retries(max_retries);
+        self
+    }
+
+    /// Consumes the [SequencedTransport], returning the [SerialTransport].
+    pub fn into_inner(self) -> SerialTransport<P> {
+        self.transport
+    }
+
+    /// Drives the [HostMachine] until `on_step` breaks, returning the break value.
+    ///
+    /// `on_step` is called before each write, and read, e.g. to drain the machine's events. An
+    /// error from `on_step` ends the loop.
+    ///
+    /// Returns an error if the machine stalls, with nothing to send, and nothing to wait for.
+    pub fn run<T, F>(&mut self, mut on_step: F) -> Result<T>
+    where
+        F: FnMut(&mut M) -> Result<ControlFlow<T>>,
+    {
+        let clock = Clock::new(self.machine.protocol());
+
+        loop {
+            if let ControlFlow::Break(value) = on_step(&mut self.machine)? {
+                return Ok(value);
+            }
+
+            match self.machine.poll_timeout() {
+                Some(wait_ms) if wait_ms > 0 && !self.machine.protocol().is_busy() => {
+                    std::thread::sleep(Duration::from_millis(wait_ms));
+                    self.tick(&clock);
+                }
+                Some(_) => {
+                    self.step(&clock)?;
+                }
+                None => return Err(Error::failure("machine stalled, nothing to send")),
+            }
+        }
+    }
+
+    // Writes the pending message, reads the available input, and advances the protocol clock.
+    //
+    // Returns the written message, if any.
+    fn step(&mut self, clock: &Clock) -> Result<Option<Frame>> {
+        let mut transmit = self.machine.poll_transmit();
+        if let Some(frame) = transmit.as_mut() {
+            self.transport.write_message(frame)?;
+        }
+
+        let mut chunk = [0u8; len::MAX_MESSAGE];
+        let read = self.transport.read_input(&mut chunk)?;
+        if read > 0 {
+            self.machine.handle_input(&chunk[..read]);
+        }
+
+        self.tick(clock);
+
+        Ok(transmit)
+    }
+
+    fn tick(&mut self, clock: &Clock) {
+        let elapsed_ms = clock
+            .now_ms()
+            .saturating_sub(self.machine.protocol().now_ms());
+        self.machine.handle_elapsed(elapsed_ms);
+    }
+}
+
+impl<P: Read + Write> SequencedTransport<P, DevicePoller> {
+    /// Runs the Omnibus poll loop of the [DevicePoller], passing each [DeviceEvent] to the callback,
+    /// until the callback breaks.
+    ///
+    /// Polling is started, if the poller is stopped. Commands failing after all retries are
+    /// logged, and polling continues.
+    pub fn poll<F>(&mut self, mut on_event: F) -> Result<()>
+    where
+        F: FnMut(&DeviceEvent) -> ControlFlow<()>,
+    {
+        if !self.machine.protocol().polling() {
+            self.machine.start();
+        }
+
+        self.run(|poller| {
+            while let Some(event) = poller.poll_host_event() {
+                log_host_event(&event);
+            }
+
+            while let Some(event) = poller.poll_event() {
+                if on_event(&event).is_break() {
+                    return Ok(ControlFlow::Break(()));
+                }
+            }
+
+            Ok(ControlFlow::Continue(()))
+        })
+    }
+}
+
+impl<P, F> SequencedTransport<P, EscrowSession<F>>
+where
+    P: Read + Write,
+    F: FnMut(&DocumentStatus) -> Option<EscrowDecision>,
+{
+    /// Runs the [EscrowSession], passing each [EscrowOutcome] to the callback, until the callback
+    /// breaks.
+    ///
+    /// The session is started, if the poller is stopped. Commands failing after all retries are
+    /// logged, and polling continues. Deferred decisions can be made with
+    /// [EscrowSession::decide] through [run](Self::run).
+    pub fn run_escrow<G>(&mut self, mut on_outcome: G) -> Result<()>
+    where
+        G: FnMut(&EscrowOutcome) -> ControlFlow<()>,
+    {
+        if !self.machine.poller().protocol().polling() {
+            self.machine.start();
+        }
+
+        self.run(|session| {
+            while let Some(event) = session.poll_host_event() {
+                log_host_event(&event);
+            }
+
+            while let Some(event) = session.poll_event() {
+                log::trace!("Device event: {event}");
+            }
+
+            while let Some(outcome) = session.poll_outcome() {
+                if on_outcome(&outcome).is_break() {
+                    return Ok(ControlFlow::Break(()));
+                }
+            }
+
+            Ok(ControlFlow::Continue(()))
+        })
+    }
+}
+
+impl<P: Read + Write + SetLineSettings> SequencedTransport<P> {
+    /// Downloads a firmware image to the device, driving the [FirmwareDownloader] to completion.
+    ///
+    /// The `on_progress` callback is called each time the device acknowledges a packet.
+    ///
+    /// Line settings are changed as requested by the [FirmwareDownloader], and restored after the
+    /// final packet. Messages are sequenced, and re-sent, by the [HostProtocol] of the transport, so
+    /// the download continues the ACK/NAK toggle of the session.
+    pub fn download_firmware<F>(
+        &mut self,
+        downloader: FirmwareDownloader,
+        mut on_progress: F,
+    ) -> Result<()>
+    where
+        F: FnMut(&DownloadProgress),
+    {
+        self.drive_download(downloader, |_, progress| {
+            on_progress(progress);
+            Ok(())
+        })
+    }
+
+    /// Downloads a firmware image to the device, persisting a [DownloadRecord] at `record_path`.
+    ///
+    /// The record is saved every [DOWNLOAD_RECORD_INTERVAL] acknowledged packets, and when the
+    /// device rewinds the download. If a record for the same image already exists (e.g. after a
+    /// host crash), the download resumes using the record as a hint, and continues from the packet
+    /// the device reports, see [FirmwareDownloader::resume]. A record for a different image is
+    /// ignored.
+    ///
+    /// When resuming, the `protocol`, and `baud_rate` arguments are ignored in favour of the
+    /// settings negotiated with the device before the interruption, as stored in the record.
+    ///
+    /// The record is removed after the download completes.
+    pub fn download_firmware_resumable<F, R>(
+        &mut self,
+        image: &[u8],
+        protocol: SerialProtocol,
+        baud_rate: BaudRate,
+        record_path: R,
+        mut on_progress: F,
+    ) -> Result<()>
+    where
+        F: FnMut(&DownloadProgress),
+        R: AsRef<std::path::Path>,
+    {
+        let record_path = record_path.as_ref();
+
+        let resumed = match DownloadRecord::load(record_path)? {
+            Some(record) => FirmwareDownloader::resume(image, &record)
+                .map_err(|err| log::warn!("Ignoring download record: {err}"))
+                .ok(),
+            None => None,
+        };
+
+        let downloader = match resumed {
+            Some(downloader) => {
+                if downloader.protocol() != protocol || downloader.baud_rate() != baud_rate {
+                    log::info!(
+                        "Resuming with the download record settings: {} protocol, {} baud",
+                        downloader.protocol(),
+                        downloader.baud_rate()
+                    );
+                }
+                downloader
+            }
+            None => FirmwareDownloader::new(image, protocol)?.with_baud_rate(baud_rate),
+        };
+
+        let mut last_saved = downloader.progress().packets_acked();
+
+        self.drive_download(downloader, |downloader, progress| {
+            let acked = progress.packets_acked();
+
+            if acked < last_saved || acked - last_saved >= DOWNLOAD_RECORD_INTERVAL {
+                downloader.record().save(record_path)?;
+                last_saved = acked;
+            }
+
+            on_progress(progress);
+            Ok(())
+        })?;
+
+        match std::fs::remove_file(record_path) {
+            // short downloads may finish before the first save
+            Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err.into()),
+            _ => Ok(()),
+        }
+    }
+
+    fn drive_download<F>(
+        &mut self,
+        mut downloader: FirmwareDownloader,
+        mut on_progress: F,
+    ) -> Result<()>
+    where
+        F: FnMut(&FirmwareDownloader, &DownloadProgress) -> Result<()>,
+    {
+        self.run_session(&mut downloader, |downloader, transport| {
+            while let Some(event) = downloader.poll_event() {
+                match event {
+                    DownloadEvent::SetLineSettings(baud_rate, protocol) => {
+                        transport.set_line_settings(baud_rate, protocol)?
+                    }
+                    DownloadEvent::Progress(progress) => on_progress(downloader, &progress)?,
+                    DownloadEvent::Complete => (),
+                    DownloadEvent::Failed(err) => return Err(err),
+                }
+            }
+
+            Ok(())
+        })
+    }
+}
+
+// Logs the protocol events not surfaced by the poll loops.
+fn log_host_event(event: &HostEvent) {
+    match event {
+        HostEvent::CommandFailed(command, err) => {
+            log::warn!("Command failed: {command}, error: {err}")
+        }
+        HostEvent::BytesDiscarded(count) => {
+            log::debug!("Discarded {count} bytes of invalid input")
+        }
+        HostEvent::Reply(_) => (),
+    }
+}
+
+// Keeps the protocol time in step with the wall clock.
+struct Clock {
+    epoch: Instant,
+    start_ms: u64,
+}
+
+impl Clock {
+    fn new(protocol: &HostProtocol) -> Self {
+        Self {
+            epoch: Instant::now(),
+            start_ms: protocol.now_ms(),
+        }
+    }
+
+    fn now_ms(&self) -> u64 {
+        self.start_ms
+            .saturating_add(self.epoch.elapsed().as_millis() as u64)
+    }
+}
+
+fn data_bits(protocol: SerialProtocol) -> serialport::DataBits {
+    match protocol {
+        SerialProtocol::_7bit => serialport::DataBits::Seven,
+        SerialProtocol::_8bit => serialport::DataBits::Eight,
+    }
+}
+
+fn parity(protocol: SerialProtocol) -> serialport::Parity {
+    match protocol {
+        SerialProtocol::_7bit => serialport::Parity::Even,
+        SerialProtocol::_8bit => serialport::Parity::None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::mock::MockPort;
+    use super::*;
+    use crate::{
+        index, AckNak, BaudRateChangeReply, CalibrateReply, CassetteAttached, DocumentStack,
+        DownloadReady, ErrorCode, FlashDownloadReply, FlashDownloadReply7bit,
+        FlashDownloadReply8bit, Idling, OmnibusCommand, OmnibusCommandOps, OmnibusReply,
+        OmnibusReplyOps, QueryBootPartNumberCommand, ReplyVariant, SetEscrowTimeoutReply,
+        StartDownloadCommand, StartDownloadReply,
+    };
+
+    #[test]
+    #[rustfmt::skip]
+    fn test_send_omnibus_command() -> Result<()> {
+        let msg_bytes = [
+            // Noise
+            0xff, 0x00,
+            // STX | LEN | Message type
+            0x02, 0x0b, 0x20,
+            // Data
+            0b0000_0001, 0b0001_0000, 0b0000_0000, 0b0000_0000, 0b0000_0000, 0b0000_0000,
+            // ETX | Checksum
+            0x03, 0x3a,
+        ];
+
+        let mut transport = SerialTransport::new(MockPort::new(msg_bytes.as_ref()));
+        let mut command = CommandVariant::OmnibusCommand(OmnibusCommand::new());
+
+        let reply = transport.send(&mut command)?;
+
+        assert_eq!(transport.port().tx.as_slice(), command.as_message().as_bytes_unchecked());
+        assert!(matches!(reply, ReplyVariant::OmnibusReply(_)));
+        assert_eq!(reply.as_omnibus_reply().idling(), Idling::Set);
+        assert_eq!(reply.as_omnibus_reply().cassette_attached(), CassetteAttached::Set);
+
+        Ok(())
+    }
+
+    #[test]
+    #[rustfmt::skip]
+    fn test_send_aux_command() -> Result<()> {
+        let msg_bytes = [
+            // STX | LEN | Message type
+            0x02, 0x0e, 0x60,
+            // Project number (in ASCII)
+            b'2', b'8', b'6', b'0', b'0', b'0',
+            // Version (in ASCII)
+            b'1', b'2', b'3',
+            // ETX | Checksum
+            0x03, 0x52,
+        ];
+
+        let mut transport = SerialTransport::new(MockPort::new(msg_bytes.as_ref()));
+        let mut command = CommandVariant::QueryBootPartNumberCommand(QueryBootPartNumberCommand::new());
+
+        let reply = transport.send(&mut command)?;
+
+        assert!(matches!(reply, ReplyVariant::QueryBootPartNumberReply(_)));
+
+        Ok(())
+    }
+
+    #[test]
+    #[rustfmt::skip]
+    fn test_sequenced_send() -> Result<()> {
+        let msg_bytes = [
+            // STX | LEN | Message type (ACK 0)
+            0x02, 0x0b, 0x20,
+            // Data
+            0b0000_0001, 0b0001_0000, 0b0000_0000, 0b0000_0000, 0b0000_0000, 0b0000_0000,
+            // ETX | Checksum (invalid)
+            0x03, 0x00,
+
+            // STX | LEN | Message type (ACK 0)
+            0x02, 0x0b, 0x20,
+            // Data
+            0b0000_0001, 0b0001_0000, 0b0000_0000, 0b0000_0000, 0b0000_0000, 0b0000_0000,
+            // ETX | Checksum
+            0x03, 0x3a,
+
+            // STX | LEN | Message type (ACK 0, duplicate)
+            0x02, 0x0b, 0x20,
+            // Data
+            0b0000_0001, 0b0001_0000, 0b0000_0000, 0b0000_0000, 0b0000_0000, 0b0000_0000,
+            // ETX | Checksum
+            0x03, 0x3a,
+
+            // STX | LEN | Message type (ACK 1)
+            0x02, 0x0b, 0x21,
+            // Data
+            0b0000_0001, 0b0001_0000, 0b0000_0000, 0b0000_0000, 0b0000_0000, 0b0000_0000,
+            // ETX | Checksum
+            0x03, 0x3b,
+        ];
+
+        let mut transport = SequencedTransport::new(SerialTransport::new(MockPort::new(msg_bytes.as_ref())));
+        let mut command = CommandVariant::OmnibusCommand(OmnibusCommand::new());
+
+        let reply = transport.send(&mut command)?;
+        assert_eq!(reply.as_message().acknak(), AckNak::Ack);
+
+        let reply = transport.send(&mut command)?;
+        assert_eq!(reply.as_message().acknak(), AckNak::Nak);
+
+        // the corrupt frame, and the duplicate reply, are discarded without re-sending
+        let tx = transport.transport().port().tx.as_slice();
+        let control: Vec<u8> = tx.chunks(len::OMNIBUS_COMMAND).map(|c| c[index::CONTROL]).collect();
+        assert_eq!(control, [0x10, 0x11]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sequenced_send_retries_exhausted() {
+        let transport =
+            SerialTransport::new(MockPort::new(&[])).with_reply_timeout(Duration::from_millis(5));
+        let mut transport = SequencedTransport::new(transport).with_max_retries(2);
+        let mut command = CommandVariant::OmnibusCommand(OmnibusCommand::new());
+
+        let err = transport.send(&mut command).unwrap_err();
+
+        assert_eq!(err.code(), ErrorCode::Timeout);
+        assert_eq!(
+            transport.transport().port().tx.len(),
+            len::OMNIBUS_COMMAND * 3
+        );
+    }
+
+    #[test]
+    fn test_send_timeout() {
+        let mut transport = SerialTransport::new(MockPort::new(&[0x02, 0x0b, 0x20]))
+            .with_reply_timeout(Duration::from_millis(10));
+        let mut command = CommandVariant::OmnibusCommand(OmnibusCommand::new());
+
+        let err = transport.send(&mut command).unwrap_err();
+
+        assert_eq!(err.code(), ErrorCode::Timeout);
+    }
+
+    #[test]
+    fn test_send_closed() {
+        let mut transport = SerialTransport::new(MockPort::closed(&[0x02, 0x0b, 0x20]));
+        let mut command = CommandVariant::OmnibusCommand(OmnibusCommand::new());
+
+        let err = transport.send(&mut command).unwrap_err();
+
+        assert_eq!(err.code(), ErrorCode::SerialPort);
+    }
+
+    #[test]
+    fn test_download_firmware() -> Result<()> {
+        let image: Vec<u8> = (0..128u8).collect();
+
+        let mut start_reply = StartDownloadReply::new();
+        start_reply.set_download_ready(DownloadReady::Set);
+
+        let mut baud_reply = BaudRateChangeReply::new();
+        baud_reply.set_acknak(AckNak::Nak);
+        baud_reply.set_baud_rate(BaudRate::_115200);
+
+        let mut rx = start_reply.as_bytes().to_vec();
+        rx.extend_from_slice(baud_reply.as_bytes());
+
+        for (n, acknak) in [(0, AckNak::Ack), (1, AckNak::Nak)] {
+            let mut reply = FlashDownloadReply8bit::new();
+            reply.set_acknak(acknak);
+            reply.set_packet_number(n);
+            rx.extend_from_slice(reply.as_bytes());
+        }
+
+        let mut transport =
+            SequencedTransport::new(SerialTransport::new(MockPort::new(rx.as_ref())));
+        let downloader = FirmwareDownloader::new(image.as_ref(), SerialProtocol::_8bit)?
+            .with_baud_rate(BaudRate::_115200);
+
+        let mut progress = Vec::new();
+        transport.download_firmware(downloader, |p| progress.push(p.percent()))?;
+
+        assert_eq!(progress, [50, 100]);
+        assert_eq!(
+            transport.transport().port().line_settings,
+            [
+                (BaudRate::_115200, SerialProtocol::_8bit),
+                (BaudRate::_9600, SerialProtocol::_7bit),
+            ]
+        );
+        assert_eq!(
+            transport.transport().port().tx.len(),
+            len::START_DOWNLOAD_COMMAND
+                + len::BAUD_CHANGE_REQUEST
+                + len::FLASH_DOWNLOAD_MESSAGE_8BIT_64 * 2
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_download_firmware_continues_sequence() -> Result<()> {
+        let image: Vec<u8> = (0..32u8).collect();
+
+        let mut omnibus_reply = OmnibusReply::new();
+        omnibus_reply.set_acknak(AckNak::Ack);
+
+        let mut start_reply = StartDownloadReply::new();
+        start_reply.set_acknak(AckNak::Nak);
+        start_reply.set_download_ready(DownloadReady::Set);
+
+        // the device answers the packet with an invalid reply
+        let mut packet_reply = StartDownloadCommand::new();
+        packet_reply.set_acknak(AckNak::Ack);
+
+        let mut rx = omnibus_reply.as_bytes().to_vec();
+        rx.extend_from_slice(start_reply.as_bytes());
+        rx.extend_from_slice(packet_reply.as_bytes());
+
+        let transport = SerialTransport::new(MockPort::new(rx.as_ref()))
+            .with_reply_timeout(Duration::from_millis(5));
+        let mut transport = SequencedTransport::new(transport).with_max_retries(0);
+
+        transport.send(&mut CommandVariant::OmnibusCommand(OmnibusCommand::new()))?;
+
+        let downloader = FirmwareDownloader::new(image.as_ref(), SerialProtocol::_7bit)?;
+        let err = transport.download_firmware(downloader, |_| ()).unwrap_err();
+
+        assert_eq!(err.code(), ErrorCode::Failure);
+        assert_eq!(
+            err.message(),
+            "unsupported FirmwareDownload reply message length: 8"
+        );
+        assert_eq!(
+            transport.transport().port().sent_acknaks(),
+            [AckNak::Ack, AckNak::Nak, AckNak::Ack]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    #[rustfmt::skip]
+    fn test_poll() -> Result<()> {
+        let msg_bytes = [
+            // STX | LEN | Message type (ACK 0)
+            0x02, 0x0b, 0x20,
+            // Data (idling)
+            0b0000_0001, 0b0001_0000, 0b0000_0000, 0b0000_0000, 0b0000_0000, 0b0000_0000,
+            // ETX | Checksum
+            0x03, 0x3a,
+
+            // STX | LEN | Message type (ACK 1)
+            0x02, 0x0b, 0x21,
+            // Data (idling, stacked)
+            0b0001_0001, 0b0001_0000, 0b0000_0000, 0b0000_0000, 0b0000_0000, 0b0000_0000,
+            // ETX | Checksum
+            0x03, 0x2b,
+        ];
+
+        let poller = DevicePoller::new().with_poll_interval_ms(0);
+        let mut transport = SequencedTransport::with_machine(
+            SerialTransport::new(MockPort::new(msg_bytes.as_ref())),
+            poller,
+        );
+
+        let mut events = Vec::new();
+        transport.poll(|event| {
+            events.push(*event);
+            match event {
+                DeviceEvent::Stacked(_) => ControlFlow::Break(()),
+                _ => ControlFlow::Continue(()),
+            }
+        })?;
+
+        assert_eq!(events.last(), Some(&DeviceEvent::Stacked(DocumentStatus::default())));
+        assert_eq!(transport.transport().port().sent_acknaks(), [AckNak::Ack, AckNak::Nak]);
+        assert_eq!(transport.sequencer().acknak(), AckNak::Ack);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_run_escrow() -> Result<()> {
+        let mut msg_bytes = Vec::new();
+
+        let mut reply = SetEscrowTimeoutReply::new();
+        reply.set_device_state(0b0000_0001.into());
+        msg_bytes.extend_from_slice(reply.as_bytes());
+
+        for (acknak, device_state) in [
+            // escrowed
+            (AckNak::Nak, 0b0000_0100u8),
+            // idling, stacked
+            (AckNak::Ack, 0b0001_0001u8),
+        ] {
+            let mut reply = OmnibusReply::new();
+            reply.set_acknak(acknak);
+            reply.set_device_state(device_state.into());
+            msg_bytes.extend_from_slice(reply.as_bytes());
+        }
+
+        // the decision is made on the escrowed reply, before the next poll is due
+        let poller = DevicePoller::new().with_poll_interval_ms(5);
+        let session = EscrowSession::new(poller, |_: &DocumentStatus| Some(EscrowDecision::Stack));
+        let mut transport = SequencedTransport::with_machine(
+            SerialTransport::new(MockPort::new(msg_bytes.as_ref())),
+            session,
+        );
+
+        let mut outcomes = Vec::new();
+        transport.run_escrow(|outcome| {
+            outcomes.push(*outcome);
+            ControlFlow::Break(())
+        })?;
+
+        assert_eq!(
+            outcomes,
+            [EscrowOutcome::Stacked(DocumentStatus::default())]
+        );
+        assert_eq!(
+            transport.transport().port().sent_acknaks(),
+            [AckNak::Ack, AckNak::Nak, AckNak::Ack]
+        );
+
+        // the stack bit is set while the document is in escrow
+        let sent = transport.transport().port().tx.as_slice();
+        let last = &sent[sent.len() - len::OMNIBUS_COMMAND..];
+        let mut poll = OmnibusCommand::new();
+        poll.from_buf(last)?;
+        assert_eq!(poll.document_stack(), DocumentStack::Set);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_calibrate() -> Result<()> {
+        let mut rx = Vec::new();
+        let mut acknak = AckNak::Ack;
+
+        for (calibration, accepting) in
+            [(false, false), (true, false), (true, true), (false, false)]
+        {
+            let mut reply = CalibrateReply::new();
+            reply.set_acknak(acknak);
+            reply.set_calibration(calibration.into());
+            reply.set_accepting(accepting.into());
+            rx.extend_from_slice(reply.as_bytes());
+            acknak = !acknak;
+        }
+
+        let mut transport =
+            SequencedTransport::new(SerialTransport::new(MockPort::new(rx.as_ref())));
+        let mut events = Vec::new();
+
+        let session = CalibrationSession::new().with_poll_interval_ms(0);
+        transport.calibrate(session, |event| events.push(*event))?;
+
+        assert_eq!(
+            events,
+            [
+                CalibrationEvent::InsertDocument,
+                CalibrationEvent::Calibrating,
+                CalibrationEvent::Succeeded
+            ]
+        );
+        assert_eq!(
+            transport.transport().port().tx.len(),
+            len::CALIBRATE_COMMAND * 4
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_download_firmware_resumable() -> Result<()> {
+        let image: Vec<u8> = (0..96u8).collect();
+        let record_path =
+            std::env::temp_dir().join(format!("ebds-download-record-{}.json", std::process::id()));
+
+        // host crashed after the device acknowledged packet 0
+        let record = FirmwareDownloader::new(image.as_ref(), SerialProtocol::_7bit)?.record();
+        assert_eq!(record.last_acked_packet(), None);
+        DownloadRecord::new(
+            record.sha256(),
+            SerialProtocol::_7bit,
+            Some(0),
+            BaudRate::_9600,
+        )
+        .save(&record_path)?;
+
+        let mut start_reply = StartDownloadReply::new();
+        start_reply.set_download_ready(DownloadReady::Set);
+
+        let mut rx = start_reply.as_bytes().to_vec();
+        for (n, acknak) in [(1, AckNak::Nak), (2, AckNak::Ack)] {
+            let mut reply = FlashDownloadReply7bit::new();
+            reply.set_acknak(acknak);
+            reply.set_packet_number(n);
+            rx.extend_from_slice(reply.as_bytes());
+        }
+
+        let mut transport =
+            SequencedTransport::new(SerialTransport::new(MockPort::new(rx.as_ref())));
+
+        let mut progress = Vec::new();
+        transport.download_firmware_resumable(
+            image.as_ref(),
+            SerialProtocol::_7bit,
+            BaudRate::_9600,
+            &record_path,
+            |p| progress.push(p.packets_acked()),
+        )?;
+
+        assert_eq!(progress, [2, 3]);
+        assert!(!record_path.exists());
+        assert_eq!(
+            transport.transport().port().tx.len(),
+            len::START_DOWNLOAD_COMMAND + len::FLASH_DOWNLOAD_MESSAGE_7BIT * 2
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_download_record_throttled() -> Result<()> {
+        let image: Vec<u8> = (0..40 * 32).map(|b| b as u8).collect();
+        let record_path = std::env::temp_dir().join(format!(
+            "ebds-download-record-throttled-{}.json",
+            std::process::id()
+        ));
+
+        let mut start_reply = StartDownloadReply::new();
+        start_reply.set_download_ready(DownloadReady::Set);
+
+        let mut rx = start_reply.as_bytes().to_vec();
+        let mut acknak = AckNak::Nak;
+
+        // the device stops replying after packet 34
+        for n in 0..35 {
+            let mut reply = FlashDownloadReply7bit::new();
+            reply.set_acknak(acknak);
+            reply.set_packet_number(n);
+            rx.extend_from_slice(reply.as_bytes());
+            acknak = !acknak;
+        }
+
+        let transport = SerialTransport::new(MockPort::new(rx.as_ref()))
+            .with_reply_timeout(Duration::from_millis(5));
+        let mut transport = SequencedTransport::new(transport);
+
+        assert!(transport
+            .download_firmware_resumable(
+                image.as_ref(),
+                SerialProtocol::_7bit,
+                BaudRate::_9600,
+                &record_path,
+                |_| (),
+            )
+            .is_err());
+
+        // the record was last saved after 32 acknowledged packets
+        let record = DownloadRecord::load(&record_path)?.unwrap();
+        assert_eq!(record.last_acked_packet(), Some(31));
+
+        std::fs::remove_file(&record_path)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,63 @@
+use crate::std;
+use std::io::{Read, Write};
+
+use crate::{
+    AcceptorType, QueryAcceptorSerialNumberCommand, QueryAcceptorTypeCommand, Result, SerialNumber,
+};
+
+use super::SequencedTransport;
+
+impl<P: Read + Write> SequencedTransport<P> {
+    /// Queries the [AcceptorType] of the device.
+    pub fn query_acceptor_type(&mut self) -> Result<AcceptorType> {
+        let reply = self.send_message(&mut QueryAcceptorTypeCommand::new())?;
+
+        Ok(reply.as_query_acceptor_type_reply()?.acceptor_type())
+    }
+
+    /// Queries the factory [SerialNumber] of the device.
+    ///
+    /// Returns an error if the device replies with an invalid serial number.
+    pub fn query_serial_number(&mut self) -> Result<SerialNumber> {
+        let reply = self.send_message(&mut QueryAcceptorSerialNumberCommand::new())?;
+
+        reply
+            .as_query_acceptor_serial_number_reply()?
+            .serial_number()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{mock::MockPort, SerialTransport};
+    use super::*;
+    use crate::{AckNak, MessageOps, QueryAcceptorSerialNumberReply, QueryAcceptorTypeReply};
+
+    #[test]
+    fn test_query_acceptor_info() -> Result<()> {
+        let mut type_reply = QueryAcceptorTypeReply::new();
+        type_reply.set_type_name("SC Advance");
+
+        let mut serial_reply = QueryAcceptorSerialNumberReply::new();
+        serial_reply.set_acknak(AckNak::Nak);
+        serial_reply.set_serial_number(&SerialNumber::new("1234567890")?);
+
+        let mut rx = type_reply.as_bytes().to_vec();
+        rx.extend_from_slice(serial_reply.as_bytes());
+        type_reply.set_acknak(AckNak::Ack);
+        rx.extend_from_slice(type_reply.as_bytes());
+
+        let mut transport = SequencedTransport::new(SerialTransport::new(MockPort::new(&rx)));
+
+        assert_eq!(transport.query_acceptor_type()?, AcceptorType::ScAdvance);
+        assert_eq!(transport.query_serial_number()?.as_str(), "1234567890");
+        assert_eq!(transport.query_acceptor_type()?, AcceptorType::ScAdvance);
+
+        assert_eq!(
+            transport.transport().port().sent_acknaks(),
+            [AckNak::Ack, AckNak::Nak, AckNak::Ack]
+        );
+
+        Ok(())
+    }
+}
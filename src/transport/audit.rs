@@ -0,0 +1,67 @@
+use crate::std;
+use std::io::{Read, Write};
+
+use crate::{
+    QueryAuditLifeTimeTotalsCommand, QueryAuditLifeTimeTotalsReply,
+    QueryAuditPerformanceMeasuresCommand, QueryAuditPerformanceMeasuresReply,
+    QueryAuditQpMeasuresCommand, QueryAuditQpMeasuresReply, Result,
+};
+
+use super::SequencedTransport;
+
+impl<P: Read + Write> SequencedTransport<P> {
+    /// Queries the life-time audit totals of the device.
+    pub fn query_audit_life_time_totals(&mut self) -> Result<QueryAuditLifeTimeTotalsReply> {
+        let reply = self.send_message(&mut QueryAuditLifeTimeTotalsCommand::new())?;
+
+        reply.into_query_audit_life_time_totals_reply()
+    }
+
+    /// Queries the quick-performance (QP) audit measures of the device.
+    pub fn query_audit_qp_measures(&mut self) -> Result<QueryAuditQpMeasuresReply> {
+        let reply = self.send_message(&mut QueryAuditQpMeasuresCommand::new())?;
+
+        reply.into_query_audit_qp_measures_reply()
+    }
+
+    /// Queries the performance audit measures of the device.
+    pub fn query_audit_performance_measures(
+        &mut self,
+    ) -> Result<QueryAuditPerformanceMeasuresReply> {
+        let reply = self.send_message(&mut QueryAuditPerformanceMeasuresCommand::new())?;
+
+        reply.into_query_audit_performance_measures_reply()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{mock::MockPort, SerialTransport};
+    use super::*;
+    use crate::{AckNak, MessageOps};
+
+    #[test]
+    fn test_query_audit() -> Result<()> {
+        let mut totals_reply = QueryAuditLifeTimeTotalsReply::new();
+        let mut qp_reply = QueryAuditQpMeasuresReply::new();
+        let mut performance_reply = QueryAuditPerformanceMeasuresReply::new();
+        qp_reply.set_acknak(AckNak::Nak);
+
+        let mut rx = totals_reply.as_bytes().to_vec();
+        rx.extend_from_slice(qp_reply.as_bytes());
+        rx.extend_from_slice(performance_reply.as_bytes());
+
+        let mut transport = SequencedTransport::new(SerialTransport::new(MockPort::new(&rx)));
+
+        transport.query_audit_life_time_totals()?;
+        transport.query_audit_qp_measures()?;
+        transport.query_audit_performance_measures()?;
+
+        assert_eq!(
+            transport.transport().port().sent_acknaks(),
+            [AckNak::Ack, AckNak::Nak, AckNak::Ack]
+        );
+
+        Ok(())
+    }
+}
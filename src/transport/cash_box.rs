@@ -0,0 +1,89 @@
+use crate::std;
+use std::io::{Read, Write};
+
+use crate::{
+    ClearCashBoxTotalCommand, Error, QueryCashBoxTotalCommand, QueryDeviceResetsCommand, Result,
+};
+
+use super::SequencedTransport;
+
+impl<P: Read + Write> SequencedTransport<P> {
+    /// Queries the number of notes stacked in the cash box since the total was last cleared.
+    pub fn query_cash_box_total(&mut self) -> Result<u32> {
+        let reply = self.send_message(&mut QueryCashBoxTotalCommand::new())?;
+
+        Ok(reply.as_query_cash_box_total_reply()?.cash_box_total())
+    }
+
+    /// Queries the number of times the device has been reset.
+    pub fn query_device_resets(&mut self) -> Result<u32> {
+        let reply = self.send_message(&mut QueryDeviceResetsCommand::new())?;
+
+        Ok(reply.as_query_device_resets_reply()?.device_resets())
+    }
+
+    /// Clears the cash box total, and returns the total reported after the clear.
+    ///
+    /// Returns an error if the device reports a non-zero total.
+    pub fn clear_cash_box_total(&mut self) -> Result<u32> {
+        let reply = self.send_message(&mut ClearCashBoxTotalCommand::new())?;
+        let total = reply.as_clear_cash_box_total_reply()?.cash_box_total();
+
+        if total == 0 {
+            Ok(total)
+        } else {
+            Err(Error::failure(format!(
+                "cash box total not cleared, have: {total}"
+            )))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{mock::MockPort, SerialTransport};
+    use super::*;
+    use crate::{
+        AckNak, ClearCashBoxTotalReply, MessageOps, QueryCashBoxTotalReply, QueryDeviceResetsReply,
+    };
+
+    #[test]
+    fn test_cash_box_total() -> Result<()> {
+        let mut total_reply = QueryCashBoxTotalReply::new();
+        total_reply.set_cash_box_total(1234);
+
+        let mut cleared_reply = ClearCashBoxTotalReply::new();
+        cleared_reply.set_acknak(AckNak::Nak);
+
+        let mut resets_reply = QueryDeviceResetsReply::new();
+        resets_reply.set_device_resets(7);
+
+        let mut rx = total_reply.as_bytes().to_vec();
+        // the device replays the reply to the query, before the reply to the clear
+        rx.extend_from_slice(total_reply.as_bytes());
+        rx.extend_from_slice(cleared_reply.as_bytes());
+        rx.extend_from_slice(resets_reply.as_bytes());
+
+        let mut transport = SequencedTransport::new(SerialTransport::new(MockPort::new(&rx)));
+
+        assert_eq!(transport.query_cash_box_total()?, 1234);
+        assert_eq!(transport.clear_cash_box_total()?, 0);
+        assert_eq!(transport.query_device_resets()?, 7);
+
+        assert_eq!(
+            transport.transport().port().sent_acknaks(),
+            [AckNak::Ack, AckNak::Nak, AckNak::Ack]
+        );
+
+        let mut not_cleared_reply = ClearCashBoxTotalReply::new();
+        not_cleared_reply.set_cash_box_total(1234);
+
+        let mut transport = SequencedTransport::new(SerialTransport::new(MockPort::new(
+            not_cleared_reply.as_bytes(),
+        )));
+
+        assert!(transport.clear_cash_box_total().is_err());
+
+        Ok(())
+    }
+}
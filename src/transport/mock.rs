@@ -0,0 +1,81 @@
+use crate::std;
+use std::{
+    io::{self, Read, Write},
+    time::Duration,
+};
+
+use crate::{index, AckNak, BaudRate, Result, SerialProtocol};
+
+use super::SetLineSettings;
+
+/// Mock serial port replaying canned device replies.
+pub(crate) struct MockPort {
+    rx: io::Cursor<Vec<u8>>,
+    pub(crate) tx: Vec<u8>,
+    pub(crate) line_settings: Vec<(BaudRate, SerialProtocol)>,
+    closed: bool,
+}
+
+impl MockPort {
+    pub(crate) fn new(rx: &[u8]) -> Self {
+        Self {
+            rx: io::Cursor::new(rx.into()),
+            tx: Vec::new(),
+            line_settings: Vec::new(),
+            closed: false,
+        }
+    }
+
+    pub(crate) fn closed(rx: &[u8]) -> Self {
+        Self {
+            closed: true,
+            ..Self::new(rx)
+        }
+    }
+
+    /// Gets the ACK/NAK toggle of each message written to the port.
+    pub(crate) fn sent_acknaks(&self) -> Vec<AckNak> {
+        let mut acknaks = Vec::new();
+        let mut sent = self.tx.as_slice();
+
+        while sent.len() > index::CONTROL {
+            acknaks.push(AckNak::from(sent[index::CONTROL]));
+            sent = &sent[(sent[index::LEN] as usize).min(sent.len())..];
+        }
+
+        acknaks
+    }
+}
+
+impl Read for MockPort {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        // simulate fragmented serial reads
+        let end = buf.len().min(3);
+        match self.rx.read(&mut buf[..end])? {
+            0 if self.closed => Ok(0),
+            // simulate the port read timeout once the replies are exhausted
+            0 => {
+                std::thread::sleep(Duration::from_millis(1));
+                Err(io::ErrorKind::TimedOut.into())
+            }
+            read => Ok(read),
+        }
+    }
+}
+
+impl Write for MockPort {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.tx.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl SetLineSettings for MockPort {
+    fn set_line_settings(&mut self, baud_rate: BaudRate, protocol: SerialProtocol) -> Result<()> {
+        self.line_settings.push((baud_rate, protocol));
+        Ok(())
+    }
+}
@@ -0,0 +1,46 @@
+use crate::std;
+use std::io::{Read, Write};
+
+use crate::{QuerySoftwareCrcCommand, Result};
+
+use super::SequencedTransport;
+
+impl<P: Read + Write> SequencedTransport<P> {
+    /// Queries the 16-bit software CRC of the firmware installed on the device.
+    pub fn query_software_crc(&mut self) -> Result<u16> {
+        let reply = self.send_message(&mut QuerySoftwareCrcCommand::new())?;
+
+        Ok(reply.as_query_software_crc_reply()?.crc())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{mock::MockPort, SerialTransport};
+    use super::*;
+    use crate::{AckNak, MessageOps, QuerySoftwareCrcReply};
+
+    #[test]
+    fn test_query_software_crc() -> Result<()> {
+        let mut rx = Vec::new();
+        for acknak in [AckNak::Ack, AckNak::Nak] {
+            let mut reply = QuerySoftwareCrcReply::new();
+            reply.set_acknak(acknak);
+            reply.set_crc(0x1234);
+            rx.extend_from_slice(reply.as_bytes());
+        }
+
+        let mut transport = SequencedTransport::new(SerialTransport::new(MockPort::new(&rx)));
+
+        assert_eq!(transport.query_software_crc()?, 0x1234);
+        assert_eq!(transport.query_software_crc()?, 0x1234);
+
+        // consecutive queries alternate the ACK/NAK toggle
+        assert_eq!(
+            transport.transport().port().sent_acknaks(),
+            [AckNak::Ack, AckNak::Nak]
+        );
+
+        Ok(())
+    }
+}
@@ -1,9 +1,15 @@
 use crate::{
-    index, inner_enum, len, std::fmt, AuxCommandOps, Control, Error, ExtendedCommandOps,
-    MessageOps, MessageType, OmnibusCommand, QueryApplicationPartNumberCommand,
-    QueryBootPartNumberCommand, QueryDeviceCapabilitiesCommand, QueryExtendedNoteSpecification,
-    QueryValueTableCommand, QueryVariantNameCommand, QueryVariantPartNumberCommand, Result,
-    SetExtendedNoteInhibitsCFSC, SetExtendedNoteInhibitsSC, SoftReset, CFSC_ENABLE_LEN,
+    index, inner_enum, len, std::fmt, AuxCommandOps, BaudRateChangeRequest, CalibrateCommand,
+    ClearCashBoxTotalCommand, Control, Error, ExtendedCommandOps, FlashDownloadMessage7bit,
+    FlashDownloadMessage8bit_32, FlashDownloadMessage8bit_64, MessageOps, MessageType,
+    OmnibusBookmarkCommand, OmnibusCommand, QueryAcceptorSerialNumberCommand,
+    QueryAcceptorTypeCommand, QueryApplicationPartNumberCommand, QueryAuditLifeTimeTotalsCommand,
+    QueryAuditPerformanceMeasuresCommand, QueryAuditQpMeasuresCommand, QueryBootPartNumberCommand,
+    QueryCashBoxTotalCommand, QueryDeviceCapabilitiesCommand, QueryDeviceResetsCommand,
+    QueryExtendedNoteSpecification, QuerySoftwareCrcCommand, QueryValueTableCommand,
+    QueryVariantNameCommand, QueryVariantPartNumberCommand, Result, SetEscrowTimeoutCommand,
+    SetExtendedNoteInhibitsCFSC, SetExtendedNoteInhibitsSC, SoftReset, StartDownloadCommand,
+    CFSC_ENABLE_LEN,
 };
 
 /// Represents variants of an EBDS command message.
@@ -11,17 +17,36 @@ use crate::{
 pub enum CommandVariant {
     // Omnibus command
     OmnibusCommand(OmnibusCommand),
+    OmnibusBookmarkCommand(OmnibusBookmarkCommand),
+    // Calibrate command
+    CalibrateCommand(CalibrateCommand),
+    // Firmware download commands
+    StartDownloadCommand(StartDownloadCommand),
+    BaudRateChangeRequest(BaudRateChangeRequest),
+    FlashDownloadMessage7bit(FlashDownloadMessage7bit),
+    FlashDownloadMessage8bit32(FlashDownloadMessage8bit_32),
+    FlashDownloadMessage8bit64(FlashDownloadMessage8bit_64),
     // Extended commands
     QueryValueTableCommand(QueryValueTableCommand),
     QueryExtendedNoteSpecification(QueryExtendedNoteSpecification),
     SetExtendedNoteInhibitsCFSC(SetExtendedNoteInhibitsCFSC),
     SetExtendedNoteInhibitsSC(SetExtendedNoteInhibitsSC),
+    SetEscrowTimeoutCommand(SetEscrowTimeoutCommand),
     // Aux commands
+    QuerySoftwareCrcCommand(QuerySoftwareCrcCommand),
+    QueryCashBoxTotalCommand(QueryCashBoxTotalCommand),
+    QueryDeviceResetsCommand(QueryDeviceResetsCommand),
+    ClearCashBoxTotalCommand(ClearCashBoxTotalCommand),
     QueryBootPartNumberCommand(QueryBootPartNumberCommand),
     QueryApplicationPartNumberCommand(QueryApplicationPartNumberCommand),
     QueryDeviceCapabilitiesCommand(QueryDeviceCapabilitiesCommand),
     QueryVariantPartNumberCommand(QueryVariantPartNumberCommand),
     QueryVariantNameCommand(QueryVariantNameCommand),
+    QueryAcceptorTypeCommand(QueryAcceptorTypeCommand),
+    QueryAcceptorSerialNumberCommand(QueryAcceptorSerialNumberCommand),
+    QueryAuditLifeTimeTotalsCommand(QueryAuditLifeTimeTotalsCommand),
+    QueryAuditQpMeasuresCommand(QueryAuditQpMeasuresCommand),
+    QueryAuditPerformanceMeasuresCommand(QueryAuditPerformanceMeasuresCommand),
     SoftReset(SoftReset),
 }
 
@@ -30,15 +55,66 @@ impl CommandVariant {
     pub fn as_message(&self) -> &dyn MessageOps {
         match self {
             Self::OmnibusCommand(cmd) => cmd,
+            Self::OmnibusBookmarkCommand(cmd) => cmd,
+            Self::CalibrateCommand(cmd) => cmd,
+            Self::StartDownloadCommand(cmd) => cmd,
+            Self::BaudRateChangeRequest(cmd) => cmd,
+            Self::FlashDownloadMessage7bit(cmd) => cmd,
+            Self::FlashDownloadMessage8bit32(cmd) => cmd,
+            Self::FlashDownloadMessage8bit64(cmd) => cmd,
             Self::QueryExtendedNoteSpecification(cmd) => cmd,
             Self::SetExtendedNoteInhibitsCFSC(cmd) => cmd,
             Self::SetExtendedNoteInhibitsSC(cmd) => cmd,
+            Self::SetEscrowTimeoutCommand(cmd) => cmd,
             Self::QueryValueTableCommand(cmd) => cmd,
+            Self::QuerySoftwareCrcCommand(cmd) => cmd,
+            Self::QueryCashBoxTotalCommand(cmd) => cmd,
+            Self::QueryDeviceResetsCommand(cmd) => cmd,
+            Self::ClearCashBoxTotalCommand(cmd) => cmd,
             Self::QueryBootPartNumberCommand(cmd) => cmd,
             Self::QueryApplicationPartNumberCommand(cmd) => cmd,
             Self::QueryDeviceCapabilitiesCommand(cmd) => cmd,
             Self::QueryVariantPartNumberCommand(cmd) => cmd,
             Self::QueryVariantNameCommand(cmd) => cmd,
+            Self::QueryAcceptorTypeCommand(cmd) => cmd,
+            Self::QueryAcceptorSerialNumberCommand(cmd) => cmd,
+            Self::QueryAuditLifeTimeTotalsCommand(cmd) => cmd,
+            Self::QueryAuditQpMeasuresCommand(cmd) => cmd,
+            Self::QueryAuditPerformanceMeasuresCommand(cmd) => cmd,
+            Self::SoftReset(cmd) => cmd,
+        }
+    }
+
+    /// Gets the [CommandVariant] as a mutable generic [MessageOps] trait object.
+    pub fn as_message_mut(&mut self) -> &mut dyn MessageOps {
+        match self {
+            Self::OmnibusCommand(cmd) => cmd,
+            Self::OmnibusBookmarkCommand(cmd) => cmd,
+            Self::CalibrateCommand(cmd) => cmd,
+            Self::StartDownloadCommand(cmd) => cmd,
+            Self::BaudRateChangeRequest(cmd) => cmd,
+            Self::FlashDownloadMessage7bit(cmd) => cmd,
+            Self::FlashDownloadMessage8bit32(cmd) => cmd,
+            Self::FlashDownloadMessage8bit64(cmd) => cmd,
+            Self::QueryExtendedNoteSpecification(cmd) => cmd,
+            Self::SetExtendedNoteInhibitsCFSC(cmd) => cmd,
+            Self::SetExtendedNoteInhibitsSC(cmd) => cmd,
+            Self::SetEscrowTimeoutCommand(cmd) => cmd,
+            Self::QueryValueTableCommand(cmd) => cmd,
+            Self::QuerySoftwareCrcCommand(cmd) => cmd,
+            Self::QueryCashBoxTotalCommand(cmd) => cmd,
+            Self::QueryDeviceResetsCommand(cmd) => cmd,
+            Self::ClearCashBoxTotalCommand(cmd) => cmd,
+            Self::QueryBootPartNumberCommand(cmd) => cmd,
+            Self::QueryApplicationPartNumberCommand(cmd) => cmd,
+            Self::QueryDeviceCapabilitiesCommand(cmd) => cmd,
+            Self::QueryVariantPartNumberCommand(cmd) => cmd,
+            Self::QueryVariantNameCommand(cmd) => cmd,
+            Self::QueryAcceptorTypeCommand(cmd) => cmd,
+            Self::QueryAcceptorSerialNumberCommand(cmd) => cmd,
+            Self::QueryAuditLifeTimeTotalsCommand(cmd) => cmd,
+            Self::QueryAuditQpMeasuresCommand(cmd) => cmd,
+            Self::QueryAuditPerformanceMeasuresCommand(cmd) => cmd,
             Self::SoftReset(cmd) => cmd,
         }
     }
@@ -46,11 +122,20 @@ impl CommandVariant {
     /// Gets the [CommandVariant] as a generic [AuxCommandOps] trait object.
     pub fn as_aux_command(&self) -> Result<&dyn AuxCommandOps> {
         match self {
+            Self::QuerySoftwareCrcCommand(cmd) => Ok(cmd),
+            Self::QueryCashBoxTotalCommand(cmd) => Ok(cmd),
+            Self::QueryDeviceResetsCommand(cmd) => Ok(cmd),
+            Self::ClearCashBoxTotalCommand(cmd) => Ok(cmd),
             Self::QueryBootPartNumberCommand(cmd) => Ok(cmd),
             Self::QueryApplicationPartNumberCommand(cmd) => Ok(cmd),
             Self::QueryDeviceCapabilitiesCommand(cmd) => Ok(cmd),
             Self::QueryVariantPartNumberCommand(cmd) => Ok(cmd),
             Self::QueryVariantNameCommand(cmd) => Ok(cmd),
+            Self::QueryAcceptorTypeCommand(cmd) => Ok(cmd),
+            Self::QueryAcceptorSerialNumberCommand(cmd) => Ok(cmd),
+            Self::QueryAuditLifeTimeTotalsCommand(cmd) => Ok(cmd),
+            Self::QueryAuditQpMeasuresCommand(cmd) => Ok(cmd),
+            Self::QueryAuditPerformanceMeasuresCommand(cmd) => Ok(cmd),
             Self::SoftReset(cmd) => Ok(cmd),
             _ => Err(Error::failure(format!(
                 "invalid variant, expected AuxCommand, have: {self}"
@@ -65,6 +150,7 @@ impl CommandVariant {
             Self::QueryExtendedNoteSpecification(cmd) => Ok(cmd),
             Self::SetExtendedNoteInhibitsCFSC(cmd) => Ok(cmd),
             Self::SetExtendedNoteInhibitsSC(cmd) => Ok(cmd),
+            Self::SetEscrowTimeoutCommand(cmd) => Ok(cmd),
             _ => Err(Error::failure(format!(
                 "invalid variant, expected ExtendedCommand, have: {self}"
             ))),
@@ -73,14 +159,39 @@ impl CommandVariant {
 }
 
 inner_enum!(CommandVariant, OmnibusCommand);
+inner_enum!(CommandVariant, OmnibusBookmarkCommand);
+inner_enum!(CommandVariant, CalibrateCommand);
+inner_enum!(CommandVariant, StartDownloadCommand);
+inner_enum!(CommandVariant, BaudRateChangeRequest);
+inner_enum!(CommandVariant, FlashDownloadMessage7bit);
+inner_enum!(
+    CommandVariant,
+    FlashDownloadMessage8bit32,
+    FlashDownloadMessage8bit_32
+);
+inner_enum!(
+    CommandVariant,
+    FlashDownloadMessage8bit64,
+    FlashDownloadMessage8bit_64
+);
 inner_enum!(CommandVariant, QueryValueTableCommand);
 inner_enum!(CommandVariant, QueryExtendedNoteSpecification);
 inner_enum!(CommandVariant, SetExtendedNoteInhibitsCFSC);
 inner_enum!(CommandVariant, SetExtendedNoteInhibitsSC);
+inner_enum!(CommandVariant, SetEscrowTimeoutCommand);
+inner_enum!(CommandVariant, QuerySoftwareCrcCommand);
+inner_enum!(CommandVariant, QueryCashBoxTotalCommand);
+inner_enum!(CommandVariant, QueryDeviceResetsCommand);
+inner_enum!(CommandVariant, ClearCashBoxTotalCommand);
 inner_enum!(CommandVariant, QueryBootPartNumberCommand);
 inner_enum!(CommandVariant, QueryApplicationPartNumberCommand);
 inner_enum!(CommandVariant, QueryVariantPartNumberCommand);
 inner_enum!(CommandVariant, QueryVariantNameCommand);
+inner_enum!(CommandVariant, QueryAcceptorTypeCommand);
+inner_enum!(CommandVariant, QueryAcceptorSerialNumberCommand);
+inner_enum!(CommandVariant, QueryAuditLifeTimeTotalsCommand);
+inner_enum!(CommandVariant, QueryAuditQpMeasuresCommand);
+inner_enum!(CommandVariant, QueryAuditPerformanceMeasuresCommand);
 inner_enum!(CommandVariant, SoftReset);
 
 impl TryFrom<&[u8]> for CommandVariant {
@@ -104,6 +215,46 @@ impl TryFrom<&[u8]> for CommandVariant {
                     cmd.from_buf(val)?;
                     Ok(Self::OmnibusCommand(cmd))
                 }
+                MessageType::OmnibusBookmark => {
+                    let mut cmd = OmnibusBookmarkCommand::new();
+                    cmd.from_buf(val)?;
+                    Ok(Self::OmnibusBookmarkCommand(cmd))
+                }
+                MessageType::Calibrate => {
+                    let mut cmd = CalibrateCommand::new();
+                    cmd.from_buf(val)?;
+                    Ok(Self::CalibrateCommand(cmd))
+                }
+                MessageType::FirmwareDownload => match len {
+                    len::START_DOWNLOAD_COMMAND => {
+                        let mut cmd = StartDownloadCommand::new();
+                        cmd.from_buf(val)?;
+                        Ok(Self::StartDownloadCommand(cmd))
+                    }
+                    len::BAUD_CHANGE_REQUEST => {
+                        let mut cmd = BaudRateChangeRequest::new();
+                        cmd.from_buf(val)?;
+                        Ok(Self::BaudRateChangeRequest(cmd))
+                    }
+                    len::FLASH_DOWNLOAD_MESSAGE_7BIT => {
+                        let mut cmd = FlashDownloadMessage7bit::new();
+                        cmd.from_buf(val)?;
+                        Ok(Self::FlashDownloadMessage7bit(cmd))
+                    }
+                    len::FLASH_DOWNLOAD_MESSAGE_8BIT_32 => {
+                        let mut cmd = FlashDownloadMessage8bit_32::new();
+                        cmd.from_buf(val)?;
+                        Ok(Self::FlashDownloadMessage8bit32(cmd))
+                    }
+                    len::FLASH_DOWNLOAD_MESSAGE_8BIT_64 => {
+                        let mut cmd = FlashDownloadMessage8bit_64::new();
+                        cmd.from_buf(val)?;
+                        Ok(Self::FlashDownloadMessage8bit64(cmd))
+                    }
+                    _ => Err(Error::failure(format!(
+                        "invalid FirmwareDownload command length: {len}"
+                    ))),
+                },
                 MessageType::AuxCommand => {
                     use crate::aux_command::{index as aux_index, AuxCommand};
 
@@ -118,6 +269,26 @@ impl TryFrom<&[u8]> for CommandVariant {
                         let aux_type = AuxCommand::from(raw_aux_type);
 
                         match aux_type {
+                            AuxCommand::QuerySoftwareCrc => {
+                                let mut cmd = QuerySoftwareCrcCommand::new();
+                                cmd.from_buf(val)?;
+                                Ok(Self::QuerySoftwareCrcCommand(cmd))
+                            }
+                            AuxCommand::QueryCashBoxTotal => {
+                                let mut cmd = QueryCashBoxTotalCommand::new();
+                                cmd.from_buf(val)?;
+                                Ok(Self::QueryCashBoxTotalCommand(cmd))
+                            }
+                            AuxCommand::QueryDeviceResets => {
+                                let mut cmd = QueryDeviceResetsCommand::new();
+                                cmd.from_buf(val)?;
+                                Ok(Self::QueryDeviceResetsCommand(cmd))
+                            }
+                            AuxCommand::ClearCashBoxTotal => {
+                                let mut cmd = ClearCashBoxTotalCommand::new();
+                                cmd.from_buf(val)?;
+                                Ok(Self::ClearCashBoxTotalCommand(cmd))
+                            }
                             AuxCommand::QueryBootPartNumber => {
                                 let mut cmd = QueryBootPartNumberCommand::new();
                                 cmd.from_buf(val)?;
@@ -143,6 +314,31 @@ impl TryFrom<&[u8]> for CommandVariant {
                                 cmd.from_buf(val)?;
                                 Ok(Self::QueryDeviceCapabilitiesCommand(cmd))
                             }
+                            AuxCommand::QueryAcceptorType => {
+                                let mut cmd = QueryAcceptorTypeCommand::new();
+                                cmd.from_buf(val)?;
+                                Ok(Self::QueryAcceptorTypeCommand(cmd))
+                            }
+                            AuxCommand::QueryAcceptorSerialNumber => {
+                                let mut cmd = QueryAcceptorSerialNumberCommand::new();
+                                cmd.from_buf(val)?;
+                                Ok(Self::QueryAcceptorSerialNumberCommand(cmd))
+                            }
+                            AuxCommand::QueryAuditLifeTimeTotals => {
+                                let mut cmd = QueryAuditLifeTimeTotalsCommand::new();
+                                cmd.from_buf(val)?;
+                                Ok(Self::QueryAuditLifeTimeTotalsCommand(cmd))
+                            }
+                            AuxCommand::QueryAuditQpMeasures => {
+                                let mut cmd = QueryAuditQpMeasuresCommand::new();
+                                cmd.from_buf(val)?;
+                                Ok(Self::QueryAuditQpMeasuresCommand(cmd))
+                            }
+                            AuxCommand::QueryAuditPerformanceMeasures => {
+                                let mut cmd = QueryAuditPerformanceMeasuresCommand::new();
+                                cmd.from_buf(val)?;
+                                Ok(Self::QueryAuditPerformanceMeasuresCommand(cmd))
+                            }
                             AuxCommand::SoftReset => {
                                 let mut cmd = SoftReset::new();
                                 cmd.from_buf(val)?;
@@ -189,6 +385,11 @@ impl TryFrom<&[u8]> for CommandVariant {
                                     Ok(Self::SetExtendedNoteInhibitsSC(cmd))
                                 }
                             }
+                            ExtendedCommand::SetEscrowTimeout => {
+                                let mut cmd = SetEscrowTimeoutCommand::new();
+                                cmd.from_buf(val)?;
+                                Ok(Self::SetEscrowTimeoutCommand(cmd))
+                            }
                             _ => Err(Error::failure(format!("invalid ExtendedCommand message type: {ext_type}, raw: {raw_ext_type}"))),
                         }
                     }
@@ -205,6 +406,25 @@ impl fmt::Display for CommandVariant {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             CommandVariant::OmnibusCommand(cmd) => write!(f, "OmnibusCommand({cmd})"),
+            CommandVariant::OmnibusBookmarkCommand(cmd) => {
+                write!(f, "OmnibusBookmarkCommand({cmd})")
+            }
+            CommandVariant::CalibrateCommand(cmd) => write!(f, "CalibrateCommand({cmd})"),
+            CommandVariant::StartDownloadCommand(cmd) => {
+                write!(f, "StartDownloadCommand({cmd})")
+            }
+            CommandVariant::BaudRateChangeRequest(cmd) => {
+                write!(f, "BaudRateChangeRequest({cmd})")
+            }
+            CommandVariant::FlashDownloadMessage7bit(cmd) => {
+                write!(f, "FlashDownloadMessage7bit({cmd})")
+            }
+            CommandVariant::FlashDownloadMessage8bit32(cmd) => {
+                write!(f, "FlashDownloadMessage8bit32({cmd})")
+            }
+            CommandVariant::FlashDownloadMessage8bit64(cmd) => {
+                write!(f, "FlashDownloadMessage8bit64({cmd})")
+            }
             CommandVariant::QueryValueTableCommand(cmd) => {
                 write!(f, "QueryValueTableCommand({cmd})")
             }
@@ -217,6 +437,21 @@ impl fmt::Display for CommandVariant {
             CommandVariant::SetExtendedNoteInhibitsSC(cmd) => {
                 write!(f, "SetExtendedNoteInhibitsSC({cmd})")
             }
+            CommandVariant::SetEscrowTimeoutCommand(cmd) => {
+                write!(f, "SetEscrowTimeoutCommand({cmd})")
+            }
+            CommandVariant::QuerySoftwareCrcCommand(cmd) => {
+                write!(f, "QuerySoftwareCrcCommand({cmd})")
+            }
+            CommandVariant::QueryCashBoxTotalCommand(cmd) => {
+                write!(f, "QueryCashBoxTotalCommand({cmd})")
+            }
+            CommandVariant::QueryDeviceResetsCommand(cmd) => {
+                write!(f, "QueryDeviceResetsCommand({cmd})")
+            }
+            CommandVariant::ClearCashBoxTotalCommand(cmd) => {
+                write!(f, "ClearCashBoxTotalCommand({cmd})")
+            }
             CommandVariant::QueryBootPartNumberCommand(cmd) => {
                 write!(f, "QueryBootPartNumberCommand({cmd})")
             }
@@ -232,6 +467,21 @@ impl fmt::Display for CommandVariant {
             CommandVariant::QueryDeviceCapabilitiesCommand(cmd) => {
                 write!(f, "QueryDeviceCapabilitiesCommand({cmd})")
             }
+            CommandVariant::QueryAcceptorTypeCommand(cmd) => {
+                write!(f, "QueryAcceptorTypeCommand({cmd})")
+            }
+            CommandVariant::QueryAcceptorSerialNumberCommand(cmd) => {
+                write!(f, "QueryAcceptorSerialNumberCommand({cmd})")
+            }
+            CommandVariant::QueryAuditLifeTimeTotalsCommand(cmd) => {
+                write!(f, "QueryAuditLifeTimeTotalsCommand({cmd})")
+            }
+            CommandVariant::QueryAuditQpMeasuresCommand(cmd) => {
+                write!(f, "QueryAuditQpMeasuresCommand({cmd})")
+            }
+            CommandVariant::QueryAuditPerformanceMeasuresCommand(cmd) => {
+                write!(f, "QueryAuditPerformanceMeasuresCommand({cmd})")
+            }
             CommandVariant::SoftReset(cmd) => {
                 write!(f, "SoftReset({cmd})")
             }
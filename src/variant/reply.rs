@@ -1,10 +1,14 @@
 use crate::{
     index, inner_enum, len, std::fmt, AdvancedBookmarkModeReply, AuxCommand, Banknote,
-    BaudRateChangeReply, ClearAuditDataRequestAck, ClearAuditDataRequestResults, Control,
-    DocumentStatus, Error, ExtendedCommand, ExtendedNoteInhibitsReplyAlt, ExtendedNoteReply,
-    FlashDownloadReply, FlashDownloadReply7bit, FlashDownloadReply8bit, MessageOps, MessageType,
-    NoteRetrievedEvent, NoteRetrievedReply, OmnibusReply, OmnibusReplyOps, QueryApplicationIdReply,
-    QueryApplicationPartNumberReply, QueryBootPartNumberReply, QueryDeviceCapabilitiesReply,
+    BaudRateChangeReply, CalibrateReply, ClearAuditDataRequestAck, ClearAuditDataRequestResults,
+    ClearCashBoxTotalReply, Control, DocumentStatus, Error, ExtendedBarcodeReply, ExtendedCommand,
+    ExtendedCouponReply, ExtendedNoteInhibitsReplyAlt, ExtendedNoteReply, FlashDownloadReply,
+    FlashDownloadReply7bit, FlashDownloadReply8bit, MessageOps, MessageType, NoteRetrievedEvent,
+    NoteRetrievedReply, OmnibusBookmarkReply, OmnibusReply, OmnibusReplyOps,
+    QueryAcceptorSerialNumberReply, QueryAcceptorTypeReply, QueryApplicationIdReply,
+    QueryApplicationPartNumberReply, QueryAuditLifeTimeTotalsReply,
+    QueryAuditPerformanceMeasuresReply, QueryAuditQpMeasuresReply, QueryBootPartNumberReply,
+    QueryCashBoxTotalReply, QueryDeviceCapabilitiesReply, QueryDeviceResetsReply,
     QuerySoftwareCrcReply, QueryValueTableReply, QueryVariantIdReply, QueryVariantNameReply,
     QueryVariantPartNumberReply, Result, SetEscrowTimeoutReply, StartDownloadReply,
 };
@@ -14,8 +18,13 @@ use crate::{
 pub enum ReplyVariant {
     // Omnibus reply
     OmnibusReply(OmnibusReply),
+    OmnibusBookmarkReply(OmnibusBookmarkReply),
+    // Calibrate reply
+    CalibrateReply(CalibrateReply),
     // Extended replies
     AdvancedBookmarkModeReply(AdvancedBookmarkModeReply),
+    ExtendedBarcodeReply(ExtendedBarcodeReply),
+    ExtendedCouponReply(ExtendedCouponReply),
     ClearAuditDataRequestAck(ClearAuditDataRequestAck),
     ClearAuditDataRequestResults(ClearAuditDataRequestResults),
     ExtendedNoteReply(ExtendedNoteReply),
@@ -26,6 +35,9 @@ pub enum ReplyVariant {
     SetEscrowTimeoutReply(SetEscrowTimeoutReply),
     // Aux replies
     QuerySoftwareCrcReply(QuerySoftwareCrcReply),
+    QueryCashBoxTotalReply(QueryCashBoxTotalReply),
+    QueryDeviceResetsReply(QueryDeviceResetsReply),
+    ClearCashBoxTotalReply(ClearCashBoxTotalReply),
     QueryBootPartNumberReply(QueryBootPartNumberReply),
     QueryApplicationPartNumberReply(QueryApplicationPartNumberReply),
     QueryVariantNameReply(QueryVariantNameReply),
@@ -33,6 +45,11 @@ pub enum ReplyVariant {
     QueryDeviceCapabilitiesReply(QueryDeviceCapabilitiesReply),
     QueryApplicationIdReply(QueryApplicationIdReply),
     QueryVariantIdReply(QueryVariantIdReply),
+    QueryAcceptorTypeReply(QueryAcceptorTypeReply),
+    QueryAcceptorSerialNumberReply(QueryAcceptorSerialNumberReply),
+    QueryAuditLifeTimeTotalsReply(QueryAuditLifeTimeTotalsReply),
+    QueryAuditQpMeasuresReply(QueryAuditQpMeasuresReply),
+    QueryAuditPerformanceMeasuresReply(QueryAuditPerformanceMeasuresReply),
     // Flash download replies
     BaudRateChangeReply(BaudRateChangeReply),
     FlashDownloadReply7bit(FlashDownloadReply7bit),
@@ -40,7 +57,11 @@ pub enum ReplyVariant {
     StartDownloadReply(StartDownloadReply),
 }
 
+inner_enum!(ReplyVariant, OmnibusBookmarkReply);
+inner_enum!(ReplyVariant, CalibrateReply);
 inner_enum!(ReplyVariant, AdvancedBookmarkModeReply);
+inner_enum!(ReplyVariant, ExtendedBarcodeReply);
+inner_enum!(ReplyVariant, ExtendedCouponReply);
 inner_enum!(ReplyVariant, ClearAuditDataRequestAck);
 inner_enum!(ReplyVariant, ClearAuditDataRequestResults);
 inner_enum!(ReplyVariant, ExtendedNoteReply);
@@ -50,6 +71,9 @@ inner_enum!(ReplyVariant, NoteRetrievedEvent);
 inner_enum!(ReplyVariant, QueryValueTableReply);
 inner_enum!(ReplyVariant, SetEscrowTimeoutReply);
 inner_enum!(ReplyVariant, QuerySoftwareCrcReply);
+inner_enum!(ReplyVariant, QueryCashBoxTotalReply);
+inner_enum!(ReplyVariant, QueryDeviceResetsReply);
+inner_enum!(ReplyVariant, ClearCashBoxTotalReply);
 inner_enum!(ReplyVariant, QueryBootPartNumberReply);
 inner_enum!(ReplyVariant, QueryApplicationPartNumberReply);
 inner_enum!(ReplyVariant, QueryVariantNameReply);
@@ -57,6 +81,11 @@ inner_enum!(ReplyVariant, QueryVariantPartNumberReply);
 inner_enum!(ReplyVariant, QueryApplicationIdReply);
 inner_enum!(ReplyVariant, QueryVariantIdReply);
 inner_enum!(ReplyVariant, QueryDeviceCapabilitiesReply);
+inner_enum!(ReplyVariant, QueryAcceptorTypeReply);
+inner_enum!(ReplyVariant, QueryAcceptorSerialNumberReply);
+inner_enum!(ReplyVariant, QueryAuditLifeTimeTotalsReply);
+inner_enum!(ReplyVariant, QueryAuditQpMeasuresReply);
+inner_enum!(ReplyVariant, QueryAuditPerformanceMeasuresReply);
 inner_enum!(ReplyVariant, BaudRateChangeReply);
 inner_enum!(ReplyVariant, FlashDownloadReply7bit);
 inner_enum!(ReplyVariant, FlashDownloadReply8bit);
@@ -72,6 +101,8 @@ impl ReplyVariant {
     pub fn as_message(&self) -> &dyn MessageOps {
         match self {
             Self::AdvancedBookmarkModeReply(msg) => msg,
+            Self::ExtendedBarcodeReply(msg) => msg,
+            Self::ExtendedCouponReply(msg) => msg,
             Self::ClearAuditDataRequestAck(msg) => msg,
             Self::ClearAuditDataRequestResults(msg) => msg,
             Self::ExtendedNoteReply(msg) => msg,
@@ -79,9 +110,14 @@ impl ReplyVariant {
             Self::NoteRetrievedReply(msg) => msg,
             Self::NoteRetrievedEvent(msg) => msg,
             Self::OmnibusReply(msg) => msg,
+            Self::OmnibusBookmarkReply(msg) => msg,
+            Self::CalibrateReply(msg) => msg,
             Self::QueryValueTableReply(msg) => msg,
             Self::SetEscrowTimeoutReply(msg) => msg,
             Self::QuerySoftwareCrcReply(msg) => msg,
+            Self::QueryCashBoxTotalReply(msg) => msg,
+            Self::QueryDeviceResetsReply(msg) => msg,
+            Self::ClearCashBoxTotalReply(msg) => msg,
             Self::QueryBootPartNumberReply(msg) => msg,
             Self::QueryApplicationPartNumberReply(msg) => msg,
             Self::QueryVariantNameReply(msg) => msg,
@@ -89,6 +125,11 @@ impl ReplyVariant {
             Self::QueryDeviceCapabilitiesReply(msg) => msg,
             Self::QueryApplicationIdReply(msg) => msg,
             Self::QueryVariantIdReply(msg) => msg,
+            Self::QueryAcceptorTypeReply(msg) => msg,
+            Self::QueryAcceptorSerialNumberReply(msg) => msg,
+            Self::QueryAuditLifeTimeTotalsReply(msg) => msg,
+            Self::QueryAuditQpMeasuresReply(msg) => msg,
+            Self::QueryAuditPerformanceMeasuresReply(msg) => msg,
             Self::BaudRateChangeReply(msg) => msg,
             Self::FlashDownloadReply7bit(msg) => msg,
             Self::FlashDownloadReply8bit(msg) => msg,
@@ -100,6 +141,8 @@ impl ReplyVariant {
     pub fn as_message_mut(&mut self) -> &mut dyn MessageOps {
         match self {
             Self::AdvancedBookmarkModeReply(msg) => msg,
+            Self::ExtendedBarcodeReply(msg) => msg,
+            Self::ExtendedCouponReply(msg) => msg,
             Self::ClearAuditDataRequestAck(msg) => msg,
             Self::ClearAuditDataRequestResults(msg) => msg,
             Self::ExtendedNoteReply(msg) => msg,
@@ -107,9 +150,14 @@ impl ReplyVariant {
             Self::NoteRetrievedReply(msg) => msg,
             Self::NoteRetrievedEvent(msg) => msg,
             Self::OmnibusReply(msg) => msg,
+            Self::OmnibusBookmarkReply(msg) => msg,
+            Self::CalibrateReply(msg) => msg,
             Self::QueryValueTableReply(msg) => msg,
             Self::SetEscrowTimeoutReply(msg) => msg,
             Self::QuerySoftwareCrcReply(msg) => msg,
+            Self::QueryCashBoxTotalReply(msg) => msg,
+            Self::QueryDeviceResetsReply(msg) => msg,
+            Self::ClearCashBoxTotalReply(msg) => msg,
             Self::QueryBootPartNumberReply(msg) => msg,
             Self::QueryApplicationPartNumberReply(msg) => msg,
             Self::QueryVariantNameReply(msg) => msg,
@@ -117,6 +165,11 @@ impl ReplyVariant {
             Self::QueryDeviceCapabilitiesReply(msg) => msg,
             Self::QueryApplicationIdReply(msg) => msg,
             Self::QueryVariantIdReply(msg) => msg,
+            Self::QueryAcceptorTypeReply(msg) => msg,
+            Self::QueryAcceptorSerialNumberReply(msg) => msg,
+            Self::QueryAuditLifeTimeTotalsReply(msg) => msg,
+            Self::QueryAuditQpMeasuresReply(msg) => msg,
+            Self::QueryAuditPerformanceMeasuresReply(msg) => msg,
             Self::BaudRateChangeReply(msg) => msg,
             Self::FlashDownloadReply7bit(msg) => msg,
             Self::FlashDownloadReply8bit(msg) => msg,
@@ -128,6 +181,8 @@ impl ReplyVariant {
     pub fn as_omnibus_reply(&self) -> &dyn OmnibusReplyOps {
         match self {
             Self::AdvancedBookmarkModeReply(msg) => msg,
+            Self::ExtendedBarcodeReply(msg) => msg,
+            Self::ExtendedCouponReply(msg) => msg,
             Self::ClearAuditDataRequestAck(msg) => msg,
             Self::ClearAuditDataRequestResults(msg) => msg,
             Self::ExtendedNoteReply(msg) => msg,
@@ -135,9 +190,14 @@ impl ReplyVariant {
             Self::NoteRetrievedReply(msg) => msg,
             Self::NoteRetrievedEvent(msg) => msg,
             Self::OmnibusReply(msg) => msg,
+            Self::OmnibusBookmarkReply(msg) => msg,
+            Self::CalibrateReply(msg) => msg,
             Self::QueryValueTableReply(msg) => msg,
             Self::SetEscrowTimeoutReply(msg) => msg,
             Self::QuerySoftwareCrcReply(msg) => msg,
+            Self::QueryCashBoxTotalReply(msg) => msg,
+            Self::QueryDeviceResetsReply(msg) => msg,
+            Self::ClearCashBoxTotalReply(msg) => msg,
             Self::QueryBootPartNumberReply(msg) => msg,
             Self::QueryApplicationPartNumberReply(msg) => msg,
             Self::QueryVariantNameReply(msg) => msg,
@@ -145,6 +205,11 @@ impl ReplyVariant {
             Self::QueryDeviceCapabilitiesReply(msg) => msg,
             Self::QueryApplicationIdReply(msg) => msg,
             Self::QueryVariantIdReply(msg) => msg,
+            Self::QueryAcceptorTypeReply(msg) => msg,
+            Self::QueryAcceptorSerialNumberReply(msg) => msg,
+            Self::QueryAuditLifeTimeTotalsReply(msg) => msg,
+            Self::QueryAuditQpMeasuresReply(msg) => msg,
+            Self::QueryAuditPerformanceMeasuresReply(msg) => msg,
             Self::BaudRateChangeReply(msg) => msg,
             Self::FlashDownloadReply7bit(msg) => msg,
             Self::FlashDownloadReply8bit(msg) => msg,
@@ -156,6 +221,8 @@ impl ReplyVariant {
     pub fn as_omnibus_reply_mut(&mut self) -> &mut dyn OmnibusReplyOps {
         match self {
             Self::AdvancedBookmarkModeReply(msg) => msg,
+            Self::ExtendedBarcodeReply(msg) => msg,
+            Self::ExtendedCouponReply(msg) => msg,
             Self::ClearAuditDataRequestAck(msg) => msg,
             Self::ClearAuditDataRequestResults(msg) => msg,
             Self::ExtendedNoteReply(msg) => msg,
@@ -163,9 +230,14 @@ impl ReplyVariant {
             Self::NoteRetrievedReply(msg) => msg,
             Self::NoteRetrievedEvent(msg) => msg,
             Self::OmnibusReply(msg) => msg,
+            Self::OmnibusBookmarkReply(msg) => msg,
+            Self::CalibrateReply(msg) => msg,
             Self::QueryValueTableReply(msg) => msg,
             Self::SetEscrowTimeoutReply(msg) => msg,
             Self::QuerySoftwareCrcReply(msg) => msg,
+            Self::QueryCashBoxTotalReply(msg) => msg,
+            Self::QueryDeviceResetsReply(msg) => msg,
+            Self::ClearCashBoxTotalReply(msg) => msg,
             Self::QueryBootPartNumberReply(msg) => msg,
             Self::QueryApplicationPartNumberReply(msg) => msg,
             Self::QueryVariantNameReply(msg) => msg,
@@ -173,6 +245,11 @@ impl ReplyVariant {
             Self::QueryDeviceCapabilitiesReply(msg) => msg,
             Self::QueryApplicationIdReply(msg) => msg,
             Self::QueryVariantIdReply(msg) => msg,
+            Self::QueryAcceptorTypeReply(msg) => msg,
+            Self::QueryAcceptorSerialNumberReply(msg) => msg,
+            Self::QueryAuditLifeTimeTotalsReply(msg) => msg,
+            Self::QueryAuditQpMeasuresReply(msg) => msg,
+            Self::QueryAuditPerformanceMeasuresReply(msg) => msg,
             Self::BaudRateChangeReply(msg) => msg,
             Self::FlashDownloadReply7bit(msg) => msg,
             Self::FlashDownloadReply8bit(msg) => msg,
@@ -183,6 +260,8 @@ impl ReplyVariant {
     pub fn into_omnibus_reply(self) -> OmnibusReply {
         match self {
             Self::AdvancedBookmarkModeReply(msg) => msg.into(),
+            Self::ExtendedBarcodeReply(msg) => msg.into(),
+            Self::ExtendedCouponReply(msg) => msg.into(),
             Self::ClearAuditDataRequestAck(msg) => msg.into(),
             Self::ClearAuditDataRequestResults(msg) => msg.into(),
             Self::ExtendedNoteReply(msg) => msg.into(),
@@ -190,8 +269,13 @@ impl ReplyVariant {
             Self::NoteRetrievedReply(msg) => msg.into(),
             Self::NoteRetrievedEvent(msg) => msg.into(),
             Self::OmnibusReply(msg) => msg,
+            Self::OmnibusBookmarkReply(msg) => msg.into(),
+            Self::CalibrateReply(msg) => msg.into(),
             Self::QueryValueTableReply(msg) => msg.into(),
             Self::SetEscrowTimeoutReply(msg) => msg.into(),
+            Self::QueryCashBoxTotalReply(msg) => msg.into(),
+            Self::QueryDeviceResetsReply(msg) => msg.into(),
+            Self::ClearCashBoxTotalReply(msg) => msg.into(),
             Self::QueryBootPartNumberReply(msg) => msg.into(),
             Self::QueryApplicationPartNumberReply(msg) => msg.into(),
             Self::QueryVariantNameReply(msg) => msg.into(),
@@ -199,6 +283,11 @@ impl ReplyVariant {
             Self::QueryDeviceCapabilitiesReply(msg) => msg.into(),
             Self::QueryApplicationIdReply(msg) => msg.into(),
             Self::QueryVariantIdReply(msg) => msg.into(),
+            Self::QueryAcceptorTypeReply(msg) => msg.into(),
+            Self::QueryAcceptorSerialNumberReply(msg) => msg.into(),
+            Self::QueryAuditLifeTimeTotalsReply(msg) => msg.into(),
+            Self::QueryAuditQpMeasuresReply(msg) => msg.into(),
+            Self::QueryAuditPerformanceMeasuresReply(msg) => msg.into(),
             _ => OmnibusReply::new(),
         }
     }
@@ -260,10 +349,11 @@ impl ReplyVariant {
 
     /// Converts the [ReplyVariant] into a [DocumentStatus].
     pub fn document_status(&self) -> DocumentStatus {
-        if let Ok(extended_note) = self.as_extended_note_reply() {
-            extended_note.into()
-        } else {
-            self.as_omnibus_reply().into()
+        match self {
+            Self::ExtendedNoteReply(msg) => msg.into(),
+            Self::ExtendedBarcodeReply(msg) => msg.into(),
+            Self::ExtendedCouponReply(msg) => msg.into(),
+            _ => self.as_omnibus_reply().into(),
         }
     }
 
@@ -295,6 +385,21 @@ impl ReplyVariant {
                 msg.from_buf(buf)?;
                 Ok(Self::QuerySoftwareCrcReply(msg))
             }
+            AuxCommand::QueryCashBoxTotal => {
+                let mut msg = QueryCashBoxTotalReply::new();
+                msg.from_buf(buf)?;
+                Ok(Self::QueryCashBoxTotalReply(msg))
+            }
+            AuxCommand::QueryDeviceResets => {
+                let mut msg = QueryDeviceResetsReply::new();
+                msg.from_buf(buf)?;
+                Ok(Self::QueryDeviceResetsReply(msg))
+            }
+            AuxCommand::ClearCashBoxTotal => {
+                let mut msg = ClearCashBoxTotalReply::new();
+                msg.from_buf(buf)?;
+                Ok(Self::ClearCashBoxTotalReply(msg))
+            }
             AuxCommand::QueryBootPartNumber => {
                 let mut msg = QueryBootPartNumberReply::new();
                 msg.from_buf(buf)?;
@@ -330,10 +435,50 @@ impl ReplyVariant {
                 msg.from_buf(buf)?;
                 Ok(Self::QueryVariantIdReply(msg))
             }
+            AuxCommand::QueryAcceptorType => {
+                let mut msg = QueryAcceptorTypeReply::new();
+                msg.from_buf(buf)?;
+                Ok(Self::QueryAcceptorTypeReply(msg))
+            }
+            AuxCommand::QueryAcceptorSerialNumber => {
+                let mut msg = QueryAcceptorSerialNumberReply::new();
+                msg.from_buf(buf)?;
+                Ok(Self::QueryAcceptorSerialNumberReply(msg))
+            }
+            AuxCommand::QueryAuditLifeTimeTotals => {
+                let mut msg = QueryAuditLifeTimeTotalsReply::new();
+                msg.from_buf(buf)?;
+                Ok(Self::QueryAuditLifeTimeTotalsReply(msg))
+            }
+            AuxCommand::QueryAuditQpMeasures => {
+                let mut msg = QueryAuditQpMeasuresReply::new();
+                msg.from_buf(buf)?;
+                Ok(Self::QueryAuditQpMeasuresReply(msg))
+            }
+            AuxCommand::QueryAuditPerformanceMeasures => {
+                let mut msg = QueryAuditPerformanceMeasuresReply::new();
+                msg.from_buf(buf)?;
+                Ok(Self::QueryAuditPerformanceMeasuresReply(msg))
+            }
             _ => Err(Error::failure("invalid AuxCommand reply type")),
         }
     }
 
+    /// Parses a reply to the provided command message from the buffer.
+    ///
+    /// Auxilliary replies are parsed using the [AuxCommand] of the command message,
+    /// see [from_aux_buf](Self::from_aux_buf). All other replies are parsed with
+    /// [from_buf](Self::from_buf).
+    pub fn from_command_buf(buf: &[u8], command: &dyn MessageOps) -> Result<Self> {
+        use crate::aux_command::index as aux_index;
+
+        if command.message_type() == MessageType::AuxCommand {
+            Self::from_aux_buf(buf, AuxCommand::from(command.buf()[aux_index::COMMAND]))
+        } else {
+            Self::from_buf(buf)
+        }
+    }
+
     /// Contructs a [ReplyVariant] from a buffer
     pub fn from_buf(buf: &[u8]) -> Result<Self> {
         let msg_len = buf.len();
@@ -351,6 +496,16 @@ impl ReplyVariant {
                 msg.from_buf(buf)?;
                 Ok(Self::OmnibusReply(msg))
             }
+            MessageType::OmnibusBookmark => {
+                let mut msg = OmnibusBookmarkReply::new();
+                msg.from_buf(buf)?;
+                Ok(Self::OmnibusBookmarkReply(msg))
+            }
+            MessageType::Calibrate => {
+                let mut msg = CalibrateReply::new();
+                msg.from_buf(buf)?;
+                Ok(Self::CalibrateReply(msg))
+            }
             MessageType::FirmwareDownload => match msg_len {
                 len::BAUD_CHANGE_REPLY => {
                     let mut msg = BaudRateChangeReply::new();
@@ -380,6 +535,11 @@ impl ReplyVariant {
                 let raw_sub_type = buf[index::EXT_SUBTYPE];
                 let sub_type = ExtendedCommand::from(raw_sub_type);
                 match sub_type {
+                    ExtendedCommand::ExtendedBarcodeReply => {
+                        let mut msg = ExtendedBarcodeReply::new();
+                        msg.from_buf(buf)?;
+                        Ok(Self::ExtendedBarcodeReply(msg))
+                    }
                     ExtendedCommand::ClearAuditDataRequest => {
                         let cad_reply_diff = buf[10];
                         if cad_reply_diff == 0x00 || cad_reply_diff == 0x01 {
@@ -437,6 +597,22 @@ impl ReplyVariant {
                             }
                         }
                     }
+                    // subtype 0x04 is shared by set escrow timeout, and extended coupon replies
+                    ExtendedCommand::SetEscrowTimeout => match msg_len {
+                        len::SET_ESCROW_TIMEOUT_REPLY => {
+                            let mut msg = SetEscrowTimeoutReply::new();
+                            msg.from_buf(buf)?;
+                            Ok(Self::SetEscrowTimeoutReply(msg))
+                        }
+                        len::EXTENDED_COUPON_REPLY => {
+                            let mut msg = ExtendedCouponReply::new();
+                            msg.from_buf(buf)?;
+                            Ok(Self::ExtendedCouponReply(msg))
+                        }
+                        _ => Err(Error::failure(format!(
+                            "unsupported SetEscrowTimeout / ExtendedCoupon reply message length: {msg_len}"
+                        ))),
+                    },
                     ExtendedCommand::AdvancedBookmark => {
                         let mut msg = AdvancedBookmarkModeReply::new();
                         msg.from_buf(buf)?;
@@ -460,6 +636,8 @@ impl fmt::Display for ReplyVariant {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Self::AdvancedBookmarkModeReply(msg) => write!(f, "AdvancedBookmarkModeReply({msg})"),
+            Self::ExtendedBarcodeReply(msg) => write!(f, "ExtendedBarcodeReply({msg})"),
+            Self::ExtendedCouponReply(msg) => write!(f, "ExtendedCouponReply({msg})"),
             Self::ClearAuditDataRequestAck(msg) => write!(f, "ClearAuditDataRequestAck({msg})"),
             Self::ClearAuditDataRequestResults(msg) => {
                 write!(f, "ClearAuditDataRequestResults({msg})")
@@ -471,9 +649,14 @@ impl fmt::Display for ReplyVariant {
             Self::NoteRetrievedReply(msg) => write!(f, "NoteRetrievedReply({msg})"),
             Self::NoteRetrievedEvent(msg) => write!(f, "NoteRetrievedEvent({msg})"),
             Self::OmnibusReply(msg) => write!(f, "OmnibusReply({msg})"),
+            Self::OmnibusBookmarkReply(msg) => write!(f, "OmnibusBookmarkReply({msg})"),
+            Self::CalibrateReply(msg) => write!(f, "CalibrateReply({msg})"),
             Self::QueryValueTableReply(msg) => write!(f, "QueryValueTableReply({msg})"),
             Self::SetEscrowTimeoutReply(msg) => write!(f, "SetEscrowTimeoutReply({msg})"),
             Self::QuerySoftwareCrcReply(msg) => write!(f, "QuerySoftwareCrcReply({msg})"),
+            Self::QueryCashBoxTotalReply(msg) => write!(f, "QueryCashBoxTotalReply({msg})"),
+            Self::QueryDeviceResetsReply(msg) => write!(f, "QueryDeviceResetsReply({msg})"),
+            Self::ClearCashBoxTotalReply(msg) => write!(f, "ClearCashBoxTotalReply({msg})"),
             Self::QueryBootPartNumberReply(msg) => write!(f, "QueryBootPartNumberReply({msg})"),
             Self::QueryApplicationPartNumberReply(msg) => {
                 write!(f, "QueryApplicationPartNumberReply({msg})")
@@ -487,6 +670,17 @@ impl fmt::Display for ReplyVariant {
             }
             Self::QueryApplicationIdReply(msg) => write!(f, "QueryApplicationIdReply({msg})"),
             Self::QueryVariantIdReply(msg) => write!(f, "QueryVariantIdReply({msg})"),
+            Self::QueryAcceptorTypeReply(msg) => write!(f, "QueryAcceptorTypeReply({msg})"),
+            Self::QueryAcceptorSerialNumberReply(msg) => {
+                write!(f, "QueryAcceptorSerialNumberReply({msg})")
+            }
+            Self::QueryAuditLifeTimeTotalsReply(msg) => {
+                write!(f, "QueryAuditLifeTimeTotalsReply({msg})")
+            }
+            Self::QueryAuditQpMeasuresReply(msg) => write!(f, "QueryAuditQpMeasuresReply({msg})"),
+            Self::QueryAuditPerformanceMeasuresReply(msg) => {
+                write!(f, "QueryAuditPerformanceMeasuresReply({msg})")
+            }
             Self::BaudRateChangeReply(msg) => write!(f, "BaudRateChangeReply({msg})"),
             Self::FlashDownloadReply7bit(msg) => write!(f, "FlashDownloadReply7bit({msg})"),
             Self::FlashDownloadReply8bit(msg) => write!(f, "FlashDownloadReply8bit({msg})"),
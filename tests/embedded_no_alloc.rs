@@ -0,0 +1,175 @@
+//! Checks the [EmbeddedTransport] exchanges messages without heap allocation.
+//!
+//! Runs as its own test binary, so the counting allocator only sees this crate, and the test
+//! harness.
+
+#![cfg(feature = "embedded-io")]
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+};
+
+use ebds::{
+    CommandVariant, EmbeddedTransport, ErrorCode, OmnibusCommand, ReplyTimer, ReplyVariant,
+};
+use embedded_io::{ErrorKind, ErrorType, Read, ReadReady, Write};
+
+// Counts the allocations made by the current thread.
+struct CountingAlloc;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOC: CountingAlloc = CountingAlloc;
+
+fn allocations() -> usize {
+    ALLOCATIONS.with(Cell::get)
+}
+
+#[rustfmt::skip]
+const OMNIBUS_REPLY: [u8; 11] = [
+    // STX | LEN | Message type
+    0x02, 0x0b, 0x20,
+    // Data
+    0b0000_0001, 0b0001_0000, 0b0000_0000, 0b0000_0000, 0b0000_0000, 0b0000_0000,
+    // ETX | Checksum
+    0x03, 0x3a,
+];
+
+struct MockPort {
+    rx: [u8; 32],
+    rx_len: usize,
+    rx_pos: usize,
+    read_error: Option<ErrorKind>,
+}
+
+impl MockPort {
+    fn new(rx: &[u8]) -> Self {
+        let mut port = Self {
+            rx: [0u8; 32],
+            rx_len: rx.len(),
+            rx_pos: 0,
+            read_error: None,
+        };
+        port.rx[..rx.len()].copy_from_slice(rx);
+        port
+    }
+
+    fn failing(kind: ErrorKind) -> Self {
+        Self {
+            read_error: Some(kind),
+            ..Self::new(&[0xff])
+        }
+    }
+}
+
+impl ErrorType for MockPort {
+    type Error = ErrorKind;
+}
+
+impl Read for MockPort {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        if let Some(kind) = self.read_error {
+            return Err(kind);
+        }
+
+        // simulate fragmented serial reads
+        let n = buf.len().min(self.rx_len - self.rx_pos).min(4);
+        buf[..n].copy_from_slice(&self.rx[self.rx_pos..self.rx_pos + n]);
+        self.rx_pos += n;
+        Ok(n)
+    }
+}
+
+impl ReadReady for MockPort {
+    fn read_ready(&mut self) -> Result<bool, Self::Error> {
+        Ok(self.rx_pos < self.rx_len)
+    }
+}
+
+impl Write for MockPort {
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+// Advances one millisecond per wait.
+#[derive(Default)]
+struct MockTimer {
+    elapsed_ms: u32,
+}
+
+impl ReplyTimer for MockTimer {
+    fn start(&mut self, _timeout_ms: u32) {
+        self.elapsed_ms = 0;
+    }
+
+    fn elapsed_ms(&mut self) -> u32 {
+        self.elapsed_ms
+    }
+
+    fn wait(&mut self) {
+        self.elapsed_ms += 1;
+    }
+}
+
+#[test]
+#[rustfmt::skip]
+fn test_embedded_transport_does_not_allocate() {
+    let rx = [
+        // Noise
+        0xff, 0x00,
+        // STX | LEN | Message type (invalid checksum)
+        0x02, 0x0b, 0x20,
+        0b0000_0001, 0b0001_0000, 0b0000_0000, 0b0000_0000, 0b0000_0000, 0b0000_0000,
+        0x03, 0x00,
+    ];
+    let mut rx_buf = [0u8; 32];
+    rx_buf[..rx.len()].copy_from_slice(rx.as_ref());
+    rx_buf[rx.len()..rx.len() + OMNIBUS_REPLY.len()].copy_from_slice(OMNIBUS_REPLY.as_ref());
+    let rx = &rx_buf[..rx.len() + OMNIBUS_REPLY.len()];
+
+    let mut command = CommandVariant::OmnibusCommand(OmnibusCommand::new());
+
+    // reply framed from line noise, and an invalid frame
+    let mut transport = EmbeddedTransport::new(MockPort::new(rx), MockTimer::default());
+    let start = allocations();
+    let reply = transport.send(&mut command);
+    assert_eq!(allocations(), start);
+    assert!(matches!(reply, Ok(ReplyVariant::OmnibusReply(_))));
+
+    // retries exhausted
+    let mut transport = EmbeddedTransport::new(MockPort::new(&[]), MockTimer::default())
+        .with_reply_timeout_ms(5)
+        .with_max_retries(1);
+    let start = allocations();
+    let err = transport.send(&mut command).unwrap_err();
+    assert_eq!(allocations(), start);
+    assert_eq!(err.code(), ErrorCode::Timeout);
+
+    // port failure
+    let mut transport =
+        EmbeddedTransport::new(MockPort::failing(ErrorKind::Other), MockTimer::default());
+    let start = allocations();
+    let err = transport.send(&mut command).unwrap_err();
+    assert_eq!(allocations(), start);
+    assert_eq!(err.code(), ErrorCode::SerialPort);
+}